@@ -24,9 +24,9 @@ use parami_node_runtime::constants::currency::*;
 use parami_node_runtime::Block;
 use parami_node_runtime::{
     AdsConfig, AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig,
-    CouncilConfig, DemocracyConfig, DidConfig, ElectionsPhragmenConfig, GrandpaConfig,
-    ImOnlineConfig, IndicesConfig, SessionConfig, SessionKeys, StakerStatus, StakingConfig,
-    SudoConfig, SystemConfig, TechnicalCommitteeConfig, WASM_BINARY,
+    CouncilConfig, DemocracyConfig, DidConfig, ElectionsPhragmenConfig, GenesisDid,
+    GrandpaConfig, ImOnlineConfig, IndicesConfig, SessionConfig, SessionKeys, StakerStatus,
+    StakingConfig, SudoConfig, SystemConfig, TechnicalCommitteeConfig, WASM_BINARY,
 };
 use sc_chain_spec::ChainSpecExtension;
 use sc_service::ChainType;
@@ -42,7 +42,7 @@ use sp_runtime::{
 use std::fs::File;
 use std::io::Read;
 
-pub use parami_node_primitives::{AccountId, Balance, Signature};
+pub use parami_node_primitives::{AccountId, Balance, Hash, Signature};
 pub use parami_node_runtime::GenesisConfig;
 
 type AccountPublic = <Signature as Verify>::Signer;
@@ -59,6 +59,24 @@ struct Allocation {
     balances: Vec<(String, String)>,
 }
 
+/// A DID exported from the legacy chain, hashes and accounts in hex.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidRecord {
+    pubkey: String,
+    account: String,
+    did_type: String,
+    /// User key of the superior, listed before this DID.
+    superior: Option<String>,
+    social_hash: Option<String>,
+    locked: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DidImport {
+    dids: Vec<DidRecord>,
+}
+
 /// Node `ChainSpec` extensions.
 ///
 /// Additional parameters for some Substrate core modules,
@@ -152,6 +170,40 @@ fn get_initial_allocation() -> Result<(Vec<(AccountId, Balance)>, Balance), Stri
     Ok((balances, total))
 }
 
+// Carry the DIDs of the legacy chain over, superiors first. Locked funds are reserved from
+// the DID's account, which needs the balance from the initial allocation.
+fn get_initial_dids() -> Result<Vec<GenesisDid<AccountId, Hash, Balance>>, String> {
+    let mut file = File::open("initial_dids.json").expect("Unable to open");
+    let mut data = String::new();
+    file.read_to_string(&mut data).unwrap();
+
+    let json: DidImport = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let bytes = |value: &str| -> Result<[u8; 32], String> {
+        <[u8; 32]>::from_hex(value.trim_start_matches("0x"))
+            .map_err(|e| format!("{}: {}", value, e))
+    };
+
+    json.dids
+        .into_iter()
+        .map(|e| {
+            Ok(GenesisDid {
+                pubkey: e.pubkey.into_bytes(),
+                address: bytes(e.account.as_str())?.into(),
+                did_type: e.did_type.into_bytes(),
+                superior: e
+                    .superior
+                    .as_deref()
+                    .map(bytes)
+                    .transpose()?
+                    .map(Hash::from)
+                    .unwrap_or_default(),
+                social_account: e.social_hash.as_deref().map(bytes).transpose()?.map(Hash::from),
+                locked: e.locked.parse::<Balance>().map_err(|err| err.to_string())?,
+            })
+        })
+        .collect()
+}
+
 /// Helper function to create GenesisConfig for testing.
 ///
 /// Configuration:
@@ -285,6 +337,9 @@ pub fn testnet_genesis(
             min_deposit: 10 * DOLLARS,
             base_quota: 250,
             fee_to_previous: 25 * DOLLARS,
+            harsh_salt: b"parami did".to_vec(),
+            harsh_length: 6,
+            dids: vec![],
         }),
         ads: Some(AdsConfig {
             contract: get_account_id_from_seed::<sr25519::Public>("Alice").into(),
@@ -361,6 +416,7 @@ fn parami_genesis(
     endowed_accounts: Vec<AccountId>,
 ) -> GenesisConfig {
     let (initial_allocation, initial_total) = get_initial_allocation().unwrap();
+    let initial_dids = get_initial_dids().unwrap();
 
     const stash_amount: Balance = 10_000 * DOLLARS;
     let total_stash: Balance = 10_000 * initial_authorities.len() as u128 * DOLLARS;
@@ -464,6 +520,9 @@ fn parami_genesis(
             min_deposit: 10 * DOLLARS,
             base_quota: 250,
             fee_to_previous: 25 * DOLLARS,
+            harsh_salt: b"parami did".to_vec(),
+            harsh_length: 6,
+            dids: initial_dids,
         }),
         ads: Some(AdsConfig {
             contract: hex!["16d3ed9daeb8e8126a33843cbf1e1c09eb598413349fd4474d4b48ded3e1340b"]
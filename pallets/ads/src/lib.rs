@@ -3,6 +3,7 @@ mod array_list;
 mod tests;
 use array_list::ArrayList;
 use codec::{Decode, Encode};
+use did::{Capability, TransferKind};
 use frame_support::{debug, decl_error, decl_event, decl_module, decl_storage, ensure};
 use frame_system::ensure_signed;
 use sp_runtime::{
@@ -114,11 +115,11 @@ decl_module! {
 
             ensure!(total_amount >= Self::min_deposit(), Error::<T>::MineDeposit);
             let (from_key, did) = <did::Module<T>>::identity(sender).ok_or(<did::Error<T>>::DidNotExists)?;
-            ensure!(Self::is_sub(&did[..2] , "4".as_bytes()),Error::<T>::NotADAccount);
+            ensure!(<did::Module<T>>::has_capability(&did, Capability::PublishAds), Error::<T>::NotADAccount);
             let create_time = <pallet_timestamp::Module<T>>::get();
 
             let (contract, _) = <did::Module<T>>::identity(Self::contract()).ok_or(Error::<T>::ContractDidNotExists)?;
-            <did::Module<T>>::transfer_by_did(from_key, contract, total_amount, "开户广告费".as_bytes().to_vec())?;
+            <did::Module<T>>::transfer_by_did(from_key, contract, total_amount, TransferKind::Deposit, "开户广告费".as_bytes().to_vec())?;
 
             let ads_metadata = AdsMetadata {
                 advertiser: name,
@@ -162,7 +163,7 @@ decl_module! {
             ensure!(<AdsRecords<T>>::contains_key(adid), Error::<T>::ADNotExists);
             let (contract_key, _) = <did::Module<T>>::identity(Self::contract()).ok_or(Error::<T>::ContractDidNotExists)?;
             // update ads records
-            <did::Module<T>>::transfer_by_did(user_key, contract_key, value, memo)?;
+            <did::Module<T>>::transfer_by_did(user_key, contract_key, value, TransferKind::Deposit, memo)?;
             let mut ads_metadata = Self::ads_records(adid);
             ads_metadata.total_amount = ads_metadata.total_amount.checked_add(&value).ok_or(Error::<T>::Overflow)?;
             <AdsRecords<T>>::insert(adid, ads_metadata);
@@ -182,7 +183,7 @@ decl_module! {
             let total_amount = ads_metadata.total_amount.checked_sub(&value).ok_or(Error::<T>::Overflow)?;
             ensure!(ads_metadata.spend_amount <= total_amount , Error::<T>::NotEnoughBalance);
             let (contract_key, _) = <did::Module<T>>::identity(Self::contract()).ok_or(Error::<T>::ContractDidNotExists)?;
-            <did::Module<T>>::transfer_by_did(contract_key, from_key, value, memo)?;
+            <did::Module<T>>::transfer_by_did(contract_key, from_key, value, TransferKind::Withdrawal, memo)?;
             // update ads metadata
             ads_metadata.total_amount = total_amount;
              <AdsRecords<T>>::insert(adid, ads_metadata);
@@ -208,7 +209,7 @@ decl_module! {
             let (contract_key, _) = <did::Module<T>>::identity(Self::contract()).ok_or(Error::<T>::ContractDidNotExists)?;
             ensure!(<did::Metadata<T>>::contains_key(user),<did::Error<T>>::DidNotExists);
             let (from_key, _) = <did::Module<T>>::identity(sender).ok_or(<did::Error<T>>::DidNotExists)?;
-            <did::Module<T>>::transfer_by_did(contract_key, user, value, "ads看广告收益".as_bytes().to_vec())?;
+            <did::Module<T>>::transfer_by_did(contract_key, user, value, TransferKind::AdReward, "ads看广告收益".as_bytes().to_vec())?;
             // update ads metadata
             ads_metadata.spend_amount = spend;
             <AdsRecords<T>>::insert(adid, ads_metadata);
@@ -305,17 +306,4 @@ impl<T: Config> Module<T> {
 
         Ok(())
     }
-
-    fn is_sub(mut haystack: &[u8], needle: &[u8]) -> bool {
-        if needle.len() == 0 {
-            return true;
-        }
-        while !haystack.is_empty() {
-            if haystack.starts_with(needle) {
-                return true;
-            }
-            haystack = &haystack[1..];
-        }
-        false
-    }
 }
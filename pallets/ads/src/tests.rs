@@ -2,35 +2,50 @@
 
 use super::*;
 
-use frame_support::{assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{assert_noop, assert_ok, construct_runtime, parameter_types};
 use sp_core::H256;
 // The testing primitives are very useful for avoiding having to work with signatures
 // or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
 };
 
-impl_outer_origin! {
-  pub enum Origin for Test {}
+type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, u32, ()>;
+
+mod ads {
+    pub use super::super::*;
 }
 
 // For testing the module, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of modules we want to use.
-#[derive(Clone, Eq, PartialEq)]
-pub struct Test;
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage},
+        DidModule: did::{Module, Call, Storage, Event<T>},
+        AdsModule: ads::{Module, Call, Storage, Event<T>},
+    }
+);
 
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
-    pub const MaximumBlockWeight: Weight = 1024;
-    pub const MaximumBlockLength: u32 = 2 * 1024;
-    pub const AvailableBlockRatio: Perbill = Perbill::one();
+    pub const SS58Prefix: u8 = 42;
 }
-impl frame_system::Trait for Test {
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
     type Origin = Origin;
-    type Call = ();
+    type Call = Call;
     type Index = u64;
     type BlockNumber = u64;
     type Hash = H256;
@@ -38,40 +53,40 @@ impl frame_system::Trait for Test {
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
-    type MaximumBlockWeight = MaximumBlockWeight;
     type DbWeight = ();
-    type BlockExecutionWeight = ();
-    type ExtrinsicBaseWeight = ();
-    type MaximumBlockLength = MaximumBlockLength;
-    type AvailableBlockRatio = AvailableBlockRatio;
     type Version = ();
-    type ModuleToIndex = ();
+    type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
 }
 
 parameter_types! {
     pub const ExistentialDeposit: u64 = 1;
 }
-impl pallet_balances::Trait for Test {
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
     type Balance = u64;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = frame_system::Module<Test>;
+    type WeightInfo = ();
 }
 
 parameter_types! {
   pub const MinimumPeriod: u64 = 1;
 }
 
-impl pallet_timestamp::Trait for Test {
+impl pallet_timestamp::Config for Test {
     type Moment = u64;
     type OnTimestampSet = ();
     type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
 }
 
 parameter_types! {
@@ -79,20 +94,64 @@ parameter_types! {
   pub const MinLength: usize = 3;
   pub const MaxLength: usize = 16;
   pub const One: u64 = 1;
+  pub const MaxKeys: u32 = 3;
+  pub const MaxKeyHistory: u32 = 4;
+  pub const ServiceDeposit: u64 = 10;
+  pub const MaxServices: u32 = 2;
+  pub const MaxServiceTypeLength: u32 = 16;
+  pub const MaxServiceUrlLength: u32 = 32;
+  pub const MaxChains: u32 = 3;
+  pub const MaxAddressesPerChain: u32 = 2;
+  pub const DisputeDeposit: u64 = 100;
+  pub const MaxGuardians: u32 = 3;
+  pub const RecoveryDelay: u64 = 10;
+  pub const MaxLockPositions: u32 = 3;
+  pub const UnbondingPeriod: u64 = 10;
+  pub const MaxUnbondingChunks: u32 = 2;
+  pub const MaxUnlockHistory: u32 = 3;
+  pub const MaxRewardLevels: u32 = 3;
+  pub const MaxBatchTransfers: u32 = 3;
+  pub const FeelessEra: u64 = 10;
+  pub const FeelessLockUnit: u64 = 500;
+  pub const GroupDeposit: u64 = 10;
+  pub const MaxGroupMembers: u32 = 3;
+  pub const MaxGroupsPerDid: u32 = 2;
 }
 
-impl did::Trait for Test {
-    type Event = ();
+impl did::Config for Test {
+    type Event = Event;
+    type MaxKeys = MaxKeys;
+    type MaxKeyHistory = MaxKeyHistory;
+    type ServiceDeposit = ServiceDeposit;
+    type MaxServices = MaxServices;
+    type MaxServiceTypeLength = MaxServiceTypeLength;
+    type MaxServiceUrlLength = MaxServiceUrlLength;
+    type MaxChains = MaxChains;
+    type MaxAddressesPerChain = MaxAddressesPerChain;
+    type DisputeDeposit = DisputeDeposit;
+    type MaxGuardians = MaxGuardians;
+    type RecoveryDelay = RecoveryDelay;
+    type MaxLockPositions = MaxLockPositions;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxUnbondingChunks = MaxUnbondingChunks;
+    type MaxUnlockHistory = MaxUnlockHistory;
+    type RewardOrigin = frame_system::EnsureRoot<u64>;
+    type DidTypeOrigin = frame_system::EnsureRoot<u64>;
+    type MaxRewardLevels = MaxRewardLevels;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type FeelessCalls = ();
+    type FeelessEra = FeelessEra;
+    type FeelessLockUnit = FeelessLockUnit;
+    type GroupDeposit = GroupDeposit;
+    type MaxGroupMembers = MaxGroupMembers;
+    type MaxGroupsPerDid = MaxGroupsPerDid;
+    type WeightInfo = ();
 }
 
-impl Trait for Test {
-    type Event = ();
+impl Config for Test {
+    type Event = Event;
 }
 
-type AdsModule = Module<Test>;
-type Balances = pallet_balances::Module<Test>;
-type DidModule = did::Module<Test>;
-
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 fn new_test_ext() -> sp_io::TestExternalities {
@@ -111,11 +170,14 @@ fn new_test_ext() -> sp_io::TestExternalities {
         min_deposit: 50,
         base_quota: 250,
         fee_to_previous: 25,
+        harsh_salt: b"parami did".to_vec(),
+        harsh_length: 6,
+        dids: vec![],
     }
     .assimilate_storage(&mut t)
     .unwrap();
 
-    GenesisConfig::<Test> {
+    ads::GenesisConfig::<Test> {
         contract: 2u64,
         min_deposit: 500,
     }
@@ -133,7 +195,7 @@ fn prepare_dids_for_test() {
         1u64,
         "1".as_bytes().to_vec(),
         H256::zero(),
-        Some("f".as_bytes().to_vec()),
+        None,
         None
     ));
 
@@ -143,9 +205,9 @@ fn prepare_dids_for_test() {
         b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d".to_vec(),
         2u64,
         "1".as_bytes().to_vec(),
-        H256::zero(),
-        Some("s".as_bytes().to_vec()),
-        Some("f".as_bytes().to_vec())
+        DidModule::identity(&1).unwrap().0,
+        None,
+        None
     ));
 
     // lock funds
@@ -156,10 +218,10 @@ fn prepare_dids_for_test() {
         Origin::signed(1),
         b"0x5e9c79234b5e55348fc60f38b28c2cc60d8bb4bd2862eae2179a05ec39e62658".to_vec(),
         3u64,
-        "1".as_bytes().to_vec(),
-        H256::zero(),
-        Some("n".as_bytes().to_vec()),
-        Some("s".as_bytes().to_vec())
+        "4".as_bytes().to_vec(),
+        DidModule::identity(&2).unwrap().0,
+        None,
+        None
     ));
 }
 
@@ -0,0 +1,24 @@
+//! Runtime API definition for the credentials pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use credentials::{CredentialInfo, CredentialStatus};
+
+sp_api::decl_runtime_apis! {
+    pub trait CredentialsApi<Hash, Moment> where
+        Hash: Codec,
+        Moment: Codec,
+    {
+        /// The current status of an anchored credential.
+        fn status(credential: Hash) -> CredentialStatus;
+
+        /// Every credential anchored about a subject.
+        fn credentials_of(subject: Hash) -> Vec<CredentialInfo<Hash, Moment>>;
+
+        /// One revocation bitmap of an issuer.
+        fn status_list(issuer: Hash, list: u32) -> Vec<u8>;
+    }
+}
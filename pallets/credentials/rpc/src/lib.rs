@@ -0,0 +1,137 @@
+//! RPC interface for the credentials pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use credentials_rpc_runtime_api::CredentialsApi as CredentialsRuntimeApi;
+use credentials_rpc_runtime_api::{CredentialInfo, CredentialStatus};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// A credential anchored about a subject.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential<Hash, Moment> {
+    pub hash: Hash,
+    pub issuer: Hash,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub issued: Moment,
+    pub expires: Option<Moment>,
+    pub status: CredentialStatus,
+}
+
+impl<Hash, Moment> From<CredentialInfo<Hash, Moment>> for Credential<Hash, Moment> {
+    fn from(info: CredentialInfo<Hash, Moment>) -> Self {
+        Credential {
+            hash: info.hash,
+            issuer: info.issuer,
+            credential_type: String::from_utf8_lossy(&info.credential_type).into_owned(),
+            issued: info.issued,
+            expires: info.expires,
+            status: info.status,
+        }
+    }
+}
+
+#[rpc]
+pub trait CredentialsApi<BlockHash, Hash, Moment> {
+    /// The status of a credential hash, `unknown` if it was never anchored.
+    #[rpc(name = "credentials_status")]
+    fn status(&self, credential: Hash, at: Option<BlockHash>) -> Result<CredentialStatus>;
+
+    /// Every credential anchored about the subject's user key.
+    #[rpc(name = "credentials_ofSubject")]
+    fn credentials_of(
+        &self,
+        subject: Hash,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Credential<Hash, Moment>>>;
+
+    /// The raw revocation bitmap `list` of an issuer, bit `i` of byte `j` is slot `8 * j + i`.
+    #[rpc(name = "credentials_statusList")]
+    fn status_list(&self, issuer: Hash, list: u32, at: Option<BlockHash>) -> Result<Vec<u8>>;
+}
+
+/// An implementation of credentials specific RPC methods.
+pub struct Credentials<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Credentials<C, B> {
+    /// Create new `Credentials` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Credentials {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, Hash, Moment> CredentialsApi<<Block as BlockT>::Hash, Hash, Moment>
+    for Credentials<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: CredentialsRuntimeApi<Block, Hash, Moment>,
+    Hash: Codec,
+    Moment: Codec,
+{
+    fn status(
+        &self,
+        credential: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<CredentialStatus> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        api.status(&at, credential).map_err(runtime_error)
+    }
+
+    fn credentials_of(
+        &self,
+        subject: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Credential<Hash, Moment>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let anchored = api.credentials_of(&at, subject).map_err(runtime_error)?;
+
+        Ok(anchored.into_iter().map(Into::into).collect())
+    }
+
+    fn status_list(
+        &self,
+        issuer: Hash,
+        list: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<u8>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        api.status_list(&at, issuer, list).map_err(runtime_error)
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(err: E) -> Error {
+    Error {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime trapped".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
@@ -0,0 +1,109 @@
+//! Benchmarks for the credentials pallet, every call is measured at its worst case.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Hash, Saturating};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+fn signed<T: Config>(who: &T::AccountId) -> T::Origin {
+    RawOrigin::Signed(who.clone()).into()
+}
+
+/// A funded account with a DID of its own.
+fn new_did<T: Config>(name: &'static str, index: u32) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let who: T::AccountId = account(name, index, SEED);
+    let balance = T::CredentialDeposit::get()
+        .max(100u32.into())
+        .saturating_mul(1_000_000u32.into());
+    <pallet_balances::Module<T> as Currency<_>>::make_free_balance_be(&who, balance);
+
+    // the genesis account places it at the top of the tree
+    <did::Module<T>>::create(
+        signed::<T>(&<did::Module<T>>::genesis_account()),
+        who.encode(),
+        who.clone(),
+        b"1".to_vec(),
+        Default::default(),
+        None,
+        None,
+    )?;
+    let (user_key, _) = <did::Module<T>>::identity(&who).ok_or("did not created")?;
+    Ok((who, user_key))
+}
+
+fn credential<T: Config>(index: u32) -> T::Hash {
+    T::Hashing::hash_of(&index)
+}
+
+/// Anchor `count` credentials of the issuer about the subject, numbered from zero.
+fn fill_credentials<T: Config>(issuer: &T::AccountId, subject: T::Hash, count: u32) -> Result<(), &'static str> {
+    for i in 0..count {
+        Module::<T>::anchor(signed::<T>(issuer), credential::<T>(i), subject, b"kyc".to_vec(), None)?;
+    }
+    Ok(())
+}
+
+benchmarks! {
+    // the issuer's list about the subject is one short of full
+    anchor {
+        let (issuer, _) = new_did::<T>("issuer", 0)?;
+        let (_, subject) = new_did::<T>("subject", 0)?;
+        fill_credentials::<T>(&issuer, subject, T::MaxCredentialsPerIssuer::get().saturating_sub(1))?;
+
+        let hash = credential::<T>(u32::max_value());
+        let credential_type = vec![b'a'; T::MaxCredentialTypeLength::get() as usize];
+    }: _(RawOrigin::Signed(issuer), hash, subject, credential_type, None)
+    verify {
+        assert_eq!(Module::<T>::status(hash), CredentialStatus::Active);
+    }
+
+    revoke {
+        let (issuer, _) = new_did::<T>("issuer", 0)?;
+        let (_, subject) = new_did::<T>("subject", 0)?;
+        fill_credentials::<T>(&issuer, subject, 1)?;
+
+        let hash = credential::<T>(0);
+    }: _(RawOrigin::Signed(issuer), hash)
+    verify {
+        assert_eq!(Module::<T>::status(hash), CredentialStatus::Revoked);
+    }
+
+    // the issuer purges a revoked credential out of a full list, leaving a marker
+    purge {
+        let (issuer, issuer_key) = new_did::<T>("issuer", 0)?;
+        let (_, subject) = new_did::<T>("subject", 0)?;
+        fill_credentials::<T>(&issuer, subject, T::MaxCredentialsPerIssuer::get())?;
+
+        let hash = credential::<T>(T::MaxCredentialsPerIssuer::get().saturating_sub(1));
+        Module::<T>::revoke(signed::<T>(&issuer), hash)?;
+    }: _(RawOrigin::Signed(issuer), hash)
+    verify {
+        assert_eq!(Module::<T>::purged_revocation(hash), Some(issuer_key));
+        assert_eq!(Module::<T>::status(hash), CredentialStatus::Revoked);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_anchor::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_revoke::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_purge::<Test>());
+        });
+    }
+}
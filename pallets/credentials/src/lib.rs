@@ -0,0 +1,300 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Anchors of verifiable credentials issued by one DID about another.
+//!
+//! Only the hash of a credential is stored. Every credential takes the next slot of its
+//! issuer's status lists, a set bit marks it revoked. A revoked credential still reads as
+//! revoked once it is purged.
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure,
+    traits::{Get, ReservableCurrency},
+    IterableStorageDoubleMap,
+};
+use frame_system::ensure_signed;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+pub trait Config: did::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// The amount reserved for every anchored credential.
+    type CredentialDeposit: Get<Self::Balance>;
+    /// The number of credentials one status list tracks.
+    type StatusListLength: Get<u32>;
+    /// The maximum length of a credential type.
+    type MaxCredentialTypeLength: Get<u32>;
+    /// The maximum number of credentials one issuer anchors about one subject.
+    type MaxCredentialsPerIssuer: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Credential<AccountId, Hash, Balance, Moment> {
+    pub issuer: Hash,
+    pub subject: Hash,
+    pub credential_type: Vec<u8>,
+    pub issued: Moment,
+    pub expires: Option<Moment>,
+    pub status_index: u64,
+    depositor: AccountId,
+    deposit: Balance,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum CredentialStatus {
+    /// never anchored, or purged before it was revoked
+    Unknown,
+    Active,
+    Revoked,
+    Expired,
+}
+
+/// A credential anchored about a subject, as returned to RPC clients.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct CredentialInfo<Hash, Moment> {
+    pub hash: Hash,
+    pub issuer: Hash,
+    pub credential_type: Vec<u8>,
+    pub issued: Moment,
+    pub expires: Option<Moment>,
+    pub status: CredentialStatus,
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// subject did does not exist
+        SubjectNotExists,
+        /// credential has been anchored
+        CredentialExists,
+        /// credential does not exist
+        CredentialNotExists,
+        /// credential type is empty or too long
+        InvalidType,
+        /// expiry is in the past
+        InvalidExpiry,
+        /// you are not the issuer of the credential
+        NotIssuer,
+        /// credential has been revoked
+        AlreadyRevoked,
+        /// issuer has anchored too many credentials about the subject
+        TooManyCredentials,
+        /// only revoked or expired credentials can be purged by the issuer
+        NotPurgeable,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as CredentialsModule {
+        pub Credentials get(fn credentials): map hasher(blake2_128_concat) T::Hash => Option<Credential<T::AccountId, T::Hash, T::Balance, T::Moment>>;
+        /// Credentials an issuer anchored about a subject, bounded by `MaxCredentialsPerIssuer`.
+        pub SubjectCredentials get(fn subject_credentials): double_map hasher(twox_64_concat) T::Hash, hasher(twox_64_concat) T::Hash => Vec<T::Hash>;
+        /// Issuers of revoked credentials which have been purged.
+        pub PurgedRevocations get(fn purged_revocation): map hasher(blake2_128_concat) T::Hash => Option<T::Hash>;
+
+        /// The status list slot the next credential of an issuer takes.
+        pub NextStatusIndex get(fn next_status_index): map hasher(twox_64_concat) T::Hash => u64;
+        /// Revocation bitmaps of an issuer, bit `i` of list `l` is slot `l * StatusListLength + i`.
+        pub StatusLists get(fn status_list): double_map hasher(twox_64_concat) T::Hash, hasher(twox_64_concat) u32 => Vec<u8>;
+    }
+}
+
+decl_event! {
+  pub enum Event<T>
+  where
+    <T as frame_system::Config>::Hash,
+    {
+        Anchored(Hash, Hash, Hash),
+        Revoked(Hash, Hash),
+        Purged(Hash),
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Anchor the hash of a credential the sender's DID issued about `subject`.
+        #[weight = T::WeightInfo::anchor()]
+        pub fn anchor(origin, credential: T::Hash, subject: T::Hash, credential_type: Vec<u8>, expires: Option<T::Moment>) {
+            let sender = ensure_signed(origin)?;
+
+            let (issuer, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            ensure!(
+                <did::Metadata<T>>::contains_key(&subject) && !<did::Tombstones<T>>::contains_key(&subject),
+                Error::<T>::SubjectNotExists
+            );
+            ensure!(
+                !<Credentials<T>>::contains_key(&credential) && !<PurgedRevocations<T>>::contains_key(&credential),
+                Error::<T>::CredentialExists
+            );
+            ensure!(
+                !credential_type.is_empty() && credential_type.len() as u32 <= T::MaxCredentialTypeLength::get(),
+                Error::<T>::InvalidType
+            );
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            if let Some(expires) = expires {
+                ensure!(expires > now, Error::<T>::InvalidExpiry);
+            }
+
+            let mut anchored = Self::subject_credentials(&subject, &issuer);
+            ensure!((anchored.len() as u32) < T::MaxCredentialsPerIssuer::get(), Error::<T>::TooManyCredentials);
+
+            let deposit = T::CredentialDeposit::get();
+            <pallet_balances::Module<T>>::reserve(&sender, deposit)?;
+
+            let status_index = Self::next_status_index(&issuer);
+            <NextStatusIndex<T>>::insert(&issuer, status_index + 1);
+
+            <Credentials<T>>::insert(&credential, Credential {
+                issuer,
+                subject,
+                credential_type,
+                issued: now,
+                expires,
+                status_index,
+                depositor: sender,
+                deposit,
+            });
+            anchored.push(credential);
+            <SubjectCredentials<T>>::insert(&subject, &issuer, anchored);
+
+            Self::deposit_event(RawEvent::Anchored(issuer, subject, credential));
+        }
+
+        #[weight = T::WeightInfo::revoke()]
+        pub fn revoke(origin, credential: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            let (issuer, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let record = Self::credentials(&credential).ok_or(Error::<T>::CredentialNotExists)?;
+            ensure!(record.issuer == issuer, Error::<T>::NotIssuer);
+            ensure!(!Self::is_revoked(&issuer, record.status_index), Error::<T>::AlreadyRevoked);
+
+            let (list, byte, mask) = Self::status_position(record.status_index);
+            <StatusLists<T>>::mutate(&issuer, list, |bits| {
+                if bits.len() <= byte {
+                    bits.resize(byte + 1, 0);
+                }
+                bits[byte] |= mask;
+            });
+
+            Self::deposit_event(RawEvent::Revoked(issuer, credential));
+        }
+
+        /// Drop a credential and return its deposit. The issuer may drop revoked or expired
+        /// ones, the subject any credential about itself. Revoked ones stay revoked.
+        #[weight = T::WeightInfo::purge()]
+        pub fn purge(origin, credential: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let record = Self::credentials(&credential).ok_or(Error::<T>::CredentialNotExists)?;
+            if record.subject != user_key {
+                ensure!(record.issuer == user_key, Error::<T>::NotIssuer);
+                ensure!(Self::status(credential) != CredentialStatus::Active, Error::<T>::NotPurgeable);
+            }
+
+            if Self::is_revoked(&record.issuer, record.status_index) {
+                <PurgedRevocations<T>>::insert(&credential, record.issuer);
+            }
+            <Credentials<T>>::remove(&credential);
+
+            let mut anchored = Self::subject_credentials(&record.subject, &record.issuer);
+            anchored.retain(|c| *c != credential);
+            if anchored.is_empty() {
+                <SubjectCredentials<T>>::remove(&record.subject, &record.issuer);
+            } else {
+                <SubjectCredentials<T>>::insert(&record.subject, &record.issuer, anchored);
+            }
+            <pallet_balances::Module<T>>::unreserve(&record.depositor, record.deposit);
+
+            Self::deposit_event(RawEvent::Purged(credential));
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// The status list, byte and bit mask of a status slot.
+    fn status_position(status_index: u64) -> (u32, usize, u8) {
+        let length = T::StatusListLength::get().max(1) as u64;
+        let bit = status_index % length;
+        ((status_index / length) as u32, (bit / 8) as usize, 1 << (bit % 8))
+    }
+
+    fn is_revoked(issuer: &T::Hash, status_index: u64) -> bool {
+        let (list, byte, mask) = Self::status_position(status_index);
+        Self::status_list(issuer, list)
+            .get(byte)
+            .map_or(false, |bits| bits & mask != 0)
+    }
+
+    pub fn status(credential: T::Hash) -> CredentialStatus {
+        let record = match Self::credentials(&credential) {
+            Some(record) => record,
+            None if <PurgedRevocations<T>>::contains_key(&credential) => return CredentialStatus::Revoked,
+            None => return CredentialStatus::Unknown,
+        };
+
+        if Self::is_revoked(&record.issuer, record.status_index) {
+            return CredentialStatus::Revoked;
+        }
+        match record.expires {
+            Some(expires) if expires <= <pallet_timestamp::Module<T>>::get() => {
+                CredentialStatus::Expired
+            }
+            _ => CredentialStatus::Active,
+        }
+    }
+
+    /// Every credential anchored about a subject with its current status, none once the
+    /// subject's DID is deactivated.
+    pub fn credentials_of(subject: T::Hash) -> Vec<CredentialInfo<T::Hash, T::Moment>> {
+        if <did::Tombstones<T>>::contains_key(&subject) {
+            return Vec::new();
+        }
+
+        <SubjectCredentials<T>>::iter_prefix_values(&subject)
+            .flatten()
+            .filter_map(|hash| {
+                let record = Self::credentials(&hash)?;
+                Some(CredentialInfo {
+                    hash,
+                    issuer: record.issuer,
+                    credential_type: record.credential_type,
+                    issued: record.issued,
+                    expires: record.expires,
+                    status: Self::status(hash),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the subject holds an active credential of the type from the issuer.
+    pub fn has_active_credential(subject: T::Hash, issuer: T::Hash, credential_type: &[u8]) -> bool {
+        if <did::Tombstones<T>>::contains_key(&subject) {
+            return false;
+        }
+
+        Self::subject_credentials(&subject, &issuer).into_iter().any(|hash| {
+            Self::credentials(&hash).map_or(false, |record| {
+                record.credential_type == credential_type && Self::status(hash) == CredentialStatus::Active
+            })
+        })
+    }
+}
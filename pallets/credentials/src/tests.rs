@@ -0,0 +1,372 @@
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{assert_noop, assert_ok, construct_runtime, parameter_types};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, Hash, IdentityLookup},
+};
+
+type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, u32, ()>;
+
+mod credentials {
+    pub use super::super::*;
+}
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage},
+        DidModule: did::{Module, Call, Storage, Event<T>},
+        CredentialsModule: credentials::{Module, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+  pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = frame_system::Module<Test>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const MaxKeys: u32 = 3;
+  pub const MaxKeyHistory: u32 = 4;
+  pub const ServiceDeposit: u64 = 10;
+  pub const MaxServices: u32 = 2;
+  pub const MaxServiceTypeLength: u32 = 16;
+  pub const MaxServiceUrlLength: u32 = 32;
+  pub const MaxChains: u32 = 3;
+  pub const MaxAddressesPerChain: u32 = 2;
+  pub const DisputeDeposit: u64 = 100;
+  pub const MaxGuardians: u32 = 3;
+  pub const RecoveryDelay: u64 = 10;
+  pub const MaxLockPositions: u32 = 3;
+  pub const UnbondingPeriod: u64 = 10;
+  pub const MaxUnbondingChunks: u32 = 2;
+  pub const MaxUnlockHistory: u32 = 3;
+  pub const MaxRewardLevels: u32 = 3;
+  pub const MaxBatchTransfers: u32 = 3;
+  pub const FeelessEra: u64 = 10;
+  pub const FeelessLockUnit: u64 = 500;
+  pub const GroupDeposit: u64 = 10;
+  pub const MaxGroupMembers: u32 = 3;
+  pub const MaxGroupsPerDid: u32 = 2;
+}
+
+impl did::Config for Test {
+    type Event = Event;
+    type MaxKeys = MaxKeys;
+    type MaxKeyHistory = MaxKeyHistory;
+    type ServiceDeposit = ServiceDeposit;
+    type MaxServices = MaxServices;
+    type MaxServiceTypeLength = MaxServiceTypeLength;
+    type MaxServiceUrlLength = MaxServiceUrlLength;
+    type MaxChains = MaxChains;
+    type MaxAddressesPerChain = MaxAddressesPerChain;
+    type DisputeDeposit = DisputeDeposit;
+    type MaxGuardians = MaxGuardians;
+    type RecoveryDelay = RecoveryDelay;
+    type MaxLockPositions = MaxLockPositions;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxUnbondingChunks = MaxUnbondingChunks;
+    type MaxUnlockHistory = MaxUnlockHistory;
+    type RewardOrigin = frame_system::EnsureRoot<u64>;
+    type DidTypeOrigin = frame_system::EnsureRoot<u64>;
+    type MaxRewardLevels = MaxRewardLevels;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type FeelessCalls = ();
+    type FeelessEra = FeelessEra;
+    type FeelessLockUnit = FeelessLockUnit;
+    type GroupDeposit = GroupDeposit;
+    type MaxGroupMembers = MaxGroupMembers;
+    type MaxGroupsPerDid = MaxGroupsPerDid;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const CredentialDeposit: u64 = 10;
+  pub const StatusListLength: u32 = 16;
+  pub const MaxCredentialTypeLength: u32 = 16;
+  pub const MaxCredentialsPerIssuer: u32 = 2;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type CredentialDeposit = CredentialDeposit;
+    type StatusListLength = StatusListLength;
+    type MaxCredentialTypeLength = MaxCredentialTypeLength;
+    type MaxCredentialsPerIssuer = MaxCredentialsPerIssuer;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(1, 10000), (2, 10000), (3, 10000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    did::GenesisConfig::<Test> {
+        genesis_account: 1u64,
+        min_deposit: 10,
+        base_quota: 250,
+        fee_to_previous: 25,
+        harsh_salt: b"parami did".to_vec(),
+        harsh_length: 6,
+        dids: vec![],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    t.into()
+}
+
+// issuer 1, subjects 2 and 3
+fn prepare_dids_for_test() -> (H256, H256, H256) {
+    for account in 1..=3u64 {
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            [account as u8; 32].to_vec(),
+            account,
+            b"1".to_vec(),
+            H256::zero(),
+            None,
+            None
+        ));
+    }
+
+    (
+        DidModule::identity(&1).unwrap().0,
+        DidModule::identity(&2).unwrap().0,
+        DidModule::identity(&3).unwrap().0,
+    )
+}
+
+fn credential(n: u8) -> H256 {
+    BlakeTwo256::hash(&[n])
+}
+
+#[test]
+fn should_pass_anchor() {
+    new_test_ext().execute_with(|| {
+        let (issuer, subject, _) = prepare_dids_for_test();
+        Timestamp::set_timestamp(100);
+
+        assert_ok!(CredentialsModule::anchor(
+            Origin::signed(1),
+            credential(1),
+            subject,
+            b"kyc".to_vec(),
+            Some(200)
+        ));
+        assert_ok!(CredentialsModule::anchor(
+            Origin::signed(1),
+            credential(2),
+            subject,
+            b"advertiser".to_vec(),
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(&1), 20);
+        assert_eq!(CredentialsModule::next_status_index(&issuer), 2);
+
+        assert_eq!(CredentialsModule::status(credential(1)), CredentialStatus::Active);
+        assert_eq!(CredentialsModule::status(credential(3)), CredentialStatus::Unknown);
+        assert!(CredentialsModule::has_active_credential(subject, issuer, b"advertiser"));
+        assert!(!CredentialsModule::has_active_credential(subject, subject, b"advertiser"));
+
+        let anchored = CredentialsModule::credentials_of(subject);
+        assert_eq!(anchored.len(), 2);
+        assert_eq!(anchored[0].hash, credential(1));
+        assert_eq!(anchored[0].issuer, issuer);
+        assert_eq!(anchored[0].issued, 100);
+
+        Timestamp::set_timestamp(200);
+        assert_eq!(CredentialsModule::status(credential(1)), CredentialStatus::Expired);
+        assert_eq!(CredentialsModule::status(credential(2)), CredentialStatus::Active);
+    });
+}
+
+#[test]
+fn should_not_pass_anchor() {
+    new_test_ext().execute_with(|| {
+        let (_, subject, _) = prepare_dids_for_test();
+        Timestamp::set_timestamp(100);
+
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(4), credential(1), subject, b"kyc".to_vec(), None),
+            did::Error::<Test>::DidNotExists
+        );
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(1), H256::zero(), b"kyc".to_vec(), None),
+            Error::<Test>::SubjectNotExists
+        );
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(1), subject, Vec::new(), None),
+            Error::<Test>::InvalidType
+        );
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), Some(100)),
+            Error::<Test>::InvalidExpiry
+        );
+
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None));
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None),
+            Error::<Test>::CredentialExists
+        );
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(2), subject, b"kyc".to_vec(), None));
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(3), subject, b"kyc".to_vec(), None),
+            Error::<Test>::TooManyCredentials
+        );
+
+        // the limit is per issuer, others still anchor about the subject
+        assert_ok!(CredentialsModule::anchor(Origin::signed(3), credential(3), subject, b"kyc".to_vec(), None));
+        assert_eq!(CredentialsModule::credentials_of(subject).len(), 3);
+    });
+}
+
+#[test]
+fn should_pass_revoke() {
+    new_test_ext().execute_with(|| {
+        let (issuer, subject, other) = prepare_dids_for_test();
+
+        // the last slot of the first status list and the first of the second
+        <NextStatusIndex<Test>>::insert(&issuer, 15);
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None));
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(2), other, b"kyc".to_vec(), None));
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(3), other, b"kyc".to_vec(), None));
+
+        assert_ok!(CredentialsModule::revoke(Origin::signed(1), credential(1)));
+        assert_ok!(CredentialsModule::revoke(Origin::signed(1), credential(2)));
+        assert_eq!(CredentialsModule::status_list(&issuer, 0), vec![0, 0b1000_0000]);
+        assert_eq!(CredentialsModule::status_list(&issuer, 1), vec![0b0000_0001]);
+
+        assert_eq!(CredentialsModule::status(credential(1)), CredentialStatus::Revoked);
+        assert_eq!(CredentialsModule::status(credential(2)), CredentialStatus::Revoked);
+        assert_eq!(CredentialsModule::status(credential(3)), CredentialStatus::Active);
+
+        assert_noop!(
+            CredentialsModule::revoke(Origin::signed(1), credential(1)),
+            Error::<Test>::AlreadyRevoked
+        );
+        assert_noop!(
+            CredentialsModule::revoke(Origin::signed(2), credential(3)),
+            Error::<Test>::NotIssuer
+        );
+        assert_noop!(
+            CredentialsModule::revoke(Origin::signed(1), credential(4)),
+            Error::<Test>::CredentialNotExists
+        );
+    });
+}
+
+#[test]
+fn should_pass_purge() {
+    new_test_ext().execute_with(|| {
+        let (issuer, subject, _) = prepare_dids_for_test();
+
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None));
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(2), subject, b"kyc".to_vec(), None));
+
+        // the issuer only purges dead credentials, the subject any
+        assert_noop!(
+            CredentialsModule::purge(Origin::signed(1), credential(1)),
+            Error::<Test>::NotPurgeable
+        );
+        assert_noop!(
+            CredentialsModule::purge(Origin::signed(3), credential(1)),
+            Error::<Test>::NotIssuer
+        );
+        assert_ok!(CredentialsModule::revoke(Origin::signed(1), credential(1)));
+        assert_ok!(CredentialsModule::purge(Origin::signed(1), credential(1)));
+        assert_ok!(CredentialsModule::purge(Origin::signed(2), credential(2)));
+
+        // a purged revoked credential stays revoked and cannot be anchored again
+        assert_eq!(CredentialsModule::status(credential(1)), CredentialStatus::Revoked);
+        assert_eq!(CredentialsModule::status(credential(2)), CredentialStatus::Unknown);
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None),
+            Error::<Test>::CredentialExists
+        );
+        assert!(CredentialsModule::subject_credentials(&subject, &issuer).is_empty());
+        assert_eq!(Balances::reserved_balance(&1), 0);
+    });
+}
+
+#[test]
+fn should_not_list_credentials_of_deactivated_did() {
+    new_test_ext().execute_with(|| {
+        let (issuer, subject, _) = prepare_dids_for_test();
+
+        assert_ok!(CredentialsModule::anchor(Origin::signed(1), credential(1), subject, b"kyc".to_vec(), None));
+        assert_ok!(DidModule::deactivate(Origin::signed(2)));
+
+        assert!(CredentialsModule::credentials_of(subject).is_empty());
+        assert!(!CredentialsModule::has_active_credential(subject, issuer, b"kyc"));
+        assert_noop!(
+            CredentialsModule::anchor(Origin::signed(1), credential(2), subject, b"kyc".to_vec(), None),
+            Error::<Test>::SubjectNotExists
+        );
+    });
+}
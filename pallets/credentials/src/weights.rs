@@ -0,0 +1,65 @@
+//! Weights for the credentials pallet.
+//!
+//! The benchmarks in `benchmarking.rs` measure every call at its worst case, regenerate this file
+//! on reference hardware with:
+//!
+//! ```text
+//! parami benchmark --chain=dev --execution=wasm --wasm-execution=compiled --pallet=credentials
+//!     --extrinsic='*' --steps=50 --repeat=20 --heap-pages=4096 --output=pallets/credentials/src/weights.rs
+//! ```
+//!
+//! Not generated yet, the execution times are estimates. The storage accesses follow the
+//! worst case each benchmark sets up, e.g. `purge` of a revoked credential out of a full issuer
+//! list.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for the credentials pallet.
+pub trait WeightInfo {
+    fn anchor() -> Weight;
+    fn revoke() -> Weight;
+    fn purge() -> Weight;
+}
+
+/// Weights for the credentials pallet using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn anchor() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn revoke() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn purge() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn anchor() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn revoke() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn purge() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+}
@@ -0,0 +1,39 @@
+//! Runtime API definition for the did pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use did::{DidDocument, DidService, ShortIdRecord, VerificationMethod, SUBORDINATE_PAGE_SIZE};
+
+sp_api::decl_runtime_apis! {
+    pub trait DidApi<AccountId, Hash> where
+        AccountId: Codec,
+        Hash: Codec,
+    {
+        /// Resolve a `did:ad3:...` string into its DID document.
+        fn resolve(did: Vec<u8>) -> Option<DidDocument>;
+
+        /// Resolve the DID document of a user key.
+        fn resolve_by_key(user_key: Hash) -> Option<DidDocument>;
+
+        /// The `did:ad3:...` string of the DID a verified external address is linked to.
+        fn lookup_address(chain_id: Vec<u8>, address: Vec<u8>) -> Option<Vec<u8>>;
+
+        /// Number of direct subordinates of a DID.
+        fn child_count(user_key: Hash) -> u32;
+
+        /// One page of the direct subordinates of a DID.
+        fn children(user_key: Hash, page: u32) -> Vec<Hash>;
+
+        /// Subordinates up to `depth` levels down with their level, breadth first.
+        fn descendants(user_key: Hash, depth: u32, start: u32, limit: u32) -> Vec<(Hash, u32)>;
+
+        /// The superiors of a DID, nearest first.
+        fn ancestors(user_key: Hash) -> Vec<Hash>;
+
+        /// The sequence number, user key, DID and account a short ID stands for.
+        fn resolve_short_id(short_id: Vec<u8>) -> Option<ShortIdRecord<AccountId, Hash>>;
+    }
+}
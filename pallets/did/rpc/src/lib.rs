@@ -0,0 +1,336 @@
+//! RPC interface for the did pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use did_rpc_runtime_api::DidApi as DidRuntimeApi;
+use did_rpc_runtime_api::{
+    DidDocument, DidService, ShortIdRecord, VerificationMethod, SUBORDINATE_PAGE_SIZE,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+
+const DID_CONTEXT: &[&str] = &[
+    "https://www.w3.org/ns/did/v1",
+    "https://w3id.org/security/suites/ed25519-2020/v1",
+];
+
+/// A `did:ad3:...` string or the user key hash of a DID.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DidOrKey<Hash> {
+    Key(Hash),
+    Did(String),
+}
+
+/// A W3C DID Core verification method.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Method {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+}
+
+/// A W3C DID Core service endpoint.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub service_type: String,
+    pub service_endpoint: String,
+}
+
+/// A W3C DID Core document.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    pub controller: String,
+    pub verification_method: Vec<Method>,
+    pub authentication: Vec<String>,
+    pub service: Vec<Service>,
+    pub also_known_as: Vec<String>,
+}
+
+/// One page of the direct subordinates of a DID.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Children<Hash> {
+    pub total: u32,
+    pub page: u32,
+    pub page_size: u32,
+    pub children: Vec<Hash>,
+}
+
+/// A subordinate and how many levels below the queried DID it is.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descendant<Hash> {
+    pub user_key: Hash,
+    pub depth: u32,
+}
+
+/// The DID a short ID stands for and when it was created.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortId<AccountId, Hash> {
+    pub sequence: u64,
+    pub user_key: Hash,
+    pub did: String,
+    pub account: AccountId,
+}
+
+fn text(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl<AccountId, Hash> From<ShortIdRecord<AccountId, Hash>> for ShortId<AccountId, Hash> {
+    fn from(record: ShortIdRecord<AccountId, Hash>) -> Self {
+        ShortId {
+            sequence: record.sequence,
+            user_key: record.user_key,
+            did: text(record.did),
+            account: record.account,
+        }
+    }
+}
+
+impl From<VerificationMethod> for Method {
+    fn from(method: VerificationMethod) -> Self {
+        Method {
+            id: text(method.id),
+            method_type: text(method.method_type),
+            controller: text(method.controller),
+            public_key_multibase: text(method.public_key_multibase),
+        }
+    }
+}
+
+impl From<DidService> for Service {
+    fn from(service: DidService) -> Self {
+        Service {
+            id: text(service.id),
+            service_type: text(service.service_type),
+            service_endpoint: text(service.service_endpoint),
+        }
+    }
+}
+
+impl From<DidDocument> for Document {
+    fn from(document: DidDocument) -> Self {
+        Document {
+            context: DID_CONTEXT.iter().map(|c| c.to_string()).collect(),
+            id: text(document.id),
+            controller: text(document.controller),
+            verification_method: document
+                .verification_method
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            authentication: document.authentication.into_iter().map(text).collect(),
+            service: document.service.into_iter().map(Into::into).collect(),
+            also_known_as: document.also_known_as.into_iter().map(text).collect(),
+        }
+    }
+}
+
+#[rpc]
+pub trait DidApi<BlockHash, AccountId, Hash> {
+    /// Resolve a DID string or user key into a W3C DID document.
+    #[rpc(name = "did_resolve")]
+    fn resolve(&self, id: DidOrKey<Hash>, at: Option<BlockHash>) -> Result<Option<Document>>;
+
+    /// The DID a verified external address, e.g. `eip155:1` and `0xabc...`, is linked to.
+    #[rpc(name = "did_lookupAddress")]
+    fn lookup_address(
+        &self,
+        chain_id: String,
+        address: String,
+        at: Option<BlockHash>,
+    ) -> Result<Option<String>>;
+
+    /// One page of the direct subordinates of a DID, by user key.
+    #[rpc(name = "did_children")]
+    fn children(&self, user_key: Hash, page: u32, at: Option<BlockHash>) -> Result<Children<Hash>>;
+
+    /// Subordinates of a DID up to `depth` levels down, breadth first from `start`.
+    #[rpc(name = "did_descendants")]
+    fn descendants(
+        &self,
+        user_key: Hash,
+        depth: u32,
+        start: u32,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Descendant<Hash>>>;
+
+    /// The superiors of a DID up to the root of its referral tree, nearest first.
+    #[rpc(name = "did_ancestors")]
+    fn ancestors(&self, user_key: Hash, at: Option<BlockHash>) -> Result<Vec<Hash>>;
+
+    /// The DID a short ID, e.g. `kR3xQa`, was issued to.
+    #[rpc(name = "did_resolveShortId")]
+    fn resolve_short_id(
+        &self,
+        short_id: String,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ShortId<AccountId, Hash>>>;
+}
+
+/// An implementation of did specific RPC methods.
+pub struct Did<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Did<C, B> {
+    /// Create new `Did` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Did {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, AccountId, Hash> DidApi<<Block as BlockT>::Hash, AccountId, Hash> for Did<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: DidRuntimeApi<Block, AccountId, Hash>,
+    AccountId: Codec,
+    Hash: Codec + Clone,
+{
+    fn resolve(
+        &self,
+        id: DidOrKey<Hash>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Document>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let document = match id {
+            DidOrKey::Did(did) => api.resolve(&at, did.into_bytes()),
+            DidOrKey::Key(user_key) => api.resolve_by_key(&at, user_key),
+        }
+        .map_err(runtime_error)?;
+
+        Ok(document.map(Into::into))
+    }
+
+    fn lookup_address(
+        &self,
+        chain_id: String,
+        address: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<String>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let did = api
+            .lookup_address(&at, chain_id.into_bytes(), address.into_bytes())
+            .map_err(runtime_error)?;
+
+        Ok(did.map(text))
+    }
+
+    fn children(
+        &self,
+        user_key: Hash,
+        page: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Children<Hash>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let total = api.child_count(&at, user_key.clone()).map_err(runtime_error)?;
+        let children = api.children(&at, user_key, page).map_err(runtime_error)?;
+
+        Ok(Children {
+            total,
+            page,
+            page_size: SUBORDINATE_PAGE_SIZE,
+            children,
+        })
+    }
+
+    fn descendants(
+        &self,
+        user_key: Hash,
+        depth: u32,
+        start: u32,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Descendant<Hash>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let descendants = api
+            .descendants(&at, user_key, depth, start, limit)
+            .map_err(runtime_error)?;
+
+        Ok(descendants
+            .into_iter()
+            .map(|(user_key, depth)| Descendant { user_key, depth })
+            .collect())
+    }
+
+    fn ancestors(
+        &self,
+        user_key: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Hash>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        api.ancestors(&at, user_key).map_err(runtime_error)
+    }
+
+    fn resolve_short_id(
+        &self,
+        short_id: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ShortId<AccountId, Hash>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let record = api
+            .resolve_short_id(&at, short_id.into_bytes())
+            .map_err(runtime_error)?;
+
+        Ok(record.map(Into::into))
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(err: E) -> Error {
+    Error {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime trapped".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
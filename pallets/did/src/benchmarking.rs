@@ -0,0 +1,954 @@
+//! Benchmarks for the did pallet, every call is measured at its worst case.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_core::{crypto::KeyTypeId, sr25519};
+use sp_io::crypto::{sr25519_generate, sr25519_sign};
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// Keys attestors and external wallets sign with, they only live in the benchmark's keystore.
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"didb");
+
+type Attesting<T> = (<T as frame_system::Config>::AccountId, sr25519::Public);
+
+fn unit<T: Config>() -> T::Balance {
+    Module::<T>::min_deposit().max(T::GroupDeposit::get()).max(100u32.into())
+}
+
+fn fund<T: Config>(who: &T::AccountId) {
+    let balance = unit::<T>().saturating_mul(1_000_000u32.into());
+    <pallet_balances::Module<T> as Currency<_>>::make_free_balance_be(who, balance);
+}
+
+fn funded<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let who = account(name, index, SEED);
+    fund::<T>(&who);
+    who
+}
+
+fn signed<T: Config>(who: &T::AccountId) -> T::Origin {
+    RawOrigin::Signed(who.clone()).into()
+}
+
+/// `len` bytes starting with the decimal `index`, to fill bounded fields with distinct values.
+fn filled(len: u32, index: u32) -> Vec<u8> {
+    let mut value = check::to_decimal(index as u64);
+    value.resize(len.max(value.len() as u32) as usize, b'a');
+    value
+}
+
+fn public_key(index: u32) -> Vec<u8> {
+    let mut public = index.encode();
+    public.resize(KeyType::Sr25519.public_len(), 0xff);
+    public
+}
+
+fn create_did<T: Config>(who: &T::AccountId, superior: T::Hash) -> Result<T::Hash, &'static str> {
+    Module::<T>::create(
+        signed::<T>(who),
+        who.encode(),
+        who.clone(),
+        b"1".to_vec(),
+        superior,
+        None,
+        None,
+    )?;
+    Module::<T>::identity(who).map(|(user_key, _)| user_key).ok_or("did not created")
+}
+
+/// The DID of the genesis account, creating under it needs no lock.
+fn genesis_did<T: Config>() -> Result<T::Hash, &'static str> {
+    let who = Module::<T>::genesis_account();
+    match Module::<T>::identity(&who) {
+        Some((user_key, _)) => Ok(user_key),
+        None => {
+            fund::<T>(&who);
+            create_did::<T>(&who, Default::default())
+        }
+    }
+}
+
+fn new_did<T: Config>(name: &'static str, index: u32, superior: T::Hash) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let who = funded::<T>(name, index);
+    let user_key = create_did::<T>(&who, superior)?;
+    Ok((who, user_key))
+}
+
+/// Add matured lock positions until the DID holds `count` of them.
+fn fill_locks<T: Config>(who: &T::AccountId, count: u32) -> Result<(), &'static str> {
+    let amount = unit::<T>().saturating_mul(10u32.into());
+    for _ in 0..count {
+        Module::<T>::lock(signed::<T>(who), amount, Zero::zero())?;
+    }
+    Ok(())
+}
+
+/// A chain of `depth` locked DIDs below the genesis DID, returns the deepest one.
+fn referral_chain<T: Config>(depth: u32) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let mut superior = (Module::<T>::genesis_account(), genesis_did::<T>()?);
+    for i in 0..depth {
+        superior = new_did::<T>("superior", i, superior.1)?;
+        fill_locks::<T>(&superior.0, 1)?;
+    }
+    Ok(superior)
+}
+
+/// A schedule paying every one of `levels` superiors, without a cap.
+fn schedule<T: Config>(levels: u32) -> RewardSchedule<T::Balance> {
+    RewardSchedule {
+        rebate: Permill::from_percent(50),
+        cap: T::Balance::max_value(),
+        levels: (0..levels)
+            .map(|_| RewardLevel {
+                share: Permill::from_rational_approximation(1u32, levels.max(1)),
+                max_payout: None,
+            })
+            .collect(),
+    }
+}
+
+fn advance<T: Config>(by: T::Moment) {
+    let now = <pallet_timestamp::Module<T>>::get();
+    <pallet_timestamp::Module<T>>::set_timestamp(now.saturating_add(by));
+}
+
+/// `MAX_DID_TYPE_CREATORS` accounts, `last` the one searched last.
+fn did_type_creators<T: Config>(last: &T::AccountId) -> DidTypeCreators<T::AccountId> {
+    let mut accounts: Vec<T::AccountId> = (1..MAX_DID_TYPE_CREATORS as u32)
+        .map(|i| account("creator", i, SEED))
+        .collect();
+    accounts.push(last.clone());
+    DidTypeCreators::Accounts(accounts)
+}
+
+/// Make DIDs of the `1` type reserve a deposit, `creators` limits who may create them.
+fn restrict_did_type<T: Config>(creators: DidTypeCreators<T::AccountId>) {
+    <DidTypes<T>>::insert(b"1".to_vec(), DidTypeInfo {
+        name: filled(MAX_DID_TYPE_NAME_LENGTH as u32, 1),
+        creators,
+        deposit: unit::<T>(),
+        capabilities: vec![Capability::PublishAds],
+    });
+}
+
+/// Invite a new DID to a group and have it accept.
+fn join<T: Config>(
+    admin: &T::AccountId,
+    group_id: GroupId,
+    name: &'static str,
+    index: u32,
+) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let (member, member_key) = new_did::<T>(name, index, genesis_did::<T>()?)?;
+    Module::<T>::invite_member(signed::<T>(admin), group_id, member_key, GroupRole::Member)?;
+    Module::<T>::accept_invite(signed::<T>(&member), group_id)?;
+    Ok((member, member_key))
+}
+
+/// A group with the longest name and `members` members besides its owner.
+fn group_with_members<T: Config>(members: u32) -> Result<(T::AccountId, GroupId), &'static str> {
+    let (owner, _) = new_did::<T>("owner", 0, genesis_did::<T>()?)?;
+    fill_locks::<T>(&owner, 1)?;
+    let group_id = Module::<T>::next_group_id();
+    Module::<T>::create_group(signed::<T>(&owner), filled(MAX_GROUP_NAME_LENGTH as u32, 0))?;
+    for i in 0..members {
+        join::<T>(&owner, group_id, "member", i + 1)?;
+    }
+    Ok((owner, group_id))
+}
+
+/// Make the DID a member of `count` more groups, each founded by another DID.
+fn fill_groups<T: Config>(who: &T::AccountId, user_key: T::Hash, count: u32) -> Result<(), &'static str> {
+    for i in 0..count {
+        let (founder, _) = new_did::<T>("founder", i, genesis_did::<T>()?)?;
+        fill_locks::<T>(&founder, 1)?;
+        let group_id = Module::<T>::next_group_id();
+        Module::<T>::create_group(signed::<T>(&founder), filled(MAX_GROUP_NAME_LENGTH as u32, i + 1))?;
+        Module::<T>::invite_member(signed::<T>(&founder), group_id, user_key, GroupRole::Member)?;
+        Module::<T>::accept_invite(signed::<T>(who), group_id)?;
+    }
+    Ok(())
+}
+
+/// Unlock into `chunks` unbonding chunks, after `MaxUnlockHistory` unlocks into the first one.
+fn fill_unbonding<T: Config>(who: &T::AccountId, chunks: u32) -> Result<(), &'static str> {
+    let amount = unit::<T>() / 100u32.into();
+    for _ in 0..T::MaxUnlockHistory::get() {
+        Module::<T>::unlock(signed::<T>(who), amount)?;
+    }
+    for _ in 1..chunks {
+        advance::<T>(1u32.into());
+        Module::<T>::unlock(signed::<T>(who), amount)?;
+    }
+    Ok(())
+}
+
+fn fill_services<T: Config>(who: &T::AccountId, count: u32) -> Result<(), &'static str> {
+    for i in 0..count {
+        Module::<T>::add_service(
+            signed::<T>(who),
+            filled(T::MaxServiceTypeLength::get(), i),
+            filled(T::MaxServiceTypeLength::get(), i),
+            filled(T::MaxServiceUrlLength::get(), i),
+        )?;
+    }
+    Ok(())
+}
+
+/// Add keys until the DID holds `count` of them, the DID's account key is the first one.
+fn fill_keys<T: Config>(who: &T::AccountId, count: u32) -> Result<(), &'static str> {
+    for i in 1..count {
+        Module::<T>::add_key(signed::<T>(who), KeyType::Sr25519, public_key(i))?;
+    }
+    Ok(())
+}
+
+/// Have `count` more groups invite the DID, numbered after the groups of `fill_groups`.
+fn fill_invites<T: Config>(user_key: T::Hash, from: u32, count: u32) -> Result<(), &'static str> {
+    for i in from..from + count {
+        let (founder, _) = new_did::<T>("founder", i, genesis_did::<T>()?)?;
+        fill_locks::<T>(&founder, 1)?;
+        let group_id = Module::<T>::next_group_id();
+        Module::<T>::create_group(signed::<T>(&founder), filled(MAX_GROUP_NAME_LENGTH as u32, i + 1))?;
+        Module::<T>::invite_member(signed::<T>(&founder), group_id, user_key, GroupRole::Member)?;
+    }
+    Ok(())
+}
+
+fn chain_id(index: u32) -> ChainId {
+    let mut chain_id = b"polkadot:".to_vec();
+    chain_id.append(&mut filled(32, index));
+    chain_id
+}
+
+/// Link `chains` chains of verified addresses, each holding all addresses it may hold.
+fn fill_addresses<T: Config>(user_key: T::Hash, chains: u32) {
+    let mut metadata = Module::<T>::metadata(&user_key);
+    for c in 0..chains {
+        let chain_id = chain_id(c);
+        let addresses: Vec<Vec<u8>> = (0..T::MaxAddressesPerChain::get())
+            .map(|a| {
+                let address = filled(48, a);
+                <ExternalAddressOwner<T>>::insert(&chain_id, &address, user_key);
+                address
+            })
+            .collect();
+        metadata.external_addresses.insert(chain_id, addresses);
+    }
+    <Metadata<T>>::insert(&user_key, metadata);
+}
+
+/// A fresh SS58 address and its signature over the DID's current challenge.
+fn polkadot_proof<T: Config>(user_key: T::Hash) -> (Vec<u8>, Vec<u8>) {
+    let public = sr25519_generate(KEY_TYPE, None);
+    let challenge = Module::<T>::proof_challenge(user_key);
+    let signature = sr25519_sign(KEY_TYPE, &public, &challenge).expect("key is in the keystore");
+    (check::to_ss58(&public.0), signature.0.to_vec())
+}
+
+fn register_attestor<T: Config>() -> Result<Attesting<T>, &'static str> {
+    let attestor: T::AccountId = account("attestor", 0, SEED);
+    let public = sr25519_generate(KEY_TYPE, None);
+    Module::<T>::register_attestor(
+        RawOrigin::Root.into(),
+        attestor.clone(),
+        b"twitter".to_vec(),
+        public.0.to_vec(),
+    )?;
+    Ok((attestor, public))
+}
+
+fn attest<T: Config>(attestor: &Attesting<T>, did: &[u8], handle: &[u8]) -> SocialProof<T::AccountId> {
+    let statement = proof::social_statement(
+        b"twitter",
+        handle,
+        &Module::<T>::did_uri(did),
+        Module::<T>::proof_nonce(T::Hashing::hash(did)),
+    );
+    let signature = sr25519_sign(KEY_TYPE, &attestor.1, &statement).expect("key is in the keystore");
+    SocialProof {
+        attestor: attestor.0.clone(),
+        platform: b"twitter".to_vec(),
+        handle: handle.to_vec(),
+        signature: signature.0.to_vec(),
+    }
+}
+
+fn bind<T: Config>(attestor: &Attesting<T>, who: &T::AccountId, handle: &[u8]) -> Result<(), &'static str> {
+    let (_, did) = Module::<T>::identity(who).ok_or("did not exists")?;
+    Module::<T>::bind_social_account(signed::<T>(who), attest::<T>(attestor, &did, handle))?;
+    Ok(())
+}
+
+fn dispute<T: Config>(attestor: &Attesting<T>, handle: &[u8]) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let (claimant, claimant_key) = new_did::<T>("claimant", 0, genesis_did::<T>()?)?;
+    let (_, did) = Module::<T>::identity(&claimant).ok_or("did not exists")?;
+    Module::<T>::dispute_social_account(signed::<T>(&claimant), attest::<T>(attestor, &did, handle))?;
+    Ok((claimant, claimant_key))
+}
+
+fn social_hash<T: Config>(handle: &[u8]) -> T::Hash {
+    Module::<T>::social_hash(b"twitter", handle, b"1")
+}
+
+fn guardians<T: Config>(count: u32) -> Result<Vec<(T::AccountId, T::Hash)>, &'static str> {
+    let genesis = genesis_did::<T>()?;
+    (0..count).map(|i| new_did::<T>("guardian", i, genesis)).collect()
+}
+
+/// A DID whose recovery to `new_account` is ready to be claimed.
+fn ready_recovery<T: Config>(lost: T::Hash, new_account: &T::AccountId) -> Result<(), &'static str> {
+    let guardians = guardians::<T>(1)?;
+    <RecoveryConfigs<T>>::insert(&lost, RecoveryConfig {
+        guardians: vec![guardians[0].1],
+        threshold: 1,
+    });
+    Module::<T>::vouch_recovery(signed::<T>(&guardians[0].0), lost, new_account.clone())?;
+    advance::<T>(T::RecoveryDelay::get());
+    Ok(())
+}
+
+benchmarks! {
+    create {
+        let attestor = register_attestor::<T>()?;
+        let (superior, _) = referral_chain::<T>(1)?;
+        bind::<T>(&attestor, &superior, b"superior")?;
+
+        let caller = funded::<T>("caller", 0);
+        restrict_did_type::<T>(did_type_creators::<T>(&caller));
+        let pubkey = caller.encode();
+        let did = Module::<T>::generate_did(&pubkey, b"1");
+        let proof = attest::<T>(&attestor, &did, b"caller");
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        pubkey,
+        caller.clone(),
+        b"1".to_vec(),
+        Default::default(),
+        Some(proof),
+        Some(b"twitter:superior".to_vec())
+    )
+    verify {
+        assert_eq!(Module::<T>::identity(&caller).map(|(key, _)| key), Some(T::Hashing::hash(&did)));
+    }
+
+    // the reserved funds of every lock position and unbonding chunk follow the DID
+    update {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get())?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get())?;
+        let to: T::AccountId = account("to", 0, SEED);
+    }: _(RawOrigin::Signed(caller), to.clone())
+    verify {
+        assert_eq!(Module::<T>::identity_of(&user_key), Some(to));
+    }
+
+    // chains are filled with the most addresses each
+    deactivate {
+        let s in 0 .. T::MaxServices::get();
+        let c in 0 .. T::MaxChains::get();
+        let g in 0 .. T::MaxGroupsPerDid::get();
+        let i in 0 .. T::MaxGroupsPerDid::get();
+        let attestor = register_attestor::<T>()?;
+        let (_, superior) = referral_chain::<T>(1)?;
+        restrict_did_type::<T>(DidTypeCreators::Anyone);
+        let (caller, user_key) = new_did::<T>("caller", 0, superior)?;
+
+        fill_locks::<T>(&caller, T::MaxLockPositions::get())?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get())?;
+        advance::<T>(T::UnbondingPeriod::get());
+        fill_services::<T>(&caller, s)?;
+        bind::<T>(&attestor, &caller, b"caller")?;
+        fill_addresses::<T>(user_key, c);
+        <RecoveryConfigs<T>>::insert(&user_key, RecoveryConfig { guardians: vec![superior], threshold: 1 });
+        fill_groups::<T>(&caller, user_key, g)?;
+        fill_invites::<T>(user_key, g, i)?;
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(Module::<T>::tombstone(&user_key).is_some());
+        assert!(Module::<T>::groups_of(&user_key).is_empty());
+        assert!(Module::<T>::invites_of(&user_key).is_empty());
+    }
+
+    add_key {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_keys::<T>(&caller, T::MaxKeys::get() - 1)?;
+        let public = public_key(T::MaxKeys::get());
+    }: _(RawOrigin::Signed(caller), KeyType::Sr25519, public)
+    verify {
+        assert_eq!(Module::<T>::metadata(&user_key).keys.len() as u32, T::MaxKeys::get());
+    }
+
+    remove_key {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_keys::<T>(&caller, T::MaxKeys::get())?;
+    }: _(RawOrigin::Signed(caller), public_key(T::MaxKeys::get() - 1))
+    verify {
+        assert_eq!(Module::<T>::metadata(&user_key).keys.len() as u32, T::MaxKeys::get() - 1);
+    }
+
+    // rotating the account key moves the DID and its reserved funds to the new account
+    rotate_key {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_keys::<T>(&caller, T::MaxKeys::get())?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get())?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get())?;
+        let to: T::AccountId = account("to", 0, SEED);
+        let mut new_public = to.encode();
+        new_public.resize(KeyType::Sr25519.public_len(), 0);
+    }: _(RawOrigin::Signed(caller.clone()), caller.encode(), KeyType::Sr25519, new_public)
+    verify {
+        assert_eq!(Module::<T>::identity_of(&user_key), Some(to));
+    }
+
+    add_service {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_services::<T>(&caller, T::MaxServices::get() - 1)?;
+        let index = T::MaxServices::get();
+    }: _(
+        RawOrigin::Signed(caller),
+        filled(T::MaxServiceTypeLength::get(), index),
+        filled(T::MaxServiceTypeLength::get(), index),
+        filled(T::MaxServiceUrlLength::get(), index)
+    )
+    verify {
+        assert_eq!(Module::<T>::services(&user_key).len() as u32, T::MaxServices::get());
+    }
+
+    update_service {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_services::<T>(&caller, T::MaxServices::get())?;
+        let index = T::MaxServices::get() - 1;
+        let endpoint = filled(T::MaxServiceUrlLength::get(), index + 1);
+    }: _(
+        RawOrigin::Signed(caller),
+        filled(T::MaxServiceTypeLength::get(), index),
+        filled(T::MaxServiceTypeLength::get(), index),
+        endpoint.clone()
+    )
+    verify {
+        assert!(Module::<T>::services(&user_key).iter().any(|s| s.endpoint == endpoint));
+    }
+
+    remove_service {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_services::<T>(&caller, T::MaxServices::get())?;
+    }: _(RawOrigin::Signed(caller), filled(T::MaxServiceTypeLength::get(), 0))
+    verify {
+        assert_eq!(Module::<T>::services(&user_key).len() as u32, T::MaxServices::get() - 1);
+    }
+
+    transfer {
+        let genesis = genesis_did::<T>()?;
+        let (caller, _) = new_did::<T>("caller", 0, genesis)?;
+        let (to, to_user) = new_did::<T>("to", 0, genesis)?;
+        let before = <pallet_balances::Module<T>>::free_balance(&to);
+    }: _(RawOrigin::Signed(caller), to_user, unit::<T>(), filled(32, 0))
+    verify {
+        assert!(<pallet_balances::Module<T>>::free_balance(&to) > before);
+    }
+
+    transfer_batch {
+        let n in 1 .. T::MaxBatchTransfers::get();
+
+        let genesis = genesis_did::<T>()?;
+        let (caller, _) = new_did::<T>("caller", 0, genesis)?;
+        let mut transfers = Vec::new();
+        for i in 0..n {
+            let (_, to_user) = new_did::<T>("to", i, genesis)?;
+            transfers.push((to_user, unit::<T>(), filled(32, i)));
+        }
+        let before = <pallet_balances::Module<T>>::free_balance(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), transfers)
+    verify {
+        assert!(<pallet_balances::Module<T>>::free_balance(&caller) < before);
+    }
+
+    lock {
+        let l in 1 .. T::MaxRewardLevels::get();
+
+        <ReferralSchedule<T>>::put(schedule::<T>(l));
+        let (_, superior) = referral_chain::<T>(l)?;
+        let (caller, user_key) = new_did::<T>("caller", 0, superior)?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get() - 1)?;
+    }: _(RawOrigin::Signed(caller), unit::<T>().saturating_mul(10u32.into()), Zero::zero())
+    verify {
+        let positions = Module::<T>::metadata(&user_key).locked_records.map_or(0, |r| r.positions.len());
+        assert_eq!(positions as u32, T::MaxLockPositions::get());
+    }
+
+    force_lock {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get() - 1)?;
+    }: _(RawOrigin::Root, user_key, unit::<T>())
+    verify {
+        let positions = Module::<T>::metadata(&user_key).locked_records.map_or(0, |r| r.positions.len());
+        assert_eq!(positions as u32, T::MaxLockPositions::get());
+    }
+
+    set_reward_schedule {
+        let l in 1 .. T::MaxRewardLevels::get();
+
+        let origin = T::RewardOrigin::successful_origin();
+        let schedule = schedule::<T>(l);
+    }: {
+        Module::<T>::set_reward_schedule(origin, schedule.clone())?;
+    }
+    verify {
+        assert_eq!(Module::<T>::reward_schedule(), schedule);
+    }
+
+    set_rewards_curve {
+        let origin = T::RewardOrigin::successful_origin();
+        let curve = RewardsCurve { quota_per_unit: 5, base_ratio: 10, max_ratio: 90 };
+    }: {
+        Module::<T>::set_rewards_curve(origin, curve.clone())?;
+    }
+    verify {
+        assert_eq!(Module::<T>::rewards_curve(), curve);
+    }
+
+    set_did_type {
+        let c in 0 .. MAX_DID_TYPE_CREATORS as u32;
+        let origin = T::DidTypeOrigin::successful_origin();
+        let info = DidTypeInfo {
+            name: filled(MAX_DID_TYPE_NAME_LENGTH as u32, 0),
+            creators: DidTypeCreators::Accounts((0..c).map(|i| account("creator", i, SEED)).collect()),
+            deposit: unit::<T>(),
+            capabilities: vec![Capability::PublishAds],
+        };
+    }: {
+        Module::<T>::set_did_type(origin, filled(MAX_DID_TYPE_LENGTH as u32, 9), info.clone())?;
+    }
+    verify {
+        assert_eq!(Module::<T>::did_type(filled(MAX_DID_TYPE_LENGTH as u32, 9)), Some(info));
+    }
+
+    remove_did_type {
+        let origin = T::DidTypeOrigin::successful_origin();
+    }: {
+        Module::<T>::remove_did_type(origin, b"4".to_vec())?;
+    }
+    verify {
+        assert!(Module::<T>::did_type(b"4".to_vec()).is_none());
+    }
+
+    unlock {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get())?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get() - 1)?;
+        advance::<T>(1u32.into());
+    }: _(RawOrigin::Signed(caller), unit::<T>() / 100u32.into())
+    verify {
+        assert_eq!(Module::<T>::unbonding(&user_key).len() as u32, T::MaxUnbondingChunks::get());
+    }
+
+    withdraw_unbonded {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, 1)?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get())?;
+        advance::<T>(T::UnbondingPeriod::get());
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(Module::<T>::unbonding(&user_key).is_empty());
+    }
+
+    // sr25519 verification, ETH and BTC proofs recover a secp256k1 key at a similar cost
+    add_external_address {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_addresses::<T>(user_key, T::MaxChains::get() - 1);
+        let (address, signature) = polkadot_proof::<T>(user_key);
+        let chain_id = chain_id(T::MaxChains::get());
+    }: _(RawOrigin::Signed(caller), chain_id.clone(), address.clone(), signature)
+    verify {
+        assert_eq!(Module::<T>::external_address_owner(&chain_id, &address), Some(user_key));
+    }
+
+    remove_external_address {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_addresses::<T>(user_key, T::MaxChains::get());
+        let chain_id = chain_id(0);
+        let address = filled(48, 0);
+    }: _(RawOrigin::Signed(caller), chain_id.clone(), address.clone())
+    verify {
+        assert_eq!(Module::<T>::external_address_owner(&chain_id, &address), None);
+    }
+
+    register_attestor {
+        let attestor: T::AccountId = account("attestor", 0, SEED);
+        let platform = filled(social::MAX_HANDLE_LENGTH as u32, 0);
+    }: _(RawOrigin::Root, attestor.clone(), platform, public_key(0))
+    verify {
+        assert!(Module::<T>::attestors(&attestor).is_some());
+    }
+
+    remove_attestor {
+        let (attestor, _) = register_attestor::<T>()?;
+    }: _(RawOrigin::Root, attestor.clone())
+    verify {
+        assert!(Module::<T>::attestors(&attestor).is_none());
+    }
+
+    // the social account was bound to another DID without an attestation and is taken over
+    bind_social_account {
+        let attestor = register_attestor::<T>()?;
+        let genesis = genesis_did::<T>()?;
+        let (_, holder) = new_did::<T>("holder", 0, genesis)?;
+        let social_hash = social_hash::<T>(b"caller");
+        <SocialAccount<T>>::insert(&social_hash, holder);
+        <Metadata<T>>::mutate(&holder, |m| m.social_account = Some(social_hash));
+
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis)?;
+        let (_, did) = Module::<T>::identity(&caller).ok_or("did not exists")?;
+        let proof = attest::<T>(&attestor, &did, b"caller");
+    }: _(RawOrigin::Signed(caller), proof)
+    verify {
+        assert_eq!(Module::<T>::social_account(&social_hash), user_key);
+    }
+
+    rebind_social_account {
+        let attestor = register_attestor::<T>()?;
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        bind::<T>(&attestor, &caller, b"old")?;
+        dispute::<T>(&attestor, b"old")?;
+
+        let (_, did) = Module::<T>::identity(&caller).ok_or("did not exists")?;
+        let proof = attest::<T>(&attestor, &did, b"new");
+    }: _(RawOrigin::Signed(caller), proof)
+    verify {
+        assert_eq!(Module::<T>::social_account(&social_hash::<T>(b"new")), user_key);
+    }
+
+    unbind_social_account {
+        let attestor = register_attestor::<T>()?;
+        let (caller, _) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        bind::<T>(&attestor, &caller, b"caller")?;
+        dispute::<T>(&attestor, b"caller")?;
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(Module::<T>::social_dispute(&social_hash::<T>(b"caller")).is_none());
+    }
+
+    dispute_social_account {
+        let attestor = register_attestor::<T>()?;
+        let genesis = genesis_did::<T>()?;
+        let (holder, _) = new_did::<T>("holder", 0, genesis)?;
+        bind::<T>(&attestor, &holder, b"holder")?;
+
+        let (caller, _) = new_did::<T>("caller", 0, genesis)?;
+        let (_, did) = Module::<T>::identity(&caller).ok_or("did not exists")?;
+        let proof = attest::<T>(&attestor, &did, b"holder");
+    }: _(RawOrigin::Signed(caller), proof)
+    verify {
+        assert!(Module::<T>::social_dispute(&social_hash::<T>(b"holder")).is_some());
+    }
+
+    resolve_social_dispute {
+        let attestor = register_attestor::<T>()?;
+        let (holder, _) = new_did::<T>("holder", 0, genesis_did::<T>()?)?;
+        bind::<T>(&attestor, &holder, b"holder")?;
+        let (_, claimant) = dispute::<T>(&attestor, b"holder")?;
+        let social_hash = social_hash::<T>(b"holder");
+    }: _(RawOrigin::Root, social_hash, true)
+    verify {
+        assert_eq!(Module::<T>::social_account(&social_hash), claimant);
+    }
+
+    set_recovery {
+        let g in 1 .. T::MaxGuardians::get();
+
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        let guardians: Vec<T::Hash> = guardians::<T>(g)?.into_iter().map(|(_, key)| key).collect();
+    }: _(RawOrigin::Signed(caller), guardians, g)
+    verify {
+        assert!(Module::<T>::recovery_config(&user_key).is_some());
+    }
+
+    remove_recovery {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        let guardians: Vec<T::Hash> = guardians::<T>(T::MaxGuardians::get())?
+            .into_iter()
+            .map(|(_, key)| key)
+            .collect();
+        Module::<T>::set_recovery(signed::<T>(&caller), guardians, T::MaxGuardians::get())?;
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(Module::<T>::recovery_config(&user_key).is_none());
+    }
+
+    // the last vouch reaches the threshold
+    vouch_recovery {
+        let (caller, lost) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        let guardians = guardians::<T>(T::MaxGuardians::get())?;
+        Module::<T>::set_recovery(
+            signed::<T>(&caller),
+            guardians.iter().map(|(_, key)| *key).collect(),
+            T::MaxGuardians::get(),
+        )?;
+
+        let new_account: T::AccountId = account("new", 0, SEED);
+        let (last, others) = guardians.split_last().ok_or("no guardians")?;
+        for (guardian, _) in others {
+            Module::<T>::vouch_recovery(signed::<T>(guardian), lost, new_account.clone())?;
+        }
+        let last = last.0.clone();
+    }: _(RawOrigin::Signed(last), lost, new_account)
+    verify {
+        assert!(Module::<T>::active_recovery(&lost).is_some());
+    }
+
+    cancel_recovery {
+        let (caller, lost) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        let new_account: T::AccountId = account("new", 0, SEED);
+        ready_recovery::<T>(lost, &new_account)?;
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(Module::<T>::active_recovery(&lost).is_none());
+    }
+
+    claim_recovery {
+        let (caller, lost) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, T::MaxLockPositions::get())?;
+        fill_unbonding::<T>(&caller, T::MaxUnbondingChunks::get())?;
+        fill_services::<T>(&caller, T::MaxServices::get())?;
+
+        let new_account: T::AccountId = account("new", 0, SEED);
+        ready_recovery::<T>(lost, &new_account)?;
+    }: _(RawOrigin::Signed(new_account.clone()), lost)
+    verify {
+        assert_eq!(Module::<T>::identity_of(&lost), Some(new_account));
+    }
+
+    create_group {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, 1)?;
+        fill_groups::<T>(&caller, user_key, T::MaxGroupsPerDid::get() - 1)?;
+        let name = filled(MAX_GROUP_NAME_LENGTH as u32, 0);
+    }: _(RawOrigin::Signed(caller), name.clone())
+    verify {
+        assert!(Module::<T>::group_by_name(name).is_some());
+    }
+
+    // invitations are written directly, `invite_member` would not let them exceed the free places
+    dissolve_group {
+        let m in 1 .. T::MaxGroupMembers::get();
+        let i in 0 .. T::MaxGroupMembers::get() - 1;
+        let (owner, group_id) = group_with_members::<T>(m - 1)?;
+        for j in 0..i {
+            <GroupInvites<T>>::insert(group_id, T::Hashing::hash_of(&j), GroupRole::Member);
+        }
+        <Groups<T>>::mutate(group_id, |group| {
+            if let Some(group) = group {
+                group.invite_count = i;
+            }
+        });
+    }: _(RawOrigin::Signed(owner), group_id)
+    verify {
+        assert!(Module::<T>::group(group_id).is_none());
+    }
+
+    // invited by an admin rather than the owner
+    invite_member {
+        let (owner, group_id) = group_with_members::<T>(0)?;
+        let (admin, admin_key) = join::<T>(&owner, group_id, "admin", 0)?;
+        Module::<T>::set_member_role(signed::<T>(&owner), group_id, admin_key, GroupRole::Admin)?;
+        let (_, member) = new_did::<T>("member", 0, genesis_did::<T>()?)?;
+    }: _(RawOrigin::Signed(admin), group_id, member, GroupRole::Member)
+    verify {
+        assert_eq!(Module::<T>::group_invite(group_id, &member), Some(GroupRole::Member));
+    }
+
+    accept_invite {
+        let (owner, group_id) = group_with_members::<T>(T::MaxGroupMembers::get() - 2)?;
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_groups::<T>(&caller, user_key, T::MaxGroupsPerDid::get() - 1)?;
+        Module::<T>::invite_member(signed::<T>(&owner), group_id, user_key, GroupRole::Member)?;
+    }: _(RawOrigin::Signed(caller), group_id)
+    verify {
+        assert_eq!(Module::<T>::group_member(group_id, &user_key), Some(GroupRole::Member));
+    }
+
+    // removed by an admin from the last of its groups
+    remove_member {
+        let (owner, group_id) = group_with_members::<T>(0)?;
+        let (admin, admin_key) = join::<T>(&owner, group_id, "admin", 0)?;
+        Module::<T>::set_member_role(signed::<T>(&owner), group_id, admin_key, GroupRole::Admin)?;
+        let (member, member_key) = new_did::<T>("member", 0, genesis_did::<T>()?)?;
+        fill_groups::<T>(&member, member_key, T::MaxGroupsPerDid::get() - 1)?;
+        Module::<T>::invite_member(signed::<T>(&owner), group_id, member_key, GroupRole::Member)?;
+        Module::<T>::accept_invite(signed::<T>(&member), group_id)?;
+    }: _(RawOrigin::Signed(admin), group_id, member_key)
+    verify {
+        assert!(Module::<T>::group_member(group_id, &member_key).is_none());
+    }
+
+    set_member_role {
+        let (owner, group_id) = group_with_members::<T>(0)?;
+        let (_, member) = join::<T>(&owner, group_id, "member", 0)?;
+    }: _(RawOrigin::Signed(owner), group_id, member, GroupRole::Admin)
+    verify {
+        assert_eq!(Module::<T>::group_member(group_id, &member), Some(GroupRole::Admin));
+    }
+
+    set_group_name {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+        fill_locks::<T>(&caller, 1)?;
+        let name = filled(49, 0);
+    }: _(RawOrigin::Signed(caller), name.clone())
+    verify {
+        assert_eq!(Module::<T>::metadata(&user_key).group_name, Some(name));
+    }
+
+    judge {
+        let (caller, user_key) = new_did::<T>("caller", 0, genesis_did::<T>()?)?;
+    }: _(RawOrigin::Signed(Module::<T>::genesis_account()), caller.clone())
+    verify {
+        assert_eq!(Module::<T>::metadata(&user_key).creator, caller);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{new_test_ext, Test};
+    use frame_support::assert_ok;
+    use sp_keystore::{testing::KeyStore, KeystoreExt};
+    use std::sync::Arc;
+
+    fn new_bench_ext() -> sp_io::TestExternalities {
+        let mut ext = new_test_ext();
+        ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+        ext
+    }
+
+    #[test]
+    fn test_benchmarks() {
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_update::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_deactivate::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_add_key::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_key::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_rotate_key::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_add_service::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_update_service::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_service::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_batch::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_lock::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_force_lock::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_reward_schedule::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_rewards_curve::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_did_type::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_did_type::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_unlock::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_withdraw_unbonded::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_add_external_address::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_external_address::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_register_attestor::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_attestor::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_bind_social_account::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_rebind_social_account::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_unbind_social_account::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_dispute_social_account::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_resolve_social_dispute::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_recovery::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_recovery::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_vouch_recovery::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_cancel_recovery::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_claim_recovery::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_group_name::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create_group::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_dissolve_group::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_invite_member::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_accept_invite::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_member::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_member_role::<Test>());
+        });
+        new_bench_ext().execute_with(|| {
+            assert_ok!(test_benchmark_judge::<Test>());
+        });
+    }
+}
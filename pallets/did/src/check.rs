@@ -1,8 +1,146 @@
+use blake2_rfc::blake2b::blake2b;
+use sp_io::hashing::{keccak_256, sha2_256};
 use sp_std::prelude::Vec;
 
 static BASE58_CHARS: &'static [u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
 static ETH_CHARS: &'static [u8] = b"0123456789abcdef";
 static EOS_CHARS: &'static [u8] = b"12345abcdefghijklmnopqrstuvwxyz.";
+static EOS_LAST_CHARS: &'static [u8] = b"12345abcdefghij";
+static BECH32_CHARS: &'static [u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+const BTC_P2PKH_VERSIONS: &[u8] = &[0x00, 0x6f];
+const BTC_P2SH_VERSIONS: &[u8] = &[0x05, 0xc4];
+const BTC_HRPS: &[&[u8]] = &[b"bc", b"tb"];
+/// Base58check encoding of a version byte, 20-byte hash and 4-byte checksum.
+const BTC_BASE58_MAX_LENGTH: usize = 35;
+
+const TRON_VERSION: u8 = 0x41;
+
+const SS58_PREFIX: &[u8] = b"SS58PRE";
+/// The generic substrate network prefix, SS58 addresses are stored re-encoded with it.
+const SS58_GENERIC_PREFIX: u8 = 42;
+
+// longest base58 spelling of each payload, checked before the quadratic decoding
+const SS58_MAX_LENGTH: usize = 48;
+const SOLANA_MAX_LENGTH: usize = 44;
+const TRON_MAX_LENGTH: usize = 34;
+
+/// Longest address accepted on any chain, the CAIP-10 account address limit.
+pub const MAX_ADDRESS_LENGTH: usize = 128;
+/// Longest ownership proof, a recoverable secp256k1 signature.
+pub const MAX_SIGNATURE_LENGTH: usize = 65;
+
+/// CAIP-2 ids of the chains the legacy `btc`, `eth` and `eos` names refer to.
+pub const BTC_CHAIN_ID: &[u8] = b"bip122:000000000019d6689c085ae165831e93";
+pub const ETH_CHAIN_ID: &[u8] = b"eip155:1";
+pub const EOS_CHAIN_ID: &[u8] = b"eosio:aca376f206b8fc25a6ed44dbdc66547c";
+
+/// Chain families addresses can be linked from, picked by the CAIP-2 namespace.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChainType {
+    Btc,
+    Eth,
+    Eos,
+    Polkadot,
+    Solana,
+    Tron,
+    /// Any other namespace, addresses only have to be CAIP-10 shaped.
+    Other,
+}
+
+impl ChainType {
+    /// The chain type of a CAIP-2 chain id, `None` if the id is malformed.
+    pub fn from_chain_id(chain_id: &[u8]) -> Option<Self> {
+        if !is_valid_chain_id(chain_id) {
+            return None;
+        }
+
+        let namespace = chain_id.split(|b| *b == b':').next()?;
+        Some(match namespace {
+            b"bip122" => ChainType::Btc,
+            b"eip155" => ChainType::Eth,
+            b"eosio" => ChainType::Eos,
+            b"polkadot" => ChainType::Polkadot,
+            b"solana" => ChainType::Solana,
+            b"tron" => ChainType::Tron,
+            _ => ChainType::Other,
+        })
+    }
+
+    pub fn is_valid_address(&self, address: &[u8]) -> bool {
+        match self {
+            ChainType::Btc => is_valid_btc_address(address),
+            ChainType::Eth => is_valid_eth_address(address),
+            ChainType::Eos => is_valid_eos_address(address),
+            ChainType::Polkadot => is_valid_ss58_address(address),
+            ChainType::Solana => is_valid_solana_address(address),
+            ChainType::Tron => is_valid_tron_address(address),
+            ChainType::Other => is_valid_account_address(address),
+        }
+    }
+
+    /// Whether linking an address of this chain requires a signature from its key.
+    pub fn is_verifiable(&self) -> bool {
+        match self {
+            ChainType::Btc | ChainType::Eth | ChainType::Polkadot | ChainType::Solana => true,
+            ChainType::Eos | ChainType::Tron | ChainType::Other => false,
+        }
+    }
+
+    /// The canonical spelling of a valid address, used to store and index it.
+    pub fn normalize_address(&self, address: &[u8]) -> Vec<u8> {
+        match self {
+            ChainType::Eth => {
+                let mut normalized = b"0x".to_vec();
+                normalized.append(&mut normalize_eth_address(address));
+                normalized
+            }
+            // bech32 is case insensitive, base58 is not
+            ChainType::Btc if from_bech32(address).is_ok() => {
+                address.iter().map(|b| b.to_ascii_lowercase()).collect()
+            }
+            // the same key under any network prefix
+            ChainType::Polkadot => match ss58_public_key(address) {
+                Some(public) => to_ss58(&public),
+                None => address.to_vec(),
+            },
+            _ => address.to_vec(),
+        }
+    }
+}
+
+/// Map the legacy `btc`, `eth` and `eos` names to their CAIP-2 chain ids.
+pub fn canonical_chain_id(name: &[u8]) -> Vec<u8> {
+    match name {
+        b"btc" => BTC_CHAIN_ID.to_vec(),
+        b"eth" => ETH_CHAIN_ID.to_vec(),
+        b"eos" => EOS_CHAIN_ID.to_vec(),
+        _ => name.to_vec(),
+    }
+}
+
+/// CAIP-2: `[-a-z0-9]{3,8}` namespace, a colon and a `[-_a-zA-Z0-9]{1,32}` reference.
+pub fn is_valid_chain_id(chain_id: &[u8]) -> bool {
+    let mut parts = chain_id.splitn(2, |b| *b == b':');
+    let (namespace, reference) = match (parts.next(), parts.next()) {
+        (Some(namespace), Some(reference)) => (namespace, reference),
+        _ => return false,
+    };
+
+    namespace.len() >= 3
+        && namespace.len() <= 8
+        && namespace
+            .iter()
+            .all(|b| *b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit())
+        && !reference.is_empty()
+        && reference.len() <= 32
+        && reference
+            .iter()
+            .all(|b| *b == b'-' || *b == b'_' || b.is_ascii_alphanumeric())
+}
 
 #[rustfmt::skip]
 static BASE58_DIGITS: [Option<u8>; 128] = [
@@ -33,7 +171,7 @@ pub fn from(data: Vec<u8>) -> Result<Vec<u8>, &'static str> {
     // Build in base 256
     for d58 in data.clone() {
         // Compute "X = X * 58 + next_digit" in base 256
-        if d58 as usize > BASE58_DIGITS.len() {
+        if d58 as usize >= BASE58_DIGITS.len() {
             return Err("BadByte");
         }
         let mut carry = match BASE58_DIGITS[d58 as usize] {
@@ -61,43 +199,358 @@ pub fn from(data: Vec<u8>) -> Result<Vec<u8>, &'static str> {
     Ok(ret)
 }
 
-pub fn is_valid_eth_address(address: Vec<u8>) -> bool {
-    let mut is_valid = true;
+pub fn to_base58(data: &[u8]) -> Vec<u8> {
+    // base58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::new();
+    for &d256 in data {
+        let mut carry = d256 as u32;
+        for d58 in digits.iter_mut() {
+            carry += (*d58 as u32) << 8;
+            *d58 = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
 
-    if address.len() < 40 {
-        return false;
-    };
+    // Copy leading zeroes directly
+    let mut ret: Vec<u8> = data
+        .iter()
+        .take_while(|&x| *x == 0)
+        .map(|_| BASE58_CHARS[0])
+        .collect();
+    ret.extend(digits.iter().rev().map(|&d| BASE58_CHARS[d as usize]));
+    ret
+}
 
-    for b in address {
-        if !ETH_CHARS.contains(&b) {
-            is_valid = false;
+pub fn to_hex(data: &[u8]) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(data.len() * 2);
+    for b in data {
+        ret.push(ETH_CHARS[(b >> 4) as usize]);
+        ret.push(ETH_CHARS[(b & 0x0f) as usize]);
+    }
+    ret
+}
+
+pub fn to_decimal(mut n: u64) -> Vec<u8> {
+    let mut ret = Vec::new();
+    loop {
+        ret.push(b'0' + (n % 10) as u8);
+        n /= 10;
+        if n == 0 {
             break;
         }
     }
+    ret.reverse();
+    ret
+}
 
-    is_valid
+/// Decode base58 and verify the trailing 4-byte double-SHA256 checksum, returns the payload.
+pub fn from_base58check(data: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+    let mut decoded = from(data)?;
+    if decoded.len() < 5 {
+        return Err("TooShort");
+    }
+
+    let checksum = decoded.split_off(decoded.len() - 4);
+    if sha2_256(&sha2_256(&decoded))[..4] != checksum[..] {
+        return Err("BadChecksum");
+    }
+    Ok(decoded)
 }
 
-pub fn is_valid_eos_address(address: Vec<u8>) -> bool {
-    let mut is_valid = true;
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
 
-    if address.len() > 12 {
-        return false;
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = (chk & 0x01ff_ffff) << 5 ^ *v as u32;
+        for (i, g) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Decode a bech32 or bech32m string into its lowercase hrp, 5-bit data and checksum constant.
+pub fn from_bech32(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>, u32), &'static str> {
+    if data.len() < 8 || data.len() > 90 {
+        return Err("BadLength");
+    }
+
+    let has_lower = data.iter().any(|b| b.is_ascii_lowercase());
+    let has_upper = data.iter().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("MixedCase");
+    }
+    let data: Vec<u8> = data.iter().map(|b| b.to_ascii_lowercase()).collect();
+
+    let pos = data.iter().rposition(|&b| b == b'1').ok_or("NoSeparator")?;
+    if pos == 0 || pos + 7 > data.len() {
+        return Err("BadSeparator");
+    }
+
+    let hrp = data[..pos].to_vec();
+    if hrp.iter().any(|&b| b < 33 || b > 126) {
+        return Err("BadHrp");
+    }
+
+    let mut values = Vec::with_capacity(data.len() - pos - 1);
+    for b in &data[pos + 1..] {
+        let v = BECH32_CHARS.iter().position(|c| c == b).ok_or("BadByte")?;
+        values.push(v as u8);
+    }
+
+    // expand the hrp ahead of the data for the checksum
+    let mut expanded: Vec<u8> = hrp.iter().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.iter().map(|b| b & 0x1f));
+    expanded.extend_from_slice(&values);
+
+    let constant = match bech32_polymod(&expanded) {
+        BECH32_CONST => BECH32_CONST,
+        BECH32M_CONST => BECH32M_CONST,
+        _ => return Err("BadChecksum"),
     };
 
-    for b in address {
-        if !EOS_CHARS.contains(&b) {
-            is_valid = false;
-            break;
+    values.truncate(values.len() - 6);
+    Ok((hrp, values, constant))
+}
+
+fn convert_bits(data: &[u8], from: u32, to: u32) -> Result<Vec<u8>, &'static str> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1 << to) - 1;
+    let mut ret = Vec::new();
+
+    for &v in data {
+        if (v as u32) >> from != 0 {
+            return Err("BadByte");
+        }
+        acc = (acc << from) | v as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            ret.push(((acc >> bits) & max) as u8);
+        }
+    }
+
+    // only zero padding shorter than a group is allowed
+    if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return Err("BadPadding");
+    }
+    Ok(ret)
+}
+
+/// Decode a segwit address into its witness version and program (BIP-173 and BIP-350).
+pub fn from_segwit(data: &[u8]) -> Result<(u8, Vec<u8>), &'static str> {
+    let (hrp, values, constant) = from_bech32(data)?;
+    if !BTC_HRPS.contains(&&hrp[..]) {
+        return Err("BadHrp");
+    }
+    if values.is_empty() || values[0] > 16 {
+        return Err("BadVersion");
+    }
+
+    let version = values[0];
+    let program = convert_bits(&values[1..], 5, 8)?;
+    if program.len() < 2 || program.len() > 40 {
+        return Err("BadProgram");
+    }
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err("BadProgram");
+    }
+
+    // version 0 uses bech32, later versions bech32m
+    let expected = if version == 0 { BECH32_CONST } else { BECH32M_CONST };
+    if constant != expected {
+        return Err("BadChecksum");
+    }
+    Ok((version, program))
+}
+
+/// The public key hash a bitcoin address pays to, only P2PKH and P2WPKH have one.
+pub fn btc_pubkey_hash(address: &[u8]) -> Option<Vec<u8>> {
+    if let Ok((0, program)) = from_segwit(address) {
+        if program.len() == 20 {
+            return Some(program);
         }
+        return None;
+    }
+    if address.len() > BTC_BASE58_MAX_LENGTH {
+        return None;
     }
 
-    is_valid
+    let payload = from_base58check(address.to_vec()).ok()?;
+    if payload.len() == 21 && BTC_P2PKH_VERSIONS.contains(&payload[0]) {
+        return Some(payload[1..].to_vec());
+    }
+    None
+}
+
+pub fn is_valid_btc_address(address: &[u8]) -> bool {
+    if from_segwit(address).is_ok() {
+        return true;
+    }
+    if address.len() > BTC_BASE58_MAX_LENGTH {
+        return false;
+    }
+
+    match from_base58check(address.to_vec()) {
+        Ok(payload) => {
+            payload.len() == 21
+                && (BTC_P2PKH_VERSIONS.contains(&payload[0])
+                    || BTC_P2SH_VERSIONS.contains(&payload[0]))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Lowercase an ethereum address and strip its `0x` prefix.
+pub fn normalize_eth_address(address: &[u8]) -> Vec<u8> {
+    let address = if address.starts_with(b"0x") {
+        &address[2..]
+    } else {
+        address
+    };
+    address.iter().map(|b| b.to_ascii_lowercase()).collect()
+}
+
+/// Hex address with optional `0x` prefix, mixed-case ones must carry a valid EIP-55 checksum.
+pub fn is_valid_eth_address(address: &[u8]) -> bool {
+    let address = if address.starts_with(b"0x") {
+        &address[2..]
+    } else {
+        address
+    };
+    if address.len() != 40 {
+        return false;
+    }
+
+    let lower = normalize_eth_address(address);
+    if !lower.iter().all(|b| ETH_CHARS.contains(b)) {
+        return false;
+    }
+
+    let has_lower = address.iter().any(|b| b.is_ascii_lowercase());
+    let has_upper = address.iter().any(|b| b.is_ascii_uppercase());
+    if !(has_lower && has_upper) {
+        return true;
+    }
+
+    // a letter is uppercase iff the matching nibble of keccak(lowercase address) is >= 8
+    let hash = keccak_256(&lower);
+    address.iter().enumerate().all(|(i, b)| {
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        !b.is_ascii_alphabetic() || b.is_ascii_uppercase() == (nibble >= 8)
+    })
+}
+
+/// EOS account names: up to 12 chars of `a-z1-5.` plus an optional 13th of `a-j1-5`,
+/// never ending with a dot.
+pub fn is_valid_eos_address(address: &[u8]) -> bool {
+    let len = address.len();
+    if len == 0 || len > 13 || address[len - 1] == b'.' {
+        return false;
+    }
+    if len == 13 && !EOS_LAST_CHARS.contains(&address[12]) {
+        return false;
+    }
+
+    address.iter().all(|b| EOS_CHARS.contains(b))
+}
+
+fn ss58_checksum(payload: &[u8]) -> Vec<u8> {
+    let mut data = SS58_PREFIX.to_vec();
+    data.extend_from_slice(payload);
+    blake2b(64, &[], &data).as_bytes()[..2].to_vec()
+}
+
+/// SS58 address with a single byte network prefix, returns the 32-byte public key.
+pub fn ss58_public_key(address: &[u8]) -> Option<Vec<u8>> {
+    if address.len() > SS58_MAX_LENGTH {
+        return None;
+    }
+
+    let decoded = from(address.to_vec()).ok()?;
+    // prefix || public key || 2-byte checksum
+    if decoded.len() != 35 || decoded[0] >= 64 {
+        return None;
+    }
+    if ss58_checksum(&decoded[..33]) != decoded[33..] {
+        return None;
+    }
+    Some(decoded[1..33].to_vec())
+}
+
+/// SS58 address of a 32-byte public key under the generic substrate prefix.
+pub fn to_ss58(public: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(35);
+    data.push(SS58_GENERIC_PREFIX);
+    data.extend_from_slice(public);
+    let mut checksum = ss58_checksum(&data);
+    data.append(&mut checksum);
+    to_base58(&data)
+}
+
+pub fn is_valid_ss58_address(address: &[u8]) -> bool {
+    ss58_public_key(address).is_some()
+}
+
+/// Solana addresses are base58 encoded ed25519 public keys.
+pub fn solana_public_key(address: &[u8]) -> Option<Vec<u8>> {
+    if address.len() > SOLANA_MAX_LENGTH {
+        return None;
+    }
+
+    match from(address.to_vec()) {
+        Ok(decoded) if decoded.len() == 32 => Some(decoded),
+        _ => None,
+    }
+}
+
+pub fn is_valid_solana_address(address: &[u8]) -> bool {
+    solana_public_key(address).is_some()
+}
+
+/// Tron addresses are base58check over `0x41 || 20-byte hash`.
+pub fn is_valid_tron_address(address: &[u8]) -> bool {
+    if address.len() > TRON_MAX_LENGTH {
+        return false;
+    }
+
+    match from_base58check(address.to_vec()) {
+        Ok(payload) => payload.len() == 21 && payload[0] == TRON_VERSION,
+        Err(_) => false,
+    }
+}
+
+/// CAIP-10 account address: `[-.%a-zA-Z0-9]{1,128}`.
+pub fn is_valid_account_address(address: &[u8]) -> bool {
+    !address.is_empty()
+        && address.len() <= 128
+        && address
+            .iter()
+            .all(|b| *b == b'-' || *b == b'.' || *b == b'%' || b.is_ascii_alphanumeric())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::from;
+    use super::{
+        btc_pubkey_hash, canonical_chain_id, from, from_base58check, is_valid_account_address,
+        is_valid_btc_address, is_valid_eos_address, is_valid_eth_address, is_valid_solana_address,
+        is_valid_ss58_address, is_valid_tron_address, to_base58, to_decimal, to_hex, to_ss58,
+        ChainType, EOS_CHAIN_ID, ETH_CHAIN_ID,
+    };
     #[test]
     fn test_from() {
         let s = String::from("mjKE11gjVN4JaC9U8qL6ZB5vuEBgmwik7b");
@@ -107,4 +560,149 @@ mod tests {
         ];
         assert_eq!(from(s.as_bytes().to_vec()).unwrap(), v);
     }
+
+    #[test]
+    fn test_to_base58() {
+        let s = b"mjKE11gjVN4JaC9U8qL6ZB5vuEBgmwik7b".to_vec();
+        assert_eq!(to_base58(&from(s.clone()).unwrap()), s);
+        assert_eq!(to_base58(&[0, 0, 1]), b"112".to_vec());
+    }
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(&[0x0a, 0xff, 0x10]), b"0aff10".to_vec());
+    }
+
+    #[test]
+    fn test_to_decimal() {
+        assert_eq!(to_decimal(0), b"0".to_vec());
+        assert_eq!(to_decimal(1024), b"1024".to_vec());
+    }
+
+    #[test]
+    fn test_base58check() {
+        assert!(from_base58check(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".to_vec()).is_ok());
+        assert_eq!(
+            from_base58check(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMh".to_vec()),
+            Err("BadChecksum")
+        );
+    }
+
+    #[test]
+    fn test_btc_address() {
+        // P2PKH, testnet P2PKH and P2SH
+        assert!(is_valid_btc_address(b"1N75dvASxn1CCjaeguyqvwXLXJun9e54mM"));
+        assert!(is_valid_btc_address(b"mjKE11gjVN4JaC9U8qL6ZB5vuEBgmwik7b"));
+        assert!(is_valid_btc_address(b"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"));
+        // bech32 P2WPKH, in either case but never mixed
+        assert!(is_valid_btc_address(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"));
+        assert!(is_valid_btc_address(b"BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"));
+        assert!(!is_valid_btc_address(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kV8f3t4"));
+        // bech32m taproot
+        assert!(is_valid_btc_address(
+            b"bc1p09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09usfkr0ra"
+        ));
+        // witness v0 encoded with bech32m, v1 with bech32, foreign hrp
+        assert!(!is_valid_btc_address(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"));
+        assert!(!is_valid_btc_address(
+            b"bc1p09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09usu2nrxl"
+        ));
+        assert!(!is_valid_btc_address(b"ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9"));
+        // bad checksum
+        assert!(!is_valid_btc_address(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMh"));
+        // too long for a base58check address
+        assert!(!is_valid_btc_address(&[b'1'; 36]));
+    }
+
+    #[test]
+    fn test_btc_pubkey_hash() {
+        let hash = btc_pubkey_hash(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH").unwrap();
+        assert_eq!(
+            btc_pubkey_hash(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+            Some(hash)
+        );
+        assert_eq!(btc_pubkey_hash(b"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"), None);
+    }
+
+    #[test]
+    fn test_eth_address() {
+        assert!(is_valid_eth_address(b"5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(is_valid_eth_address(b"0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB"));
+        assert!(is_valid_eth_address(b"5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert!(is_valid_eth_address(b"5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"));
+        // broken EIP-55 checksum
+        assert!(!is_valid_eth_address(b"5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"));
+        assert!(!is_valid_eth_address(b"5aaeb6053f3e94c9b9a09f33669435e7ef1beaed00"));
+        assert!(!is_valid_eth_address(b"5gaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+    }
+
+    #[test]
+    fn test_eos_address() {
+        assert!(is_valid_eos_address(b"praqianchang"));
+        assert!(is_valid_eos_address(b"eosio.token"));
+        assert!(is_valid_eos_address(b"abcdefghijklj"));
+        assert!(!is_valid_eos_address(b"abcdefghijklm"));
+        assert!(!is_valid_eos_address(b"abc."));
+        assert!(!is_valid_eos_address(b"abc6"));
+        assert!(!is_valid_eos_address(b""));
+    }
+
+    #[test]
+    fn test_other_addresses() {
+        assert!(is_valid_ss58_address(b"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"));
+        assert!(is_valid_ss58_address(b"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"));
+        assert!(!is_valid_ss58_address(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"));
+        // broken blake2 checksum
+        assert!(!is_valid_ss58_address(b"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"));
+        assert!(!is_valid_ss58_address(&[b'5'; 49]));
+        assert!(is_valid_solana_address(b"9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"));
+        assert!(!is_valid_solana_address(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"));
+        assert!(!is_valid_solana_address(&[b'9'; 45]));
+        assert!(is_valid_tron_address(b"TJRabPrwbZy45sbavfcjinPJC18kjpRTv8"));
+        assert!(!is_valid_tron_address(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"));
+        assert!(!is_valid_tron_address(&[b'T'; 35]));
+        assert!(is_valid_account_address(b"cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0"));
+        assert!(!is_valid_account_address(b"a:b"));
+    }
+
+    #[test]
+    fn test_chain_type() {
+        assert_eq!(ChainType::from_chain_id(ETH_CHAIN_ID), Some(ChainType::Eth));
+        assert_eq!(ChainType::from_chain_id(b"eip155:137"), Some(ChainType::Eth));
+        assert_eq!(
+            ChainType::from_chain_id(b"cosmos:cosmoshub-4"),
+            Some(ChainType::Other)
+        );
+        assert_eq!(ChainType::from_chain_id(b"eth"), None);
+        assert_eq!(ChainType::from_chain_id(b"EIP155:1"), None);
+        assert_eq!(ChainType::from_chain_id(b"eip155:"), None);
+        assert_eq!(canonical_chain_id(b"eos"), EOS_CHAIN_ID.to_vec());
+        assert!(ChainType::Eos.is_valid_address(b"praqianchang"));
+        assert!(!ChainType::Eth.is_valid_address(b"praqianchang"));
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!(
+            ChainType::Eth.normalize_address(b"5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            b"0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_vec()
+        );
+        assert_eq!(
+            ChainType::Btc.normalize_address(b"BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"),
+            b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_vec()
+        );
+        assert_eq!(
+            ChainType::Btc.normalize_address(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"),
+            b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".to_vec()
+        );
+        // polkadot and generic substrate spellings of the same key
+        let generic = b"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_vec();
+        assert_eq!(
+            ChainType::Polkadot
+                .normalize_address(b"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"),
+            generic
+        );
+        assert_eq!(ChainType::Polkadot.normalize_address(&generic), generic);
+        assert_eq!(to_ss58(&super::ss58_public_key(&generic).unwrap()), generic);
+    }
 }
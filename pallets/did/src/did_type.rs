@@ -0,0 +1,115 @@
+use super::*;
+
+/// Longest DID type, it prefixes every DID of the type.
+pub const MAX_DID_TYPE_LENGTH: usize = 8;
+/// Longest human readable name of a DID type.
+pub const MAX_DID_TYPE_NAME_LENGTH: usize = 32;
+/// The maximum number of accounts a DID type may restrict creation to.
+pub const MAX_DID_TYPE_CREATORS: usize = 32;
+
+/// Bytes a DID appends to its type, see `generate_did`.
+const DID_HASH_LENGTH: usize = 24;
+
+/// What DIDs of a type may do beyond holding funds and referring others.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Capability {
+    /// Publish ads and pay for them from the DID.
+    PublishAds,
+}
+
+/// Who may create DIDs of a type.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub enum DidTypeCreators<AccountId> {
+    Anyone,
+    /// Only these accounts, on behalf of whoever controls the new DID.
+    Accounts(Vec<AccountId>),
+}
+
+/// A registered DID type.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct DidTypeInfo<AccountId, Balance> {
+    pub name: Vec<u8>,
+    pub creators: DidTypeCreators<AccountId>,
+    /// Reserved from the creator until the DID is deactivated.
+    pub deposit: Balance,
+    pub capabilities: Vec<Capability>,
+}
+
+impl<AccountId: PartialEq, Balance> DidTypeInfo<AccountId, Balance> {
+    pub fn may_create(&self, who: &AccountId) -> bool {
+        match &self.creators {
+            DidTypeCreators::Anyone => true,
+            DidTypeCreators::Accounts(accounts) => accounts.contains(who),
+        }
+    }
+
+    pub fn has(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    pub fn creator_count(&self) -> usize {
+        match &self.creators {
+            DidTypeCreators::Anyone => 0,
+            DidTypeCreators::Accounts(accounts) => accounts.len(),
+        }
+    }
+}
+
+/// The types DIDs were created with before the registry, `4` marked advertisers.
+pub fn default_did_types<AccountId, Balance: Zero>(
+) -> Vec<(Vec<u8>, DidTypeInfo<AccountId, Balance>)> {
+    sp_std::vec![
+        (
+            b"1".to_vec(),
+            DidTypeInfo {
+                name: b"user".to_vec(),
+                creators: DidTypeCreators::Anyone,
+                deposit: Zero::zero(),
+                capabilities: Vec::new(),
+            },
+        ),
+        (
+            b"4".to_vec(),
+            DidTypeInfo {
+                name: b"advertiser".to_vec(),
+                creators: DidTypeCreators::Anyone,
+                deposit: Zero::zero(),
+                capabilities: sp_std::vec![Capability::PublishAds],
+            },
+        ),
+    ]
+}
+
+impl<T: Config> Module<T> {
+    /// The type a raw DID was created with.
+    pub fn did_type_of(did: &[u8]) -> &[u8] {
+        &did[..did.len().saturating_sub(DID_HASH_LENGTH)]
+    }
+
+    /// True if the type of a raw DID is registered with `capability`.
+    pub fn has_capability(did: &[u8], capability: Capability) -> bool {
+        Self::did_type(Self::did_type_of(did)).map_or(false, |info| info.has(capability))
+    }
+
+    pub(crate) fn ensure_valid_did_type(
+        did_type: &[u8],
+        info: &mut DidTypeInfo<T::AccountId, T::Balance>,
+    ) -> DispatchResult {
+        ensure!(
+            !did_type.is_empty() && did_type.len() <= MAX_DID_TYPE_LENGTH,
+            Error::<T>::InvalidType
+        );
+        ensure!(
+            !info.name.is_empty() && info.name.len() <= MAX_DID_TYPE_NAME_LENGTH,
+            Error::<T>::InvalidType
+        );
+        ensure!(
+            info.creator_count() <= MAX_DID_TYPE_CREATORS,
+            Error::<T>::InvalidType
+        );
+
+        info.capabilities.sort();
+        info.capabilities.dedup();
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+/// Method prefix of the textual form of a DID, followed by the base58 encoded raw DID.
+pub const DID_METHOD: &[u8] = b"did:ad3:";
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct VerificationMethod {
+    pub id: Vec<u8>,
+    pub method_type: Vec<u8>,
+    pub controller: Vec<u8>,
+    pub public_key_multibase: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct DidService {
+    pub id: Vec<u8>,
+    pub service_type: Vec<u8>,
+    pub service_endpoint: Vec<u8>,
+}
+
+/// A W3C DID Core document, every field is UTF-8 text ready to be rendered as JSON.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct DidDocument {
+    pub id: Vec<u8>,
+    pub controller: Vec<u8>,
+    pub verification_method: Vec<VerificationMethod>,
+    pub authentication: Vec<Vec<u8>>,
+    pub service: Vec<DidService>,
+    pub also_known_as: Vec<Vec<u8>>,
+}
+
+impl KeyType {
+    fn verification_method_type(&self) -> &'static [u8] {
+        match self {
+            KeyType::Sr25519 => b"Sr25519VerificationKey2020",
+            KeyType::Ed25519 => b"Ed25519VerificationKey2020",
+            KeyType::Ecdsa => b"EcdsaSecp256k1VerificationKey2019",
+        }
+    }
+
+    /// Varint multicodec of the public key type, prefixing the key in its multikey encoding.
+    fn multicodec(&self) -> &'static [u8] {
+        match self {
+            KeyType::Sr25519 => &[0xef, 0x01],
+            KeyType::Ed25519 => &[0xed, 0x01],
+            KeyType::Ecdsa => &[0xe7, 0x01],
+        }
+    }
+}
+
+impl<Moment> AuthKey<Moment> {
+    /// The key as a base58btc multikey, e.g. `z6Mk...` for ed25519 keys.
+    pub fn multibase(&self) -> Vec<u8> {
+        let mut multikey = self.key_type.multicodec().to_vec();
+        multikey.extend_from_slice(&self.public);
+
+        let mut multibase = b"z".to_vec();
+        multibase.append(&mut check::to_base58(&multikey));
+        multibase
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Textual form of a raw DID, e.g. `did:ad3:...`.
+    pub fn did_uri(did: &[u8]) -> Vec<u8> {
+        let mut uri = DID_METHOD.to_vec();
+        uri.append(&mut check::to_base58(did));
+        uri
+    }
+
+    /// Resolve a `did:ad3:...` string into its DID document.
+    pub fn resolve(did: Vec<u8>) -> Option<DidDocument> {
+        if !did.starts_with(DID_METHOD) {
+            return None;
+        }
+
+        let raw = check::from(did[DID_METHOD.len()..].to_vec()).ok()?;
+        Self::resolve_by_key(T::Hashing::hash(&raw))
+    }
+
+    /// The `did:ad3:...` string of the DID a verified external address is linked to.
+    ///
+    /// `chain_id` may be a CAIP-2 id or a legacy `btc`, `eth` or `eos` name, the address is
+    /// matched in any spelling of it, e.g. with or without an EIP-55 checksum.
+    pub fn lookup_address(chain_id: Vec<u8>, address: Vec<u8>) -> Option<Vec<u8>> {
+        let chain_id = check::canonical_chain_id(&chain_id);
+        let chain = check::ChainType::from_chain_id(&chain_id)?;
+
+        let user_key = Self::external_address_owner(&chain_id, chain.normalize_address(&address))?;
+        Some(Self::did_uri(&Self::metadata(&user_key).did))
+    }
+
+    /// Resolve the DID document of a user key.
+    pub fn resolve_by_key(user_key: T::Hash) -> Option<DidDocument> {
+        if !<Metadata<T>>::contains_key(&user_key) {
+            return None;
+        }
+
+        let metadata = Self::metadata(&user_key);
+        let id = Self::did_uri(&metadata.did);
+
+        let mut verification_method = Vec::new();
+        let mut authentication = Vec::new();
+        for key in metadata.keys.iter() {
+            // named after the key itself, so removing other keys never renames it
+            let public_key_multibase = key.multibase();
+            let mut key_id = id.clone();
+            key_id.push(b'#');
+            key_id.extend_from_slice(&public_key_multibase);
+
+            authentication.push(key_id.clone());
+            verification_method.push(VerificationMethod {
+                id: key_id,
+                method_type: key.key_type.verification_method_type().to_vec(),
+                controller: id.clone(),
+                public_key_multibase,
+            });
+        }
+
+        // CAIP-10 account ids of the linked external addresses
+        let mut also_known_as = Vec::new();
+        for (chain_id, addresses) in metadata.external_addresses.iter() {
+            for address in addresses {
+                let mut account_id = chain_id.clone();
+                account_id.push(b':');
+                account_id.extend_from_slice(address);
+                also_known_as.push(account_id);
+            }
+        }
+
+        let service = Self::services(&user_key)
+            .into_iter()
+            .map(|s| {
+                let mut service_id = id.clone();
+                service_id.push(b'#');
+                service_id.extend_from_slice(&s.id);
+
+                DidService {
+                    id: service_id,
+                    service_type: s.service_type,
+                    service_endpoint: s.endpoint,
+                }
+            })
+            .collect();
+
+        Some(DidDocument {
+            controller: id.clone(),
+            id,
+            verification_method,
+            authentication,
+            service,
+            also_known_as,
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+use sp_runtime::{
+    traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension},
+    transaction_validity::{
+        TransactionLongevity, TransactionPriority, TransactionValidity, TransactionValidityError,
+        ValidTransaction,
+    },
+};
+use sp_std::{fmt, marker::PhantomData};
+
+/// Pays for a transaction with the fee charging extension `S`, unless the signer's DID may
+/// make the call for free and uses up one call of its quota for the era instead.
+///
+/// Encodes as `S` does, so it takes the place of `S` in the runtime's `SignedExtra`.
+///
+/// A feeless transaction provides a tag for the DID's next call of the era, so the pool holds
+/// one of them per DID at a time and drops it when the era ends. It ranks below every
+/// transaction paying a fee.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct FeelessOrCharge<T: Config + Send + Sync, S>(S, PhantomData<T>);
+
+impl<T: Config + Send + Sync, S> From<S> for FeelessOrCharge<T, S> {
+    fn from(charge: S) -> Self {
+        FeelessOrCharge(charge, PhantomData)
+    }
+}
+
+impl<T: Config + Send + Sync, S: fmt::Debug> fmt::Debug for FeelessOrCharge<T, S> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FeelessOrCharge({:?})", self.0)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T, S> SignedExtension for FeelessOrCharge<T, S>
+where
+    T: Config + Send + Sync,
+    S: SignedExtension<AccountId = T::AccountId, Call = <T as frame_system::Config>::Call>,
+{
+    const IDENTIFIER: &'static str = "FeelessOrCharge";
+    type AccountId = T::AccountId;
+    type Call = S::Call;
+    type AdditionalSigned = S::AdditionalSigned;
+    /// `None` if the call was made without fees.
+    type Pre = Option<S::Pre>;
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        self.0.additional_signed()
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match <Module<T>>::feeless_caller(who, call) {
+            Some(user_key) => {
+                let (era, index) = <Module<T>>::next_feeless_call(&user_key);
+                ValidTransaction::with_tag_prefix("DidFeeless")
+                    .priority(TransactionPriority::min_value())
+                    .and_provides((user_key, era, index))
+                    .longevity(<Module<T>>::feeless_longevity())
+                    .build()
+            }
+            None => self.0.validate(who, call, info, len),
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        match <Module<T>>::feeless_caller(who, call) {
+            Some(user_key) => {
+                <Module<T>>::use_feeless_call(user_key);
+                Ok(None)
+            }
+            None => self.0.pre_dispatch(who, call, info, len).map(Some),
+        }
+    }
+
+    fn post_dispatch(
+        pre: Self::Pre,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        match pre {
+            Some(pre) => S::post_dispatch(pre, info, post_info, len, result),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// The era feeless calls are counted in, all of them when `FeelessEra` is zero.
+    fn feeless_era() -> T::BlockNumber {
+        let length = T::FeelessEra::get();
+        if length.is_zero() {
+            return Zero::zero();
+        }
+        <frame_system::Module<T>>::block_number() / length
+    }
+
+    /// Blocks left before the era ends, when a feeless call's quota may no longer hold.
+    fn feeless_longevity() -> TransactionLongevity {
+        let length = T::FeelessEra::get();
+        if length.is_zero() {
+            return TransactionLongevity::max_value();
+        }
+        let now = <frame_system::Module<T>>::block_number();
+        (length - now % length).saturated_into()
+    }
+
+    /// The era and the index in it of the DID's next feeless call.
+    fn next_feeless_call(user_key: &T::Hash) -> (T::BlockNumber, u32) {
+        let current = Self::feeless_era();
+        let (era, used) = Self::feeless_usage(user_key);
+        (current, if era == current { used } else { 0 })
+    }
+
+    /// Feeless calls a DID may make per era, one for every `FeelessLockUnit` it has locked.
+    pub fn feeless_quota(user_key: T::Hash) -> u32 {
+        let unit = T::FeelessLockUnit::get();
+        match Self::metadata(&user_key).locked_records {
+            Some(records) if !unit.is_zero() => (records.locked_funds() / unit).saturated_into(),
+            _ => 0,
+        }
+    }
+
+    /// Feeless calls a DID has left this era.
+    pub fn feeless_calls_left(user_key: T::Hash) -> u32 {
+        let (_, used) = Self::next_feeless_call(&user_key);
+        Self::feeless_quota(user_key).saturating_sub(used)
+    }
+
+    /// The DID of `who` if it may make `call` without fees.
+    pub(crate) fn feeless_caller(
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::Call,
+    ) -> Option<T::Hash> {
+        if !T::FeelessCalls::filter(call) {
+            return None;
+        }
+        let (user_key, _) = Self::identity(who)?;
+        if Self::feeless_calls_left(user_key) == 0 {
+            return None;
+        }
+        Some(user_key)
+    }
+
+    pub(crate) fn use_feeless_call(user_key: T::Hash) {
+        let current = Self::feeless_era();
+        <FeelessUsage<T>>::mutate(&user_key, |(era, used)| {
+            if *era != current {
+                *era = current;
+                *used = 0;
+            }
+            *used = used.saturating_add(1);
+        });
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+/// A DID carried over from another chain at genesis, `create` would have made the same DID
+/// from `pubkey` and `did_type`.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct GenesisDid<AccountId, Hash, Balance> {
+    pub pubkey: Vec<u8>,
+    pub address: AccountId,
+    pub did_type: Vec<u8>,
+    /// User key of a DID listed earlier, the default hash for DIDs without a superior.
+    pub superior: Hash,
+    pub social_account: Option<Hash>,
+    /// Reserved from `address` in one matured lock position.
+    pub locked: Balance,
+}
+
+impl<T: Config> Module<T> {
+    /// Create a DID with every index `create` and `lock` maintain, the superior's
+    /// subordinates and quota included. Superiors other than the genesis account need locked
+    /// funds and quota left, as they do for `create`.
+    ///
+    /// The social account is recorded as attested by the genesis account, its platform and
+    /// handle stay unknown on this chain.
+    pub(crate) fn import_did(
+        record: &GenesisDid<T::AccountId, T::Hash, T::Balance>,
+    ) -> Result<T::Hash, &'static str> {
+        ensure!(
+            <DidTypes<T>>::contains_key(&record.did_type),
+            "unknown did type"
+        );
+
+        let did = Self::generate_did(&record.pubkey, &record.did_type);
+        let user_key = T::Hashing::hash(&did);
+        ensure!(!<Metadata<T>>::contains_key(&user_key), "did listed twice");
+        ensure!(
+            !<Identity<T>>::contains_key(&record.address),
+            "account listed twice"
+        );
+
+        let has_superior = <Metadata<T>>::contains_key(&record.superior);
+        ensure!(
+            has_superior || record.superior == Default::default(),
+            "superior not listed before its subordinates"
+        );
+        let mut superior = None;
+        if has_superior {
+            let mut metadata = Self::metadata(&record.superior);
+            if metadata.address != Self::genesis_account() {
+                let subordinate_count = metadata.subordinate_count.saturating_add(1);
+                let locked_records = metadata.locked_records.as_mut().ok_or(Error::<T>::NotLockFunds)?;
+                ensure!(subordinate_count <= locked_records.max_quota, Error::<T>::ExceedsMaxQuota);
+                Self::refresh_quota(locked_records, subordinate_count);
+                metadata.subordinate_count = subordinate_count;
+                superior = Some(metadata);
+            }
+        }
+        if let Some(social_hash) = record.social_account {
+            ensure!(
+                !<SocialAccount<T>>::contains_key(&social_hash),
+                "social account listed twice"
+            );
+        }
+
+        let all_did_count = Self::all_did_count();
+        let idx = Self::short_id_of(all_did_count).ok_or("invalid short id")?;
+
+        let now = <pallet_timestamp::Module<T>>::get();
+        let mut metadata = MetadataRecord {
+            address: record.address.clone(),
+            superior: record.superior,
+            creator: Self::genesis_account(),
+            did: did.clone(),
+            locked_records: None,
+            social_account: record.social_account,
+            unlocked_records: None,
+            donate: None,
+            subordinate_count: 0,
+            group_name: None,
+            external_addresses: BTreeMap::new(),
+            keys: Vec::new(),
+            key_history: Vec::new(),
+        };
+        Self::push_key(
+            &mut metadata,
+            KeyType::Sr25519,
+            record.address.encode(),
+            now,
+        );
+
+        // counted as paid to the superiors, as `force_lock` does
+        if !record.locked.is_zero() {
+            <pallet_balances::Module<T>>::reserve(&record.address, record.locked)
+                .map_err(|_| "locked funds exceed the balance")?;
+            let mut locked_records = LockedRecords::default();
+            locked_records.positions.push(LockPosition {
+                amount: record.locked,
+                locked_time: now,
+                locked_period: Zero::zero(),
+            });
+            Self::refresh_quota(&mut locked_records, 0);
+            metadata.donate = Some(record.locked.min(Self::reward_schedule().cap));
+            metadata.locked_records = Some(locked_records);
+        }
+
+        if let Some(superior) = superior {
+            <Metadata<T>>::insert(&record.superior, superior);
+        }
+        if has_superior {
+            Self::add_child(record.superior, user_key);
+        }
+        if let Some(social_hash) = record.social_account {
+            <SocialAccount<T>>::insert(&social_hash, &user_key);
+            <SocialAttestation<T>>::insert(
+                &social_hash,
+                Attestation::imported(Self::genesis_account(), now),
+            );
+        }
+        <Metadata<T>>::insert(&user_key, metadata);
+
+        <Identity<T>>::insert(&record.address, (&user_key, &did));
+        <IdentityOf<T>>::insert(&user_key, &record.address);
+
+        <AllDidCount>::put(all_did_count + 1);
+        <UserKeys<T>>::insert(T::Hashing::hash(&idx), &user_key);
+        <DidIndices<T>>::insert(&user_key, idx);
+
+        Ok(user_key)
+    }
+}
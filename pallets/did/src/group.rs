@@ -0,0 +1,120 @@
+use super::*;
+use frame_support::IterableStorageDoubleMap;
+
+/// Longest name of a group.
+pub const MAX_GROUP_NAME_LENGTH: usize = 50;
+
+pub type GroupId = u64;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum GroupRole {
+    /// Invites and removes members, only the owner grants or removes the role.
+    Admin,
+    Member,
+}
+
+/// A group of DIDs, its owner is an admin who can neither leave nor be removed.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Group<AccountId, Hash, Balance> {
+    pub name: Vec<u8>,
+    pub owner: Hash,
+    /// Number of members, the owner included.
+    pub member_count: u32,
+    /// Number of pending invitations, which hold a place in the group until withdrawn.
+    pub invite_count: u32,
+    /// Who reserved the deposit, it is returned there when the group is dissolved.
+    pub depositor: AccountId,
+    pub deposit: Balance,
+}
+
+impl<T: Config> Module<T> {
+    pub(crate) fn ensure_valid_group_name(name: &[u8]) -> DispatchResult {
+        ensure!(
+            !name.is_empty() && name.len() <= MAX_GROUP_NAME_LENGTH,
+            Error::<T>::InvalidGroupName
+        );
+        ensure!(
+            !<GroupNames>::contains_key(name),
+            Error::<T>::GroupNameTaken
+        );
+        Ok(())
+    }
+
+    /// The group if `user_key` may manage members holding `role` in it.
+    pub(crate) fn ensure_group_manager(
+        group_id: GroupId,
+        user_key: T::Hash,
+        role: GroupRole,
+    ) -> Result<Group<T::AccountId, T::Hash, T::Balance>, DispatchError> {
+        let group = Self::group(group_id).ok_or(Error::<T>::GroupNotExists)?;
+        match role {
+            GroupRole::Admin => ensure!(group.owner == user_key, Error::<T>::NotGroupOwner),
+            GroupRole::Member => ensure!(
+                Self::group_member(group_id, &user_key) == Some(GroupRole::Admin),
+                Error::<T>::NotGroupAdmin
+            ),
+        }
+        Ok(group)
+    }
+
+    pub(crate) fn ensure_may_join(
+        group: &Group<T::AccountId, T::Hash, T::Balance>,
+        user_key: T::Hash,
+    ) -> DispatchResult {
+        ensure!(
+            group.member_count < T::MaxGroupMembers::get(),
+            Error::<T>::GroupFull
+        );
+        ensure!(
+            (Self::groups_of(&user_key).len() as u32) < T::MaxGroupsPerDid::get(),
+            Error::<T>::TooManyGroups
+        );
+        Ok(())
+    }
+
+    /// Add a member, `ensure_may_join` must have passed.
+    pub(crate) fn join_group(
+        group_id: GroupId,
+        mut group: Group<T::AccountId, T::Hash, T::Balance>,
+        user_key: T::Hash,
+        role: GroupRole,
+    ) {
+        group.member_count += 1;
+        <Groups<T>>::insert(group_id, group);
+        <GroupMembers<T>>::insert(group_id, &user_key, role);
+        <DidGroups<T>>::append(&user_key, group_id);
+    }
+
+    pub(crate) fn leave_group(group_id: GroupId, user_key: T::Hash) {
+        <GroupMembers<T>>::remove(group_id, &user_key);
+        <DidGroups<T>>::mutate(&user_key, |groups| groups.retain(|id| *id != group_id));
+        <Groups<T>>::mutate(group_id, |group| {
+            if let Some(group) = group {
+                group.member_count = group.member_count.saturating_sub(1);
+            }
+        });
+    }
+
+    /// Drop a pending invitation, it frees its place in the group.
+    pub(crate) fn withdraw_invite(group_id: GroupId, user_key: T::Hash) {
+        if <GroupInvites<T>>::take(group_id, &user_key).is_none() {
+            return;
+        }
+        <DidInvites<T>>::mutate(&user_key, |invites| invites.retain(|id| *id != group_id));
+        <Groups<T>>::mutate(group_id, |group| {
+            if let Some(group) = group {
+                group.invite_count = group.invite_count.saturating_sub(1);
+            }
+        });
+    }
+
+    /// Every member of a group with its role.
+    pub fn group_members(group_id: GroupId) -> Vec<(T::Hash, GroupRole)> {
+        <GroupMembers<T>>::iter_prefix(group_id).collect()
+    }
+
+    /// Every DID invited to a group with the role it was offered.
+    pub fn group_invites(group_id: GroupId) -> Vec<(T::Hash, GroupRole)> {
+        <GroupInvites<T>>::iter_prefix(group_id).collect()
+    }
+}
@@ -10,7 +10,7 @@ const MINIMUM_ALPHABET_LENGTH: usize = 16;
 /// A hashids-compatible hasher.
 ///
 /// It's probably not a great idea to use the default, because in that case
-/// your values will be entirely trivial to _decode. On the other hand, this is
+/// your values will be entirely trivial to decode. On the other hand, this is
 /// not intended to be cryptographically-secure, so go nuts!
 #[derive(Clone, Debug)]
 pub struct Harsh {
@@ -94,7 +94,7 @@ impl Harsh {
     }
 
     /// Decodes a single hashid into a slice of `u64` values.
-    pub fn _decode(&self, value: &[u8]) -> Option<Vec<u64>> {
+    pub fn decode(&self, value: &[u8]) -> Option<Vec<u64>> {
         let mut value = value.as_ref().to_vec();
 
         if let Some(guard_idx) = value.iter().rposition(|u| self.guards.contains(u)) {
@@ -352,10 +352,12 @@ fn hash(mut value: u64, _alphabet: &[u8]) -> Vec<u8> {
     }
 }
 
+/// `None` when the input has a byte outside the alphabet or does not fit a `u64`.
 fn _unhash(input: &[u8], _alphabet: &[u8]) -> Option<u64> {
-    input.iter().enumerate().fold(Some(0), |a, (idx, &value)| {
+    let base = _alphabet.len() as u64;
+    input.iter().try_fold(0u64, |a, &value| {
         let pos = _alphabet.iter().position(|&item| item == value)? as u64;
-        a.map(|a| a + (pos * (_alphabet.len() as u64).pow((input.len() - idx - 1) as u32)))
+        a.checked_mul(base)?.checked_add(pos)
     })
 }
 
@@ -440,12 +442,12 @@ mod tests {
 
         assert_eq!(
             &[1226198605112],
-            &harsh._decode(b"4o6Z7KqxE").expect("failed to _decode")[..],
+            &harsh.decode(b"4o6Z7KqxE").expect("failed to decode")[..],
             "error decoding \"4o6Z7KqxE\""
         );
         assert_eq!(
             &[1u64, 2, 3],
-            &harsh._decode(b"laHquq").expect("failed to _decode")[..]
+            &harsh.decode(b"laHquq").expect("failed to decode")[..]
         );
     }
 
@@ -459,7 +461,7 @@ mod tests {
 
         assert_eq!(
             &[1u64, 2, 3],
-            &harsh._decode(b"GlaHquq0").expect("failed to _decode")[..]
+            &harsh.decode(b"GlaHquq0").expect("failed to decode")[..]
         );
     }
 
@@ -473,7 +475,7 @@ mod tests {
 
         assert_eq!(
             &[1u64, 2, 3],
-            &harsh._decode(b"9LGlaHquq06D").expect("failed to _decode")[..]
+            &harsh.decode(b"9LGlaHquq06D").expect("failed to decode")[..]
         );
     }
 
@@ -498,7 +500,7 @@ mod tests {
     #[test]
     fn can_decode_with_invalid_alphabet() {
         let harsh = Harsh::default();
-        assert_eq!(None, harsh._decode(b"this$ain't|a\number"));
+        assert_eq!(None, harsh.decode(b"this$ain't|a\number"));
     }
 
     #[test]
@@ -511,8 +513,8 @@ mod tests {
         // mdfphx
         assert_eq!(
             &[1, 2, 3],
-            &harsh._decode(b"lqfqhr").expect("failed to _decode")[..],
-            "failed to _decode lqfqhr"
+            &harsh.decode(b"lqfqhr").expect("failed to decode")[..],
+            "failed to decode lqfqhr"
         );
     }
 
@@ -529,6 +531,14 @@ mod tests {
         assert_eq!(b"w", result.as_slice());
     }
 
+    #[test]
+    fn unhash() {
+        let _alphabet = b"abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(Some(28), super::_unhash(b"bc", _alphabet));
+        assert_eq!(None, super::_unhash(b"b$", _alphabet));
+        assert_eq!(None, super::_unhash(&[b'z'; 20], _alphabet));
+    }
+
     #[test]
     fn alphabet_and_separator_generation() {
         use super::{DEFAULT_ALPHABET, DEFAULT_SEPARATORS};
@@ -1,80 +1,257 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 mod check;
+mod did_type;
+mod document;
+mod feeless;
+mod genesis;
+mod group;
 mod harsh;
+mod migrations;
+mod proof;
+mod quota;
+mod recovery;
+mod referral;
+mod short_id;
+mod social;
 mod tests;
+mod tree;
+pub mod weights;
+
+pub use did_type::{
+    Capability, DidTypeCreators, DidTypeInfo, MAX_DID_TYPE_CREATORS, MAX_DID_TYPE_LENGTH,
+    MAX_DID_TYPE_NAME_LENGTH,
+};
+pub use document::{DidDocument, DidService, VerificationMethod, DID_METHOD};
+pub use feeless::FeelessOrCharge;
+pub use genesis::GenesisDid;
+pub use group::{Group, GroupId, GroupRole, MAX_GROUP_NAME_LENGTH};
+pub use migrations::Releases;
+pub use quota::RewardsCurve;
+pub use recovery::{ActiveRecovery, RecoveryConfig};
+pub use referral::{RewardLevel, RewardSchedule};
+pub use short_id::ShortIdRecord;
+pub use social::{Attestation, Attestor, SocialDispute, SocialProof};
+pub use tree::{MAX_TREE_QUERY, SUBORDINATE_PAGE_SIZE};
+pub use weights::WeightInfo;
 
 use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, ensure,
-    traits::{Currency, ExistenceRequirement, ReservableCurrency},
+    traits::{
+        BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Filter, Get,
+        ReservableCurrency,
+    },
+    transactional,
     weights::Weight,
 };
 use frame_system::{ensure_root, ensure_signed};
-use harsh::HarshBuilder;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
-    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Hash, SaturatedConversion, Zero},
-    DispatchResult, Permill, RuntimeDebug,
+    traits::{
+        CheckedAdd, CheckedSub, Hash, SaturatedConversion, Saturating, Zero,
+    },
+    DispatchError, DispatchResult, Permill, RuntimeDebug,
 };
-use sp_std::vec::Vec;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 pub trait Config: pallet_balances::Config + pallet_timestamp::Config {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// The maximum number of authentication keys a DID may hold.
+    type MaxKeys: Get<u32>;
+    /// The maximum number of key events kept in a DID's key history.
+    type MaxKeyHistory: Get<u32>;
+    /// The amount reserved for every service endpoint a DID publishes.
+    type ServiceDeposit: Get<Self::Balance>;
+    /// The maximum number of service endpoints a DID may publish.
+    type MaxServices: Get<u32>;
+    /// The maximum length of a service id or type.
+    type MaxServiceTypeLength: Get<u32>;
+    /// The maximum length of a service endpoint URL.
+    type MaxServiceUrlLength: Get<u32>;
+    /// The maximum number of chains a DID may link addresses from.
+    type MaxChains: Get<u32>;
+    /// The maximum number of addresses a DID may link per chain.
+    type MaxAddressesPerChain: Get<u32>;
+    /// The amount reserved to dispute a social account bound to another DID.
+    type DisputeDeposit: Get<Self::Balance>;
+    /// The maximum number of guardians a DID may nominate.
+    type MaxGuardians: Get<u32>;
+    /// How long a vouched recovery waits before it can be claimed, the DID may cancel it meanwhile.
+    type RecoveryDelay: Get<Self::Moment>;
+    /// The maximum number of lock positions a DID may hold at once.
+    type MaxLockPositions: Get<u32>;
+    /// How long unlocked funds stay reserved before they can be withdrawn.
+    type UnbondingPeriod: Get<Self::Moment>;
+    /// The maximum number of unlocks of a DID waiting to be withdrawn.
+    type MaxUnbondingChunks: Get<u32>;
+    /// The maximum number of unlocks kept in a DID's unlock history.
+    type MaxUnlockHistory: Get<u32>;
+    /// The origin allowed to change the referral reward schedule.
+    type RewardOrigin: EnsureOrigin<Self::Origin>;
+    /// The origin allowed to register and remove DID types.
+    type DidTypeOrigin: EnsureOrigin<Self::Origin>;
+    /// The maximum number of superior levels the reward schedule may pay.
+    type MaxRewardLevels: Get<u32>;
+    /// The maximum number of transfers in one batch.
+    type MaxBatchTransfers: Get<u32>;
+    /// Calls a DID may make without fees while it has quota left, see `FeelessOrCharge`.
+    type FeelessCalls: Filter<<Self as frame_system::Config>::Call>;
+    /// Number of blocks after which the feeless quota of every DID starts over.
+    type FeelessEra: Get<Self::BlockNumber>;
+    /// Locked funds which earn a DID one feeless call per era, DIDs without locked funds
+    /// always pay.
+    type FeelessLockUnit: Get<Self::Balance>;
+    /// The amount reserved for every group a DID creates.
+    type GroupDeposit: Get<Self::Balance>;
+    /// The maximum number of members of a group, its owner included, pending invitations
+    /// count against it.
+    type MaxGroupMembers: Get<u32>;
+    /// The maximum number of groups a DID may be a member of.
+    type MaxGroupsPerDid: Get<u32>;
+    /// Weight information for the extrinsics of this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 pub type Did = Vec<u8>;
 
+/// A CAIP-2 chain id, e.g. `eip155:1`.
+pub type ChainId = Vec<u8>;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum KeyType {
+    Sr25519,
+    Ed25519,
+    Ecdsa,
+}
+
+impl KeyType {
+    /// Length of a public key of this type, ecdsa keys are compressed.
+    pub fn public_len(&self) -> usize {
+        match self {
+            KeyType::Sr25519 | KeyType::Ed25519 => 32,
+            KeyType::Ecdsa => 33,
+        }
+    }
+}
+
+impl Default for KeyType {
+    fn default() -> Self {
+        KeyType::Sr25519
+    }
+}
+
 #[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
-pub struct ExternalAddress {
-    btc: Vec<u8>,
-    eth: Vec<u8>,
-    eos: Vec<u8>,
+pub struct AuthKey<Moment> {
+    key_type: KeyType,
+    public: Vec<u8>,
+    added_time: Moment,
 }
 
+/// Why value moves between DIDs, only ad rewards are split with the receiver's superior.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TransferKind {
+    Plain,
+    /// proceeds of viewing an ad
+    AdReward,
+    ReferralRebate,
+    /// advertiser funds moving into an ad contract
+    Deposit,
+    Withdrawal,
+}
+
+impl Default for TransferKind {
+    fn default() -> Self {
+        TransferKind::Plain
+    }
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum KeyAction {
+    Added,
+    Removed,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct KeyEvent<Moment> {
+    action: KeyAction,
+    key_type: KeyType,
+    public: Vec<u8>,
+    time: Moment,
+}
+
+/// Funds locked by one `lock` call, they unlock on their own once the period is over.
 #[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
-pub struct LockedRecords<Balance, Moment> {
+pub struct LockPosition<Balance, Moment> {
+    amount: Balance,
     locked_time: Moment,
     locked_period: Moment,
-    locked_funds: Balance,
+}
+
+impl<Balance, Moment: Copy + PartialOrd + Saturating> LockPosition<Balance, Moment> {
+    pub fn is_matured(&self, now: Moment) -> bool {
+        now >= self.locked_time.saturating_add(self.locked_period)
+    }
+}
+
+/// The lock positions of a DID, quota and rewards ratio follow their sum.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct LockedRecords<Balance, Moment> {
+    positions: Vec<LockPosition<Balance, Moment>>,
     rewards_ratio: u64,
     max_quota: u64,
 }
 
+impl<Balance: Copy + Zero + Saturating, Moment> LockedRecords<Balance, Moment> {
+    pub fn locked_funds(&self) -> Balance {
+        self.positions
+            .iter()
+            .fold(Zero::zero(), |sum: Balance, p| sum.saturating_add(p.amount))
+    }
+}
+
 #[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
 pub struct UnlockedRecords<Balance, Moment> {
     unlocked_time: Moment,
     unlocked_funds: Balance,
 }
 
+/// Unlocked funds which stay reserved until `release_time`.
 #[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
-pub struct MetadataRecord<AccountId, Hash, Balance, Moment> {
-    address: AccountId,
-    superior: Hash,
-    creator: AccountId,
-    did: Did,
-    locked_records: Option<LockedRecords<Balance, Moment>>,
-    unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
-    donate: Option<Balance>,
-    social_account: Option<Hash>,
-    subordinate_count: u64,
-    group_name: Option<Vec<u8>>,
-    external_address: ExternalAddress,
+pub struct UnbondingChunk<Balance, Moment> {
+    amount: Balance,
+    release_time: Moment,
 }
 
 #[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
-pub struct OldMetadataRecord<AccountId, Hash, Balance, Moment> {
+pub struct ServiceEndpoint<AccountId, Balance> {
+    id: Vec<u8>,
+    service_type: Vec<u8>,
+    endpoint: Vec<u8>,
+    /// Who reserved the deposit, it is returned there whoever controls the DID by then.
+    depositor: AccountId,
+    deposit: Balance,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct MetadataRecord<AccountId, Hash, Balance, Moment> {
     address: AccountId,
     superior: Hash,
     creator: AccountId,
     did: Did,
     locked_records: Option<LockedRecords<Balance, Moment>>,
     unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
-    is_partner: bool,
+    donate: Option<Balance>,
     social_account: Option<Hash>,
     subordinate_count: u64,
     group_name: Option<Vec<u8>>,
-    external_address: ExternalAddress,
+    external_addresses: BTreeMap<ChainId, Vec<Vec<u8>>>,
+    keys: Vec<AuthKey<Moment>>,
+    key_history: Vec<KeyEvent<Moment>>,
 }
 
 decl_error! {
@@ -113,6 +290,116 @@ decl_error! {
         NotEligible,
         /// Can't send money to yourself
         SentToSelf,
+        /// public key length does not match the key type
+        InvalidKey,
+        /// key has been added to the did
+        KeyExists,
+        /// key does not belong to the did
+        KeyNotExists,
+        /// key set is full
+        TooManyKeys,
+        /// the last key of a did can not be removed
+        LastKey,
+        /// the controlling account's key can only be rotated
+        ControllerKey,
+        /// service id, type or endpoint is empty or too long
+        InvalidService,
+        /// service id has been used by the did
+        ServiceExists,
+        /// service does not exist
+        ServiceNotExists,
+        /// too many service endpoints
+        TooManyServices,
+        /// signature does not prove ownership of the address
+        InvalidProof,
+        /// addresses of too many chains have been linked
+        TooManyChains,
+        /// too many addresses of the chain have been linked
+        TooManyAddresses,
+        /// address has been linked to the did
+        AddressExists,
+        /// address is not linked to the did
+        AddressNotExists,
+        /// address has been claimed by another did
+        AddressClaimed,
+        /// attestor is not registered
+        AttestorNotExists,
+        /// attestation is not signed by the attestor or is for another platform
+        InvalidAttestation,
+        /// did has bound a social account
+        AlreadyBound,
+        /// did has not bound a social account, or it is not attested
+        NotBound,
+        /// social account is under dispute
+        DisputeExists,
+        /// social account is not under dispute
+        DisputeNotExists,
+        /// did has been deactivated
+        DidDeactivated,
+        /// did still has subordinates
+        HasSubordinates,
+        /// guardians are empty, repeated or include the did, or the threshold is out of range
+        InvalidRecovery,
+        /// too many guardians
+        TooManyGuardians,
+        /// guardian did does not exist
+        GuardianNotExists,
+        /// did has not set up recovery
+        RecoveryNotExists,
+        /// you are not a guardian of the did
+        NotGuardian,
+        /// did is being recovered to another account
+        RecoveryInProgress,
+        /// guardian has vouched for the recovery
+        AlreadyVouched,
+        /// recovery has not been vouched for by enough guardians or is still delayed
+        RecoveryNotReady,
+        /// too many lock positions
+        TooManyLocks,
+        /// too many unlocks are waiting to be withdrawn
+        TooManyUnbonding,
+        /// no unlocked funds can be withdrawn yet
+        NoUnbonded,
+        /// reward schedule has no or too many levels, or their shares exceed 100%
+        InvalidSchedule,
+        /// rewards curve has no quota or its ratios are out of order or above 100%
+        InvalidCurve,
+        /// batch is empty or holds too many transfers
+        InvalidBatch,
+        /// short id can not be generated with the configured salt and length
+        InvalidShortId,
+        /// did type is not registered
+        UnknownDidType,
+        /// not allowed to create dids of this type
+        NotDidTypeCreator,
+        /// group does not exist
+        GroupNotExists,
+        /// group name has been taken
+        GroupNameTaken,
+        /// only admins of the group may do this
+        NotGroupAdmin,
+        /// only the owner of the group may do this
+        NotGroupOwner,
+        /// did is not a member of the group
+        NotGroupMember,
+        /// did is a member of the group already
+        AlreadyGroupMember,
+        /// did has not been invited to the group
+        InviteNotExists,
+        /// group has too many members
+        GroupFull,
+        /// did is a member of too many groups
+        TooManyGroups,
+        /// did still owns groups
+        OwnsGroups,
+        /// the owner can not leave its group or change its role
+        IsGroupOwner,
+        /// unlock amount is zero
+        ZeroUnlock,
+        /// did has too many pending invitations
+        TooManyInvites,
+        /// lock amount is zero
+        ZeroLock,
     }
 }
 
@@ -122,6 +409,11 @@ decl_storage! {
         pub BaseQuota get(fn base_quota) config(): u64;
         pub MinDeposit get(fn min_deposit) config(): T::Balance;
         pub FeeToPrevious get(fn fee_to_previous) config(): T::Balance;
+        /// How locks are rebated to the superiors, capped at `FeeToPrevious` at genesis.
+        pub ReferralSchedule get(fn reward_schedule)
+            build(|config: &GenesisConfig<T>| RewardSchedule::legacy(config.fee_to_previous)): RewardSchedule<T::Balance>;
+        /// How quotas and the superiors' share of ads proceeds follow locked funds.
+        pub RewardsCurveParams get(fn rewards_curve): RewardsCurve;
 
         pub Identity get(fn identity): map hasher(twox_64_concat) T::AccountId => Option<(T::Hash, Did)>;
         pub IdentityOf get(fn identity_of): map hasher(twox_64_concat) T::Hash => Option<T::AccountId>;
@@ -129,8 +421,78 @@ decl_storage! {
         pub Metadata get(fn metadata): map hasher(twox_64_concat) T::Hash => MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>;
 
         pub AllDidCount get(fn all_did_count): u64;
+        /// Salt and minimum length of the short IDs encoding `AllDidCount` at creation.
+        pub HarshSalt get(fn harsh_salt) config(): Vec<u8> = b"parami did".to_vec();
+        pub HarshLength get(fn harsh_length) config(): u32 = 6;
         pub UserKeys get(fn key_by_index): map hasher(twox_64_concat) T::Hash => T::Hash;
         pub DidIndices get(fn index_by_key) : map hasher(twox_64_concat) T::Hash => Vec<u8>;
+
+        /// DID types by the prefix they give their DIDs.
+        pub DidTypes get(fn did_type) build(|_| did_type::default_did_types()): map hasher(twox_64_concat) Vec<u8> => Option<DidTypeInfo<T::AccountId, T::Balance>>;
+        /// Who reserved the deposit of a DID's type and how much.
+        pub TypeDeposits get(fn type_deposit): map hasher(twox_64_concat) T::Hash => Option<(T::AccountId, T::Balance)>;
+
+        pub Services get(fn services): map hasher(twox_64_concat) T::Hash => Vec<ServiceEndpoint<T::AccountId, T::Balance>>;
+
+        /// Unlocked funds of a DID waiting out `UnbondingPeriod`, oldest first.
+        pub Unbonding get(fn unbonding): map hasher(twox_64_concat) T::Hash => Vec<UnbondingChunk<T::Balance, T::Moment>>;
+        /// Every unlock of a DID, the oldest entries are dropped beyond `MaxUnlockHistory`.
+        pub UnlockHistory get(fn unlock_history): map hasher(twox_64_concat) T::Hash => Vec<UnlockedRecords<T::Balance, T::Moment>>;
+
+        /// Direct subordinates of a DID in pages of `SUBORDINATE_PAGE_SIZE`.
+        pub Subordinates get(fn subordinate_page): double_map hasher(twox_64_concat) T::Hash, hasher(twox_64_concat) u32 => Vec<T::Hash>;
+        /// Number of direct subordinates in `Subordinates`, the genesis DID's included.
+        pub SubordinateTotal get(fn child_count): map hasher(twox_64_concat) T::Hash => u32;
+        /// Page of `Subordinates` a DID is kept in and its index there.
+        pub ChildPositions get(fn child_position): map hasher(twox_64_concat) T::Hash => Option<(u32, u32)>;
+
+        /// The era a DID last made feeless calls in and how many it made.
+        pub FeelessUsage get(fn feeless_usage): map hasher(twox_64_concat) T::Hash => (T::BlockNumber, u32);
+
+        pub NextGroupId get(fn next_group_id): GroupId;
+        pub Groups get(fn group): map hasher(twox_64_concat) GroupId => Option<Group<T::AccountId, T::Hash, T::Balance>>;
+        /// Group names are unique, freed when a group is dissolved.
+        pub GroupNames get(fn group_by_name): map hasher(blake2_128_concat) Vec<u8> => Option<GroupId>;
+        pub GroupMembers get(fn group_member): double_map hasher(twox_64_concat) GroupId, hasher(twox_64_concat) T::Hash => Option<GroupRole>;
+        /// Invitations waiting for the invited DID to accept, with the role it will hold.
+        pub GroupInvites get(fn group_invite): double_map hasher(twox_64_concat) GroupId, hasher(twox_64_concat) T::Hash => Option<GroupRole>;
+        /// Groups a DID is a member of, bounded by `MaxGroupsPerDid`.
+        pub DidGroups get(fn groups_of): map hasher(twox_64_concat) T::Hash => Vec<GroupId>;
+        /// Groups with an invitation pending for a DID, bounded by `MaxGroupsPerDid`.
+        pub DidInvites get(fn invites_of): map hasher(twox_64_concat) T::Hash => Vec<GroupId>;
+
+        /// Guardians of a DID and how many of them recover it.
+        pub RecoveryConfigs get(fn recovery_config): map hasher(twox_64_concat) T::Hash => Option<RecoveryConfig<T::Hash>>;
+        pub ActiveRecoveries get(fn active_recovery): map hasher(twox_64_concat) T::Hash => Option<ActiveRecovery<T::AccountId, T::Hash, T::Moment>>;
+
+        /// When a DID was deactivated, its user key can never be registered again.
+        pub Tombstones get(fn tombstone): map hasher(twox_64_concat) T::Hash => Option<T::Moment>;
+
+        /// Nonce of the next external address ownership challenge of a DID.
+        pub ProofNonce get(fn proof_nonce): map hasher(twox_64_concat) T::Hash => u64;
+
+        /// The DID a verified external address, keyed by chain id and normalized address, is linked to.
+        pub ExternalAddressOwner get(fn external_address_owner):
+            double_map hasher(blake2_128_concat) ChainId, hasher(blake2_128_concat) Vec<u8> => Option<T::Hash>;
+
+        /// Accounts allowed to attest social accounts of a platform.
+        pub Attestors get(fn attestors): map hasher(twox_64_concat) T::AccountId => Option<Attestor>;
+        /// How a bound social account was verified, missing for accounts bound on first use.
+        pub SocialAttestation get(fn social_attestation): map hasher(twox_64_concat) T::Hash => Option<Attestation<T::AccountId, T::Moment>>;
+        pub SocialDisputes get(fn social_dispute): map hasher(twox_64_concat) T::Hash => Option<SocialDispute<T::AccountId, T::Hash, T::Balance, T::Moment>>;
+
+        /// Layout of the pallet's storage, `None` on chains which predate versioning.
+        pub StorageVersion get(fn storage_version) build(|_| Some(Releases::LATEST)): Option<Releases>;
+    }
+
+    add_extra_genesis {
+        // superiors have to be listed before their subordinates
+        config(dids): Vec<GenesisDid<T::AccountId, T::Hash, T::Balance>>;
+        build(|config: &GenesisConfig<T>| {
+            for record in config.dids.iter() {
+                Module::<T>::import_did(record).expect("invalid genesis did");
+            }
+        });
     }
 }
 
@@ -138,6 +500,7 @@ decl_event! {
   pub enum Event<T>
   where
     <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::Hash,
     <T as pallet_balances::Config>::Balance,
     <T as pallet_timestamp::Config>::Moment,
     {
@@ -145,9 +508,45 @@ decl_event! {
             Updated(Did, AccountId, Balance),
             Locked(Did, Balance, Moment, Moment, u64, u64),
             Unlocked(Did, Balance, Moment),
-            Transfered(Did, Did, Balance, Vec<u8>),
-            AddressAdded(Did, Vec<u8>, Vec<u8>),
+            Withdrawn(Did, Balance),
+            RewardScheduleSet(Permill, Balance, u32),
+            RewardsCurveSet(u64, u64, u64),
+            /// type and its name
+            DidTypeSet(Vec<u8>, Vec<u8>),
+            DidTypeRemoved(Vec<u8>),
+            ReferralRewarded(Did, Did, u32, Balance),
+            Transfered(Did, Did, Balance, TransferKind, Vec<u8>),
+            /// every receiver with what it and its superior got, and the memo
+            BatchTransfered(Did, TransferKind, Vec<(Did, Balance, Balance, Vec<u8>)>),
+            AddressAdded(Did, ChainId, Vec<u8>),
+            AddressRemoved(Did, ChainId, Vec<u8>),
             GroupNameSet(Did, Vec<u8>),
+            KeyAdded(Did, KeyType, Vec<u8>),
+            KeyRemoved(Did, Vec<u8>),
+            KeyRotated(Did, Vec<u8>, Vec<u8>),
+            ServiceAdded(Did, Vec<u8>, Vec<u8>, Vec<u8>),
+            ServiceUpdated(Did, Vec<u8>, Vec<u8>, Vec<u8>),
+            ServiceRemoved(Did, Vec<u8>),
+            AttestorRegistered(AccountId, Vec<u8>),
+            AttestorRemoved(AccountId),
+            SocialBound(Did, Hash, AccountId),
+            SocialUnbound(Did, Hash),
+            SocialDisputed(Did, Hash),
+            SocialDisputeResolved(Hash, bool),
+            Deactivated(Did, Balance),
+            RecoverySet(Did, Vec<Hash>, u32),
+            RecoveryRemoved(Did),
+            RecoveryVouched(Did, Hash, AccountId),
+            RecoveryReady(Did, AccountId, Moment),
+            RecoveryCancelled(Did),
+            Recovered(Did, AccountId),
+            /// group, its owner and name
+            GroupCreated(GroupId, Did, Vec<u8>),
+            GroupDissolved(GroupId),
+            MemberInvited(GroupId, Hash, GroupRole),
+            MemberJoined(GroupId, Did, GroupRole),
+            MemberRemoved(GroupId, Hash),
+            MemberRoleSet(GroupId, Hash, GroupRole),
     }
 }
 
@@ -158,34 +557,51 @@ decl_module! {
         fn deposit_event() = default;
 
         fn on_runtime_upgrade() -> Weight {
-            // Self::migrate();
+            migrations::migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            let dids = migrations::pre_upgrade::<T>()?;
+            frame_support::storage::unhashed::put(migrations::TRY_RUNTIME_DIDS, &dids);
+            Ok(())
+        }
 
-            0
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            let dids = frame_support::storage::unhashed::take(migrations::TRY_RUNTIME_DIDS)
+                .ok_or("pre_upgrade did not run")?;
+            migrations::post_upgrade::<T>(dids)
         }
 
-        #[weight = 0]
-        pub fn create(origin, pubkey: Vec<u8>, address: T::AccountId, did_type: Vec<u8>, superior: T::Hash, social_account: Option<Vec<u8>>, social_superior: Option<Vec<u8>>) {
+        /// Create a DID, `social_account` must be attested by a registered attestor and
+        /// `social_superior` is the `platform:handle` bound to the superior.
+        #[weight = T::WeightInfo::create()]
+        #[transactional]
+        pub fn create(origin, pubkey: Vec<u8>, address: T::AccountId, did_type: Vec<u8>, superior: T::Hash, social_account: Option<SocialProof<T::AccountId>>, social_superior: Option<Vec<u8>>) {
             let sender = ensure_signed(origin)?;
 
+            let type_info = Self::did_type(&did_type).ok_or(Error::<T>::UnknownDidType)?;
+            ensure!(type_info.may_create(&sender), Error::<T>::NotDidTypeCreator);
+
             let did = Self::generate_did(&pubkey, &did_type);
             let user_key = T::Hashing::hash(&did);
 
             // make sure the did is new
             ensure!(!<Metadata<T>>::contains_key(&user_key), Error::<T>::DidExists);
+            ensure!(!<Tombstones<T>>::contains_key(&user_key), Error::<T>::DidDeactivated);
             ensure!(!<Identity<T>>::contains_key(&address), Error::<T>::DidExists);
 
             let mut superior_key = superior;
-            let mut social_account_hash = None;
+            let mut social_binding = None;
 
-            if let Some(mut value) = social_account {
+            if let Some(proof) = social_account {
                 // bind social account
-                value.append(&mut did_type.to_vec());
-
-                let social_hash = T::Hashing::hash(&value);
-                social_account_hash = Some(social_hash);
+                let (social_hash, attestation) = Self::check_social_proof(user_key, &did, &proof)?;
 
                 // one social account only can bind one did
-                ensure!(!<SocialAccount<T>>::contains_key(&social_hash), Error::<T>::SocialAccountBound);
+                Self::ensure_social_available(social_hash, user_key)?;
+                social_binding = Some((social_hash, attestation));
 
                 if let Some(mut value) = social_superior {
                     value.append(&mut did_type.to_vec());
@@ -196,6 +612,22 @@ decl_module! {
                 };
             }
 
+            // only the genesis account places DIDs at the top of the tree
+            ensure!(
+                <Metadata<T>>::contains_key(&superior_key) || sender == Self::genesis_account(),
+                Error::<T>::SuperiorNotExists
+            );
+
+            let all_did_count = Self::all_did_count();
+            let new_count = all_did_count.checked_add(1)
+                    .ok_or(Error::<T>::Overflow)?;
+            let idx = Self::short_id_of(all_did_count).ok_or(Error::<T>::InvalidShortId)?;
+
+            if !type_info.deposit.is_zero() {
+                <pallet_balances::Module<T>>::reserve(&sender, type_info.deposit)?;
+                <TypeDeposits<T>>::insert(&user_key, (&sender, type_info.deposit));
+            }
+
             let mut superior_did = Vec::new();
             if <Metadata<T>>::contains_key(&superior_key) {
                 let mut superior_metadata = Self::metadata(superior_key);
@@ -204,9 +636,9 @@ decl_module! {
 
                     ensure!(superior_metadata.locked_records.is_some(), Error::<T>::NotLockFunds);
 
-                    let locked_records = superior_metadata.locked_records.unwrap();
-                    let LockedRecords { max_quota, .. } = locked_records;
-                    ensure!(subordinate_count <= max_quota, Error::<T>::ExceedsMaxQuota);
+                    let mut locked_records = superior_metadata.locked_records.unwrap();
+                    ensure!(subordinate_count <= locked_records.max_quota, Error::<T>::ExceedsMaxQuota);
+                    Self::refresh_quota(&mut locked_records, subordinate_count);
 
                     superior_metadata.subordinate_count = subordinate_count;
                     superior_metadata.locked_records = Some(locked_records);
@@ -215,29 +647,30 @@ decl_module! {
                 }
             }
 
-            if social_account_hash.is_some() {
-                let social_hash = social_account_hash.unwrap();
-                <SocialAccount<T>>::insert(social_hash, &user_key);
-            }
-
-            // update metadata
-            let metadata = MetadataRecord {
+            // the controlling account is the first authentication key
+            let now = <pallet_timestamp::Module<T>>::get();
+            let mut metadata = MetadataRecord {
                 address: address.clone(),
                 superior: superior_key,
                 creator: sender.clone(),
                 did: did.clone(),
                 locked_records: None,
-                social_account: social_account_hash,
+                social_account: None,
                 unlocked_records: None,
                 donate: None,
                 subordinate_count: 0,
                 group_name: None,
-                external_address: ExternalAddress {
-                    btc: Vec::new(),
-                    eth: Vec::new(),
-                    eos: Vec::new(),
-                },
+                external_addresses: BTreeMap::new(),
+                keys: Vec::new(),
+                key_history: Vec::new(),
             };
+            Self::push_key(&mut metadata, KeyType::Sr25519, address.encode(), now);
+            if let Some((social_hash, attestation)) = social_binding {
+                Self::bind_social(user_key, &mut metadata, social_hash, attestation);
+            }
+            if <Metadata<T>>::contains_key(&superior_key) {
+                Self::add_child(superior_key, user_key);
+            }
             <Metadata<T>>::insert(&user_key, metadata);
 
             // update address => did
@@ -247,13 +680,8 @@ decl_module! {
             <IdentityOf<T>>::insert(&user_key, &address);
 
             // update did count
-            let all_did_count = Self::all_did_count();
-            let new_count = all_did_count.checked_add(1)
-                    .ok_or(Error::<T>::Overflow)?;
             <AllDidCount>::put(new_count);
 
-            let harsher = HarshBuilder::new().salt("parami did").length(6).init().unwrap();
-            let idx = harsher.encode(&[all_did_count]).unwrap();
             let idx_hash = T::Hashing::hash(&idx);
 
             <UserKeys<T>>::insert(&idx_hash, &user_key);
@@ -263,7 +691,8 @@ decl_module! {
             Self::deposit_event(RawEvent::Created(did, pubkey, superior_did));
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::update()]
+        #[transactional]
         pub fn update(origin, to: T::AccountId) {
             let sender = ensure_signed(origin)?;
 
@@ -274,237 +703,874 @@ decl_module! {
             let money = <pallet_balances::Module<T>>::free_balance(&sender);
             <pallet_balances::Module<T> as Currency<_>>::transfer(&sender, &to, money, ExistenceRequirement::AllowDeath,)?;
 
-            // update address => did map
+            // locks and unbonding chunks are released from the account the DID is bound to
+            Self::move_reserved(user_key, &sender, &to)?;
+            Self::rebind_account(user_key, &sender, &to)?;
+
+            Self::deposit_event(RawEvent::Updated(did, to, money));
+        }
+
+        /// Deactivate the sender's DID once its lock period is over. Locked funds and service
+        /// deposits are unreserved, every binding and pending invitation is released and a
+        /// tombstone is left behind.
+        #[weight = T::WeightInfo::deactivate(
+            T::MaxServices::get(),
+            T::MaxChains::get(),
+            T::MaxGroupsPerDid::get(),
+            T::MaxGroupsPerDid::get()
+        )]
+        pub fn deactivate(origin) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(sender != Self::genesis_account(), Error::<T>::NotEligible);
+
+            let mut metadata = Self::metadata(&user_key);
+            ensure!(metadata.subordinate_count == 0, Error::<T>::HasSubordinates);
+            let groups = Self::groups_of(&user_key);
+            ensure!(
+                groups.iter().all(|id| Self::group(id).map_or(true, |group| group.owner != user_key)),
+                Error::<T>::OwnsGroups
+            );
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            let mut released: T::Balance = Zero::zero();
+            if let Some(locked_records) = metadata.locked_records.take() {
+                ensure!(locked_records.positions.iter().all(|p| p.is_matured(now)), Error::<T>::UnlockTimeNotReach);
+                released = locked_records.locked_funds();
+            }
+            let unbonding = Self::unbonding(&user_key);
+            ensure!(unbonding.iter().all(|c| now >= c.release_time), Error::<T>::UnlockTimeNotReach);
+            for chunk in unbonding {
+                released += chunk.amount;
+            }
+            <Unbonding<T>>::remove(&user_key);
+            <UnlockHistory<T>>::remove(&user_key);
+            <pallet_balances::Module<T>>::unreserve(&sender, released);
+            for service in <Services<T>>::take(&user_key) {
+                <pallet_balances::Module<T>>::unreserve(&service.depositor, service.deposit);
+            }
+            if let Some((creator, deposit)) = <TypeDeposits<T>>::take(&user_key) {
+                <pallet_balances::Module<T>>::unreserve(&creator, deposit);
+            }
+
+            // the superior counted this did against its quota
+            if <Metadata<T>>::contains_key(&metadata.superior) {
+                <Metadata<T>>::mutate(&metadata.superior, |superior| {
+                    superior.subordinate_count = superior.subordinate_count.saturating_sub(1);
+                    if let Some(locked_records) = superior.locked_records.as_mut() {
+                        Self::refresh_quota(locked_records, superior.subordinate_count);
+                    }
+                });
+                Self::remove_child(metadata.superior, user_key);
+            }
+
+            if let Some(social_hash) = Self::unbind_social(user_key, &mut metadata) {
+                Self::deposit_event(RawEvent::SocialUnbound(did.clone(), social_hash));
+            }
+            for (chain_id, addresses) in metadata.external_addresses.iter() {
+                for address in addresses {
+                    if Self::external_address_owner(chain_id, address) == Some(user_key) {
+                        <ExternalAddressOwner<T>>::remove(chain_id, address);
+                    }
+                }
+            }
+
+            <RecoveryConfigs<T>>::remove(&user_key);
+            <ActiveRecoveries<T>>::remove(&user_key);
+            <FeelessUsage<T>>::remove(&user_key);
+            for group_id in groups {
+                Self::leave_group(group_id, user_key);
+            }
+            for group_id in Self::invites_of(&user_key) {
+                Self::withdraw_invite(group_id, user_key);
+            }
+            <DidInvites<T>>::remove(&user_key);
+
+            let idx = <DidIndices<T>>::take(&user_key);
+            <UserKeys<T>>::remove(T::Hashing::hash(&idx));
             <Identity<T>>::remove(&sender);
-            <Identity<T>>::insert(&to, (&user_key, &did));
+            <IdentityOf<T>>::remove(&user_key);
+            <Metadata<T>>::remove(&user_key);
+            <ProofNonce<T>>::remove(&user_key);
+            <Tombstones<T>>::insert(&user_key, now);
 
-            // update user_key => address
-            <IdentityOf<T>>::insert(user_key, &to);
+            Self::deposit_event(RawEvent::Deactivated(did, released));
+        }
+
+        #[weight = T::WeightInfo::add_key()]
+        pub fn add_key(origin, key_type: KeyType, public: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
 
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
             let mut metadata = Self::metadata(&user_key);
-            metadata.address = to.clone();
+
+            Self::ensure_new_key(&metadata, key_type, &public)?;
+            ensure!((metadata.keys.len() as u32) < T::MaxKeys::get(), Error::<T>::TooManyKeys);
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            Self::push_key(&mut metadata, key_type, public.clone(), now);
 
             <Metadata<T>>::insert(user_key, metadata);
 
-            Self::deposit_event(RawEvent::Updated(did, to, money));
+            Self::deposit_event(RawEvent::KeyAdded(did, key_type, public));
         }
 
-        #[weight = 0]
-        pub fn transfer(origin, to_user: T::Hash, value: T::Balance, memo: Vec<u8>) {
+        #[weight = T::WeightInfo::remove_key()]
+        pub fn remove_key(origin, public: Vec<u8>) {
             let sender = ensure_signed(origin)?;
 
-            let (from_user, _) = Self::identity(sender).ok_or(Error::<T>::DidNotExists)?;
-            Self::transfer_by_did(from_user, to_user, value, memo)?;
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+
+            ensure!(metadata.keys.len() > 1, Error::<T>::LastKey);
+            ensure!(public != sender.encode(), Error::<T>::ControllerKey);
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            Self::take_key(&mut metadata, &public, now)?;
+
+            <Metadata<T>>::insert(user_key, metadata);
+
+            Self::deposit_event(RawEvent::KeyRemoved(did, public));
         }
 
-        #[weight = 0]
-        pub fn lock(origin, value: T::Balance, period: T::Moment) {
+        /// Replace a (possibly compromised) key with a new one. Rotating the controlling
+        /// account's key hands the DID and the funds it reserved to the new sr25519 account.
+        #[weight = T::WeightInfo::rotate_key()]
+        #[transactional]
+        pub fn rotate_key(origin, old_public: Vec<u8>, key_type: KeyType, new_public: Vec<u8>) {
             let sender = ensure_signed(origin)?;
 
-            let sender_balance = <pallet_balances::Module<T>>::free_balance(sender.clone());
-            ensure!(sender_balance >= value, Error::<T>::NotEnoughBalance);
-
             let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
             let mut metadata = Self::metadata(&user_key);
 
-            // make sure the superior exists
-            ensure!(<Metadata<T>>::contains_key(metadata.superior), Error::<T>::SuperiorNotExists);
+            Self::ensure_new_key(&metadata, key_type, &new_public)?;
 
-            let level2_metadata = Self::metadata(metadata.superior);
+            if old_public == sender.encode() {
+                ensure!(key_type == KeyType::Sr25519, Error::<T>::InvalidKey);
+                let to = T::AccountId::decode(&mut &new_public[..]).map_err(|_| Error::<T>::InvalidKey)?;
+                ensure!(Self::identity(&to).is_none(), Error::<T>::PublicKeyUsed);
 
-            let locked_funds;
-            let memo = "抵押分成".as_bytes().to_vec();
-            let mut rewards_ratio = 20;// basis rewards_ratio is 20%
+                Self::move_reserved(user_key, &sender, &to)?;
+                Self::rebind_account(user_key, &sender, &to)?;
+            } else {
+                let now = <pallet_timestamp::Module<T>>::get();
+                Self::take_key(&mut metadata, &old_public, now)?;
+                Self::push_key(&mut metadata, key_type, new_public.clone(), now);
 
-            if metadata.donate.is_none() {
-                ensure!(value >= Self::min_deposit(), Error::<T>::LockNotFulfilled);
+                <Metadata<T>>::insert(user_key, metadata);
+            }
 
-                let mut rebate = value.checked_div(&2_u32.into()).ok_or(Error::<T>::Overflow)?;
-                if rebate > Self::fee_to_previous() {
-                    rebate = Self::fee_to_previous();
-                }
+            Self::deposit_event(RawEvent::KeyRotated(did, old_public, new_public));
+        }
 
+        #[weight = T::WeightInfo::add_service()]
+        pub fn add_service(origin, id: Vec<u8>, service_type: Vec<u8>, endpoint: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
 
-                locked_funds = value - rebate;
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            Self::ensure_valid_service(&id, &service_type, &endpoint)?;
 
+            let mut services = Self::services(&user_key);
+            ensure!(!services.iter().any(|s| s.id == id), Error::<T>::ServiceExists);
+            ensure!((services.len() as u32) < T::MaxServices::get(), Error::<T>::TooManyServices);
 
+            let deposit = T::ServiceDeposit::get();
+            <pallet_balances::Module<T>>::reserve(&sender, deposit)?;
 
-                if level2_metadata.superior != Default::default() {
-                    let fee1 = Permill::from_percent(80) * rebate;
-                    let fee2 = Permill::from_percent(20) * rebate;
+            services.push(ServiceEndpoint {
+                id: id.clone(),
+                service_type: service_type.clone(),
+                endpoint: endpoint.clone(),
+                depositor: sender,
+                deposit,
+            });
+            <Services<T>>::insert(&user_key, services);
 
-                    Self::transfer_by_did(user_key, metadata.superior, fee1, memo.clone())?;
-                    Self::transfer_by_did(user_key, level2_metadata.superior, fee2, memo.clone())?;
-                } else {
-                    Self::transfer_by_did(user_key, metadata.superior, rebate, memo)?;
-                }
+            Self::deposit_event(RawEvent::ServiceAdded(did, id, service_type, endpoint));
+        }
+
+        #[weight = T::WeightInfo::update_service()]
+        pub fn update_service(origin, id: Vec<u8>, service_type: Vec<u8>, endpoint: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            Self::ensure_valid_service(&id, &service_type, &endpoint)?;
+
+            let mut services = Self::services(&user_key);
+            let service = services.iter_mut().find(|s| s.id == id).ok_or(Error::<T>::ServiceNotExists)?;
+            service.service_type = service_type.clone();
+            service.endpoint = endpoint.clone();
+            <Services<T>>::insert(&user_key, services);
+
+            Self::deposit_event(RawEvent::ServiceUpdated(did, id, service_type, endpoint));
+        }
+
+        #[weight = T::WeightInfo::remove_service()]
+        pub fn remove_service(origin, id: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+
+            let mut services = Self::services(&user_key);
+            let pos = services.iter().position(|s| s.id == id).ok_or(Error::<T>::ServiceNotExists)?;
+            let ServiceEndpoint { depositor, deposit, .. } = services.remove(pos);
 
-                <pallet_balances::Module<T>>::reserve(&sender, locked_funds)?;
-                metadata.donate = Some(rebate);
+            if services.is_empty() {
+                <Services<T>>::remove(&user_key);
             } else {
-                let locked_records = metadata.locked_records.unwrap();
-                let mut donate = metadata.donate.unwrap();
-                let old_locked_funds = locked_records.locked_funds;
-                let mut new_locked_funds = value;
-
-                if donate >= Self::fee_to_previous() { // without rebate
-                    locked_funds = old_locked_funds + new_locked_funds;
-                } else { // keeping rebate
-                    let lack = Self::fee_to_previous()
-                        .checked_sub(&donate)
-                        .and_then(|n| n.checked_mul(&2_u32.into()))
-                        .ok_or(Error::<T>::Overflow)?;
-                    let mut rebate = lack / 2_u32.into();
-                    if new_locked_funds < lack {
-                        rebate = new_locked_funds / 2_u32.into();
-                    }
+                <Services<T>>::insert(&user_key, services);
+            }
+            <pallet_balances::Module<T>>::unreserve(&depositor, deposit);
 
-                    new_locked_funds = new_locked_funds.checked_sub(&rebate).ok_or(Error::<T>::Overflow)?;
-                    locked_funds = old_locked_funds.checked_add(&new_locked_funds).ok_or(Error::<T>::Overflow)?;
+            Self::deposit_event(RawEvent::ServiceRemoved(did, id));
+        }
 
-                    if level2_metadata.superior != Default::default() {
-                        let part1 = Permill::from_percent(80) * rebate;
-                        let part2 = Permill::from_percent(20) * rebate;
+        #[weight = T::WeightInfo::transfer()]
+        pub fn transfer(origin, to_user: T::Hash, value: T::Balance, memo: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
 
-                        Self::transfer_by_did(user_key, metadata.superior, part1, memo.clone())?;
-                        Self::transfer_by_did(user_key, level2_metadata.superior, part2, memo.clone())?;
-                    } else {
-                        Self::transfer_by_did(user_key, metadata.superior, rebate, memo)?;
-                    }
-                    donate += rebate;
-                }
-                <pallet_balances::Module<T>>::reserve(&sender, new_locked_funds)?;
-                metadata.donate = Some(donate);
+            let (from_user, _) = Self::identity(sender).ok_or(Error::<T>::DidNotExists)?;
+            Self::transfer_by_did(from_user, to_user, value, TransferKind::Plain, memo)?;
+        }
+
+        /// Transfer to every `(user key, value, memo)` entry, all of them or none. Signed
+        /// batches are always `TransferKind::Plain`, the other kinds are only paid out by
+        /// pallets through `transfer_batch_by_did`.
+        #[weight = T::WeightInfo::transfer_batch(transfers.len() as u32)]
+        #[transactional]
+        pub fn transfer_batch(origin, transfers: Vec<(T::Hash, T::Balance, Vec<u8>)>) {
+            let sender = ensure_signed(origin)?;
+
+            let (from_user, _) = Self::identity(sender).ok_or(Error::<T>::DidNotExists)?;
+            Self::transfer_batch_by_did(from_user, transfers, TransferKind::Plain)?;
+        }
+
+        #[weight = T::WeightInfo::lock(T::MaxRewardLevels::get())]
+        #[transactional]
+        pub fn lock(origin, value: T::Balance, period: T::Moment) {
+            let sender = ensure_signed(origin)?;
+            ensure!(!value.is_zero(), Error::<T>::ZeroLock);
+
+            let sender_balance = <pallet_balances::Module<T>>::free_balance(sender.clone());
+            ensure!(sender_balance >= value, Error::<T>::NotEnoughBalance);
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+            let mut locked_records = metadata.locked_records.take().unwrap_or_default();
+            ensure!((locked_records.positions.len() as u32) < T::MaxLockPositions::get(), Error::<T>::TooManyLocks);
+
+            // make sure the superior exists
+            ensure!(<Metadata<T>>::contains_key(metadata.superior), Error::<T>::SuperiorNotExists);
+
+            // the first lock has to reach the minimum deposit
+            if metadata.donate.is_none() {
+                ensure!(value >= Self::min_deposit(), Error::<T>::LockNotFulfilled);
             }
 
-            let max_quota = Self::balance_to_u64(locked_funds) * 10;
+            // rebate the superiors until the schedule's cap has been paid
+            let donate = metadata.donate.unwrap_or_else(Zero::zero);
+            let rebate = Self::referral_rebate(value, donate);
+            let paid = Self::pay_referral_rewards(user_key, metadata.superior, rebate)?;
 
-            if max_quota >= metadata.subordinate_count {
-                rewards_ratio = 20;
-            };
+            let locked_funds = value.checked_sub(&paid).ok_or(Error::<T>::Overflow)?;
+            <pallet_balances::Module<T>>::reserve(&sender, locked_funds)?;
+            metadata.donate = Some(donate.checked_add(&paid).ok_or(Error::<T>::Overflow)?);
 
             let locked_time = <pallet_timestamp::Module<T>>::get();
-            metadata.locked_records = Some(LockedRecords {
-                locked_funds,
-                rewards_ratio,
-                max_quota,
+            locked_records.positions.push(LockPosition {
+                amount: locked_funds,
                 locked_time,
                 locked_period: period,
             });
+            Self::refresh_quota(&mut locked_records, metadata.subordinate_count);
+
+            let total_funds = locked_records.locked_funds();
+            let rewards_ratio = locked_records.rewards_ratio;
+            let max_quota = locked_records.max_quota;
+            metadata.locked_records = Some(locked_records);
 
             <Metadata<T>>::insert(user_key, metadata);
 
-            Self::deposit_event(RawEvent::Locked(did, locked_funds, locked_time, period, rewards_ratio, max_quota));
+            Self::deposit_event(RawEvent::Locked(did, total_funds, locked_time, period, rewards_ratio, max_quota));
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::force_lock()]
         pub fn force_lock(origin, user: T::Hash, value: T::Balance) {
             ensure_root(origin)?;
             ensure!(<Metadata<T>>::contains_key(&user), Error::<T>::DidNotExists);
 
             let mut metadata = Self::metadata(&user);
-            let reserved_balance = <pallet_balances::Module<T>>::reserved_balance(&metadata.address);
-            let locked_funds = reserved_balance + value;
-            let donate = if locked_funds >= Self::fee_to_previous() {
-                Self::fee_to_previous()
-            } else {
-                locked_funds
-            };
-            <pallet_balances::Module<T>>::reserve(&metadata.address, value)?;
+            let mut locked_records = metadata.locked_records.take().unwrap_or_default();
+            ensure!((locked_records.positions.len() as u32) < T::MaxLockPositions::get(), Error::<T>::TooManyLocks);
 
-            let max_quota = Self::balance_to_u64(locked_funds) * 10;
-            let rewards_ratio = 20;
+            let locked_funds = locked_records.locked_funds().checked_add(&value).ok_or(Error::<T>::Overflow)?;
+            let donate = locked_funds.min(Self::reward_schedule().cap);
+            <pallet_balances::Module<T>>::reserve(&metadata.address, value)?;
 
             let locked_time = <pallet_timestamp::Module<T>>::get();
-            metadata.donate = Some(donate);
-            metadata.locked_records = Some(LockedRecords {
-                locked_funds,
-                rewards_ratio,
-                max_quota,
+            locked_records.positions.push(LockPosition {
+                amount: value,
                 locked_time,
                 locked_period: Zero::zero(),
             });
+            Self::refresh_quota(&mut locked_records, metadata.subordinate_count);
+
+            metadata.donate = Some(donate);
+            metadata.locked_records = Some(locked_records);
 
             <Metadata<T>>::insert(user, metadata);
         }
 
-        #[weight = 0]
+        /// Replace the schedule by which locks are rebated to the superiors.
+        #[weight = T::WeightInfo::set_reward_schedule(schedule.levels.len() as u32)]
+        pub fn set_reward_schedule(origin, schedule: RewardSchedule<T::Balance>) {
+            T::RewardOrigin::ensure_origin(origin)?;
+            Self::ensure_valid_schedule(&schedule)?;
+
+            let levels = schedule.levels.len() as u32;
+            let RewardSchedule { rebate, cap, .. } = schedule;
+            <ReferralSchedule<T>>::put(schedule);
+
+            Self::deposit_event(RawEvent::RewardScheduleSet(rebate, cap, levels));
+        }
+
+        /// Replace the curve quotas and rewards ratios follow, stored ratios catch up as
+        /// locks and subordinates change while ads proceeds are split by the new curve at once.
+        #[weight = T::WeightInfo::set_rewards_curve()]
+        pub fn set_rewards_curve(origin, curve: RewardsCurve) {
+            T::RewardOrigin::ensure_origin(origin)?;
+            ensure!(curve.is_valid(), Error::<T>::InvalidCurve);
+
+            let RewardsCurve { quota_per_unit, base_ratio, max_ratio } = curve;
+            <RewardsCurveParams>::put(curve);
+
+            Self::deposit_event(RawEvent::RewardsCurveSet(quota_per_unit, base_ratio, max_ratio));
+        }
+
+        /// Register a DID type or replace it, DIDs already created keep the deposit they
+        /// reserved but gain or lose capabilities at once.
+        #[weight = T::WeightInfo::set_did_type(info.creator_count() as u32)]
+        pub fn set_did_type(origin, did_type: Vec<u8>, info: DidTypeInfo<T::AccountId, T::Balance>) {
+            T::DidTypeOrigin::ensure_origin(origin)?;
+
+            let mut info = info;
+            Self::ensure_valid_did_type(&did_type, &mut info)?;
+
+            let name = info.name.clone();
+            <DidTypes<T>>::insert(&did_type, info);
+
+            Self::deposit_event(RawEvent::DidTypeSet(did_type, name));
+        }
+
+        /// Stop creating DIDs of a type, the existing ones lose its capabilities.
+        #[weight = T::WeightInfo::remove_did_type()]
+        pub fn remove_did_type(origin, did_type: Vec<u8>) {
+            T::DidTypeOrigin::ensure_origin(origin)?;
+            ensure!(<DidTypes<T>>::contains_key(&did_type), Error::<T>::UnknownDidType);
+
+            <DidTypes<T>>::remove(&did_type);
+
+            Self::deposit_event(RawEvent::DidTypeRemoved(did_type));
+        }
+
+        #[weight = T::WeightInfo::unlock()]
         pub fn unlock(origin, value: T::Balance) {
             let sender = ensure_signed(origin)?;
-
-            let reserved_balance = <pallet_balances::Module<T>>::reserved_balance(&sender);
-            ensure!(reserved_balance >= value, Error::<T>::UnreservedFundsExceed);
+            ensure!(!value.is_zero(), Error::<T>::ZeroUnlock);
 
             let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
             let mut metadata = Self::metadata(&user_key);
-            ensure!(metadata.locked_records.is_some(), Error::<T>::NotLockFunds);
+            let mut locked_records = metadata.locked_records.take().ok_or(Error::<T>::NotLockFunds)?;
 
-            let mut locked_records = metadata.locked_records.unwrap();
-            let LockedRecords { locked_time, locked_period, locked_funds, .. } = locked_records;
             let now = <pallet_timestamp::Module<T>>::get();
-            let unlock_till_time = locked_time.checked_add(&locked_period).ok_or(Error::<T>::Overflow)?;
-
-            ensure!(now >= unlock_till_time, Error::<T>::UnlockTimeNotReach);
+            let matured_funds = locked_records.positions.iter()
+                .filter(|p| p.is_matured(now))
+                .fold(Zero::zero(), |sum: T::Balance, p| sum.saturating_add(p.amount));
+
+            ensure!(!matured_funds.is_zero(), Error::<T>::UnlockTimeNotReach);
+            ensure!(matured_funds >= value, Error::<T>::UnreservedFundsExceed);
+
+            // drain matured positions, oldest first
+            let mut remaining = value;
+            for position in locked_records.positions.iter_mut().filter(|p| p.is_matured(now)) {
+                let taken = remaining.min(position.amount);
+                position.amount -= taken;
+                remaining -= taken;
+            }
+            locked_records.positions.retain(|p| !p.amount.is_zero());
+            Self::refresh_quota(&mut locked_records, metadata.subordinate_count);
+
+            let release_time = now.checked_add(&T::UnbondingPeriod::get()).ok_or(Error::<T>::Overflow)?;
+            let mut unbonding = Self::unbonding(&user_key);
+            match unbonding.last_mut() {
+                Some(chunk) if chunk.release_time == release_time => chunk.amount += value,
+                _ => {
+                    ensure!((unbonding.len() as u32) < T::MaxUnbondingChunks::get(), Error::<T>::TooManyUnbonding);
+                    unbonding.push(UnbondingChunk { amount: value, release_time });
+                }
+            }
 
-            let unlocked_time = <pallet_timestamp::Module<T>>::get();
+            let unlocked_time = now;
             let unlocked_records = UnlockedRecords {
                 unlocked_time,
                 unlocked_funds: value,
             };
 
-            let new_locked_funds = locked_funds - value;
-            let new_max_quota = Self::balance_to_u64(new_locked_funds) * 10;
-            let rewards_ratio = if new_max_quota >= metadata.subordinate_count { 20 } else { 100 * (1 - new_max_quota / metadata.subordinate_count) as u64 };
-
-            locked_records = LockedRecords {
-                locked_funds: new_locked_funds,
-                rewards_ratio,
-                max_quota: new_max_quota,
-                .. locked_records
-            };
+            // drop the oldest entries once the history is full
+            let max_history = T::MaxUnlockHistory::get() as usize;
+            let mut history = Self::unlock_history(&user_key);
+            while !history.is_empty() && history.len() >= max_history {
+                history.remove(0);
+            }
+            history.push(unlocked_records.clone());
 
             metadata.unlocked_records = Some(unlocked_records);
             metadata.locked_records = Some(locked_records);
 
             <Metadata<T>>::insert(user_key, metadata);
-
-            <pallet_balances::Module<T>>::unreserve(&sender, value);
+            <Unbonding<T>>::insert(user_key, unbonding);
+            <UnlockHistory<T>>::insert(user_key, history);
 
             Self::deposit_event(RawEvent::Unlocked(did, value, unlocked_time));
         }
 
-        #[weight = 0]
-        pub fn add_external_address(origin, add_type: Vec<u8>, address: Vec<u8>) {
+        /// Release unlocked funds whose unbonding period is over.
+        #[weight = T::WeightInfo::withdraw_unbonded()]
+        pub fn withdraw_unbonded(origin) {
             let sender = ensure_signed(origin)?;
 
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            let mut unbonding = Self::unbonding(&user_key);
+            let mut withdrawn: T::Balance = Zero::zero();
+            unbonding.retain(|chunk| {
+                if now >= chunk.release_time {
+                    withdrawn += chunk.amount;
+                    false
+                } else {
+                    true
+                }
+            });
+            ensure!(!withdrawn.is_zero(), Error::<T>::NoUnbonded);
+
+            if unbonding.is_empty() {
+                <Unbonding<T>>::remove(&user_key);
+            } else {
+                <Unbonding<T>>::insert(&user_key, unbonding);
+            }
+            <pallet_balances::Module<T>>::unreserve(&sender, withdrawn);
+
+            Self::deposit_event(RawEvent::Withdrawn(did, withdrawn));
+        }
+
+        /// Link an external address, `signature` signs `proof_challenge` with the address' key.
+        ///
+        /// `chain_id` is a CAIP-2 chain id, the legacy `btc`, `eth` and `eos` names map to their
+        /// mainnets. ETH signatures are EIP-191 `personal_sign` (`r || s || v`), BTC ones are
+        /// P2PKH or P2WPKH signed messages (`header || r || s`), Polkadot and Solana ones are raw
+        /// sr25519 and ed25519 signatures. Addresses of other chains are linked without a proof.
+        #[weight = T::WeightInfo::add_external_address()]
+        pub fn add_external_address(origin, chain_id: ChainId, address: Vec<u8>, signature: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            // bounded before any decoding, base58 is quadratic in the input length
+            ensure!(address.len() <= check::MAX_ADDRESS_LENGTH, Error::<T>::InvalidAddressFormat);
+            ensure!(signature.len() <= check::MAX_SIGNATURE_LENGTH, Error::<T>::InvalidProof);
+
             let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
             let mut metadata = Self::metadata(&user_key);
-            let mut external_address = metadata.external_address;
 
-            match &add_type[..] {
-                b"btc" => {
-                    check::from(address.clone()).map_err(|_| Error::<T>::InvalidAddressFormat)?;
-                    external_address.btc = address.clone();
-                },
-                b"eth" => {
-                    ensure!(check::is_valid_eth_address(address.clone()), Error::<T>::InvalidAddressFormat);
-                    external_address.eth = address.clone();
+            let chain_id = check::canonical_chain_id(&chain_id);
+            let chain = check::ChainType::from_chain_id(&chain_id).ok_or(Error::<T>::InvalidType)?;
+            ensure!(chain.is_valid_address(&address), Error::<T>::InvalidAddressFormat);
+
+            let normalized = chain.normalize_address(&address);
+            let addresses = metadata.external_addresses.get(&chain_id);
+            match addresses {
+                Some(addresses) => {
+                    ensure!(!addresses.contains(&normalized), Error::<T>::AddressExists);
+                    ensure!((addresses.len() as u32) < T::MaxAddressesPerChain::get(), Error::<T>::TooManyAddresses);
                 },
-                b"eos" => {
-                    ensure!(check::is_valid_eos_address(address.clone()), Error::<T>::InvalidAddressFormat);
-                    external_address.eos = address.clone();
+                None => {
+                    ensure!((metadata.external_addresses.len() as u32) < T::MaxChains::get(), Error::<T>::TooManyChains);
                 },
-                _ => Err(Error::<T>::InvalidType)?,
-            };
+            }
 
-            metadata.external_address = external_address;
+            // only addresses proven by a signature are unique and indexed
+            let verified = chain.is_verifiable();
+            if verified {
+                ensure!(Self::external_address_owner(&chain_id, &normalized).is_none(), Error::<T>::AddressClaimed);
 
+                let challenge = Self::proof_challenge(user_key);
+                ensure!(Self::verify_address_proof(chain, &address, &challenge, &signature), Error::<T>::InvalidProof);
+                <ProofNonce<T>>::mutate(user_key, |nonce| *nonce += 1);
+            }
+
+            metadata.external_addresses.entry(chain_id.clone()).or_insert_with(Vec::new).push(normalized.clone());
             <Metadata<T>>::insert(user_key, metadata);
+            if verified {
+                <ExternalAddressOwner<T>>::insert(&chain_id, &normalized, user_key);
+            }
+
+            Self::deposit_event(RawEvent::AddressAdded(did, chain_id, normalized));
+        }
+
+        #[weight = T::WeightInfo::remove_external_address()]
+        pub fn remove_external_address(origin, chain_id: ChainId, address: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+
+            let chain_id = check::canonical_chain_id(&chain_id);
+            let chain = check::ChainType::from_chain_id(&chain_id).ok_or(Error::<T>::InvalidType)?;
+            let normalized = chain.normalize_address(&address);
+
+            let addresses = metadata.external_addresses.get_mut(&chain_id).ok_or(Error::<T>::AddressNotExists)?;
+            let pos = addresses.iter().position(|a| *a == normalized).ok_or(Error::<T>::AddressNotExists)?;
+            addresses.remove(pos);
+            if addresses.is_empty() {
+                metadata.external_addresses.remove(&chain_id);
+            }
+            <Metadata<T>>::insert(user_key, metadata);
+
+            if Self::external_address_owner(&chain_id, &normalized) == Some(user_key) {
+                <ExternalAddressOwner<T>>::remove(&chain_id, &normalized);
+            }
+
+            Self::deposit_event(RawEvent::AddressRemoved(did, chain_id, normalized));
+        }
+
+        #[weight = T::WeightInfo::register_attestor()]
+        pub fn register_attestor(origin, attestor: T::AccountId, platform: Vec<u8>, public: Vec<u8>) {
+            ensure_root(origin)?;
+
+            ensure!(!platform.is_empty() && platform.len() <= social::MAX_HANDLE_LENGTH, Error::<T>::InvalidType);
+            ensure!(public.len() == KeyType::Sr25519.public_len(), Error::<T>::InvalidKey);
+
+            <Attestors<T>>::insert(&attestor, Attestor { platform: platform.clone(), public });
+
+            Self::deposit_event(RawEvent::AttestorRegistered(attestor, platform));
+        }
+
+        /// Stop accepting new attestations of an attestor, bindings it attested stay.
+        #[weight = T::WeightInfo::remove_attestor()]
+        pub fn remove_attestor(origin, attestor: T::AccountId) {
+            ensure_root(origin)?;
+            ensure!(<Attestors<T>>::contains_key(&attestor), Error::<T>::AttestorNotExists);
+
+            <Attestors<T>>::remove(&attestor);
+
+            Self::deposit_event(RawEvent::AttestorRemoved(attestor));
+        }
+
+        /// Bind an attested social account, taking it over if it was bound without an attestation.
+        #[weight = T::WeightInfo::bind_social_account()]
+        pub fn bind_social_account(origin, proof: SocialProof<T::AccountId>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+            ensure!(metadata.social_account.is_none(), Error::<T>::AlreadyBound);
+
+            let (social_hash, attestation) = Self::check_social_proof(user_key, &did, &proof)?;
+            Self::ensure_social_available(social_hash, user_key)?;
+
+            Self::bind_social(user_key, &mut metadata, social_hash, attestation);
+            <Metadata<T>>::insert(&user_key, metadata);
+
+            Self::deposit_event(RawEvent::SocialBound(did, social_hash, proof.attestor));
+        }
+
+        /// Replace the bound social account, e.g. after the handle was renamed.
+        #[weight = T::WeightInfo::rebind_social_account()]
+        pub fn rebind_social_account(origin, proof: SocialProof<T::AccountId>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+            ensure!(metadata.social_account.is_some(), Error::<T>::NotBound);
+
+            let (social_hash, attestation) = Self::check_social_proof(user_key, &did, &proof)?;
+            Self::ensure_social_available(social_hash, user_key)?;
+
+            if let Some(old_hash) = Self::unbind_social(user_key, &mut metadata) {
+                Self::deposit_event(RawEvent::SocialUnbound(did.clone(), old_hash));
+            }
+            Self::bind_social(user_key, &mut metadata, social_hash, attestation);
+            <Metadata<T>>::insert(&user_key, metadata);
+
+            Self::deposit_event(RawEvent::SocialBound(did, social_hash, proof.attestor));
+        }
+
+        #[weight = T::WeightInfo::unbind_social_account()]
+        pub fn unbind_social_account(origin) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut metadata = Self::metadata(&user_key);
+
+            let social_hash = Self::unbind_social(user_key, &mut metadata).ok_or(Error::<T>::NotBound)?;
+            <Metadata<T>>::insert(&user_key, metadata);
+
+            Self::deposit_event(RawEvent::SocialUnbound(did, social_hash));
+        }
+
+        /// Claim an attested social account which is bound to another DID with an attestation of
+        /// its own, `DisputeDeposit` is reserved until root resolves the dispute.
+        #[weight = T::WeightInfo::dispute_social_account()]
+        pub fn dispute_social_account(origin, proof: SocialProof<T::AccountId>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(Self::metadata(&user_key).social_account.is_none(), Error::<T>::AlreadyBound);
+
+            let (social_hash, attestation) = Self::check_social_proof(user_key, &did, &proof)?;
+            ensure!(<SocialAttestation<T>>::contains_key(&social_hash), Error::<T>::NotBound);
+            ensure!(!<SocialDisputes<T>>::contains_key(&social_hash), Error::<T>::DisputeExists);
+
+            let deposit = T::DisputeDeposit::get();
+            <pallet_balances::Module<T>>::reserve(&sender, deposit)?;
+
+            <SocialDisputes<T>>::insert(&social_hash, SocialDispute {
+                claimant: user_key,
+                who: sender,
+                deposit,
+                attestation,
+            });
+            <ProofNonce<T>>::mutate(&user_key, |nonce| *nonce += 1);
+
+            Self::deposit_event(RawEvent::SocialDisputed(did, social_hash));
+        }
+
+        /// Settle a dispute, the claimant's deposit is returned if it wins and slashed otherwise.
+        #[weight = T::WeightInfo::resolve_social_dispute()]
+        pub fn resolve_social_dispute(origin, social_hash: T::Hash, in_favor_of_claimant: bool) {
+            ensure_root(origin)?;
+
+            Self::settle_dispute(social_hash, in_favor_of_claimant)?;
+
+            Self::deposit_event(RawEvent::SocialDisputeResolved(social_hash, in_favor_of_claimant));
+        }
+
+        /// Nominate guardian DIDs, `threshold` of which may move the DID to a new account.
+        #[weight = T::WeightInfo::set_recovery(guardians.len() as u32)]
+        pub fn set_recovery(origin, guardians: Vec<T::Hash>, threshold: u32) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(!<ActiveRecoveries<T>>::contains_key(&user_key), Error::<T>::RecoveryInProgress);
+            Self::ensure_valid_recovery(user_key, &guardians, threshold)?;
+
+            <RecoveryConfigs<T>>::insert(&user_key, RecoveryConfig { guardians: guardians.clone(), threshold });
+
+            Self::deposit_event(RawEvent::RecoverySet(did, guardians, threshold));
+        }
+
+        #[weight = T::WeightInfo::remove_recovery()]
+        pub fn remove_recovery(origin) {
+            let sender = ensure_signed(origin)?;
 
-            Self::deposit_event(RawEvent::AddressAdded(did, add_type, address));
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(<RecoveryConfigs<T>>::contains_key(&user_key), Error::<T>::RecoveryNotExists);
+            ensure!(!<ActiveRecoveries<T>>::contains_key(&user_key), Error::<T>::RecoveryInProgress);
+
+            <RecoveryConfigs<T>>::remove(&user_key);
+
+            Self::deposit_event(RawEvent::RecoveryRemoved(did));
         }
 
-        #[weight = 0]
+        /// Vouch, as a guardian of `lost`, for moving it to `new_account`. A guardian backs one
+        /// account at a time, the first account `threshold` guardians back may claim the DID.
+        #[weight = T::WeightInfo::vouch_recovery()]
+        pub fn vouch_recovery(origin, lost: T::Hash, new_account: T::AccountId) {
+            let sender = ensure_signed(origin)?;
+
+            let (guardian, _) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(<Metadata<T>>::contains_key(&lost), Error::<T>::DidNotExists);
+            ensure!(Self::identity(&new_account).is_none(), Error::<T>::PublicKeyUsed);
+
+            let ready_at = Self::record_vouch(lost, guardian, new_account.clone())?;
+
+            let did = Self::metadata(&lost).did;
+            Self::deposit_event(RawEvent::RecoveryVouched(did.clone(), guardian, new_account.clone()));
+            if let Some(ready_at) = ready_at {
+                Self::deposit_event(RawEvent::RecoveryReady(did, new_account, ready_at));
+            }
+        }
+
+        /// Cancel a recovery of the sender's DID, e.g. one the owner did not ask for.
+        #[weight = T::WeightInfo::cancel_recovery()]
+        pub fn cancel_recovery(origin) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(<ActiveRecoveries<T>>::contains_key(&user_key), Error::<T>::RecoveryNotExists);
+
+            <ActiveRecoveries<T>>::remove(&user_key);
+
+            Self::deposit_event(RawEvent::RecoveryCancelled(did));
+        }
+
+        /// Take control of `lost` with the account the guardians vouched for once the delay passed.
+        /// Funds the DID reserved move along, the free balance of the old account stays.
+        #[weight = T::WeightInfo::claim_recovery()]
+        pub fn claim_recovery(origin, lost: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(Self::identity(&sender).is_none(), Error::<T>::PublicKeyUsed);
+            let did = Self::finish_recovery(lost, &sender)?;
+
+            Self::deposit_event(RawEvent::Recovered(did, sender));
+        }
+
+        /// Create a group owned by the caller's DID, which must have locked funds.
+        /// `GroupDeposit` is reserved until the group is dissolved.
+        #[weight = T::WeightInfo::create_group()]
+        pub fn create_group(origin, name: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            ensure!(Self::metadata(&user_key).locked_records.is_some(), Error::<T>::NotEligible);
+            Self::ensure_valid_group_name(&name)?;
+
+            let group_id = Self::next_group_id();
+            let next_id = group_id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+            let deposit = T::GroupDeposit::get();
+            let group = Group {
+                name: name.clone(),
+                owner: user_key,
+                member_count: 0,
+                invite_count: 0,
+                depositor: sender.clone(),
+                deposit,
+            };
+            Self::ensure_may_join(&group, user_key)?;
+
+            <pallet_balances::Module<T>>::reserve(&sender, deposit)?;
+            <NextGroupId>::put(next_id);
+            <GroupNames>::insert(&name, group_id);
+            Self::join_group(group_id, group, user_key, GroupRole::Admin);
+
+            Self::deposit_event(RawEvent::GroupCreated(group_id, did, name));
+        }
+
+        /// Remove a group with all its members and invitations, its name may be taken again.
+        #[weight = T::WeightInfo::dissolve_group(
+            T::MaxGroupMembers::get(),
+            T::MaxGroupMembers::get().saturating_sub(1)
+        )]
+        pub fn dissolve_group(origin, group_id: GroupId) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let group = Self::ensure_group_manager(group_id, user_key, GroupRole::Admin)?;
+
+            for (member, _) in Self::group_members(group_id) {
+                <DidGroups<T>>::mutate(&member, |groups| groups.retain(|id| *id != group_id));
+            }
+            for (invited, _) in Self::group_invites(group_id) {
+                <DidInvites<T>>::mutate(&invited, |invites| invites.retain(|id| *id != group_id));
+            }
+            <GroupMembers<T>>::remove_prefix(group_id);
+            <GroupInvites<T>>::remove_prefix(group_id);
+            <GroupNames>::remove(&group.name);
+            <Groups<T>>::remove(group_id);
+            <pallet_balances::Module<T>>::unreserve(&group.depositor, group.deposit);
+
+            Self::deposit_event(RawEvent::GroupDissolved(group_id));
+        }
+
+        /// Invite a DID to a group, only the owner may invite admins. Invitations take places
+        /// in the group until they are accepted or withdrawn, inviting a DID again changes
+        /// its role.
+        #[weight = T::WeightInfo::invite_member()]
+        pub fn invite_member(origin, group_id: GroupId, member: T::Hash, role: GroupRole) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let mut group = Self::ensure_group_manager(group_id, user_key, role)?;
+            ensure!(<Metadata<T>>::contains_key(&member), Error::<T>::DidNotExists);
+            ensure!(!<GroupMembers<T>>::contains_key(group_id, &member), Error::<T>::AlreadyGroupMember);
+
+            if !<GroupInvites<T>>::contains_key(group_id, &member) {
+                ensure!(
+                    group.member_count.saturating_add(group.invite_count) < T::MaxGroupMembers::get(),
+                    Error::<T>::GroupFull
+                );
+                let mut invites = Self::invites_of(&member);
+                ensure!((invites.len() as u32) < T::MaxGroupsPerDid::get(), Error::<T>::TooManyInvites);
+                invites.push(group_id);
+
+                group.invite_count += 1;
+                <Groups<T>>::insert(group_id, group);
+                <DidInvites<T>>::insert(&member, invites);
+            }
+            <GroupInvites<T>>::insert(group_id, &member, role);
+
+            Self::deposit_event(RawEvent::MemberInvited(group_id, member, role));
+        }
+
+        #[weight = T::WeightInfo::accept_invite()]
+        pub fn accept_invite(origin, group_id: GroupId) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, did) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let role = Self::group_invite(group_id, &user_key).ok_or(Error::<T>::InviteNotExists)?;
+            let mut group = Self::group(group_id).ok_or(Error::<T>::GroupNotExists)?;
+            Self::ensure_may_join(&group, user_key)?;
+
+            <GroupInvites<T>>::remove(group_id, &user_key);
+            <DidInvites<T>>::mutate(&user_key, |invites| invites.retain(|id| *id != group_id));
+            group.invite_count = group.invite_count.saturating_sub(1);
+            Self::join_group(group_id, group, user_key, role);
+
+            Self::deposit_event(RawEvent::MemberJoined(group_id, did, role));
+        }
+
+        /// Remove a member or withdraw its invitation, members may remove themselves and only
+        /// the owner may remove admins.
+        #[weight = T::WeightInfo::remove_member()]
+        pub fn remove_member(origin, group_id: GroupId, member: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let group = Self::group(group_id).ok_or(Error::<T>::GroupNotExists)?;
+            ensure!(member != group.owner, Error::<T>::IsGroupOwner);
+
+            let role = Self::group_member(group_id, &member).or_else(|| Self::group_invite(group_id, &member));
+            let role = role.ok_or(Error::<T>::NotGroupMember)?;
+            if member != user_key {
+                Self::ensure_group_manager(group_id, user_key, role)?;
+            }
+
+            Self::withdraw_invite(group_id, member);
+            if <GroupMembers<T>>::contains_key(group_id, &member) {
+                Self::leave_group(group_id, member);
+            }
+
+            Self::deposit_event(RawEvent::MemberRemoved(group_id, member));
+        }
+
+        #[weight = T::WeightInfo::set_member_role()]
+        pub fn set_member_role(origin, group_id: GroupId, member: T::Hash, role: GroupRole) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = Self::identity(&sender).ok_or(Error::<T>::DidNotExists)?;
+            let group = Self::ensure_group_manager(group_id, user_key, GroupRole::Admin)?;
+            ensure!(member != group.owner, Error::<T>::IsGroupOwner);
+            ensure!(<GroupMembers<T>>::contains_key(group_id, &member), Error::<T>::NotGroupMember);
+
+            <GroupMembers<T>>::insert(group_id, &member, role);
+
+            Self::deposit_event(RawEvent::MemberRoleSet(group_id, member, role));
+        }
+
+        /// Label the caller's DID with a group name, groups with members are created by
+        /// `create_group`.
+        #[weight = T::WeightInfo::set_group_name()]
         pub fn set_group_name(origin, name: Vec<u8>) {
             let sender = ensure_signed(origin)?;
 
@@ -521,7 +1587,7 @@ decl_module! {
             Self::deposit_event(RawEvent::GroupNameSet(did, name));
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::judge()]
         fn judge(origin, account: T::AccountId) {
             let sender = ensure_signed(origin)?;
 
@@ -536,49 +1602,158 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-    fn migrate() {
-        use frame_support::{migration::StorageKeyIterator, Twox64Concat};
-        for (
-            who,
-            OldMetadataRecord {
-                address,
-                superior,
-                creator,
-                did,
-                locked_records,
-                unlocked_records,
-                is_partner,
-                social_account,
-                subordinate_count,
-                group_name,
-                external_address,
+    /// Move the funds locked and unbonding for a DID to another account, still reserved.
+    /// Service deposits stay with whoever paid them.
+    fn move_reserved(user_key: T::Hash, from: &T::AccountId, to: &T::AccountId) -> DispatchResult {
+        let metadata = Self::metadata(&user_key);
+        let mut reserved = metadata
+            .locked_records
+            .as_ref()
+            .map_or_else(Zero::zero, |r| r.locked_funds());
+        for chunk in Self::unbonding(&user_key) {
+            reserved += chunk.amount;
+        }
+
+        <pallet_balances::Module<T> as ReservableCurrency<_>>::repatriate_reserved(
+            from,
+            to,
+            reserved,
+            BalanceStatus::Reserved,
+        )?;
+        Ok(())
+    }
+
+    /// Move the DID to another controlling account, the caller handles balances.
+    fn rebind_account(user_key: T::Hash, from: &T::AccountId, to: &T::AccountId) -> DispatchResult {
+        let mut metadata = Self::metadata(&user_key);
+        metadata.address = to.clone();
+
+        // keep the controlling account key in step with the new address
+        let old_public = from.encode();
+        if metadata.keys.iter().any(|k| k.public == old_public) {
+            let now = <pallet_timestamp::Module<T>>::get();
+            Self::take_key(&mut metadata, &old_public, now)?;
+            Self::push_key(&mut metadata, KeyType::Sr25519, to.encode(), now);
+        }
+
+        // update address => did map
+        <Identity<T>>::remove(from);
+        <Identity<T>>::insert(to, (&user_key, &metadata.did));
+
+        // update user_key => address
+        <IdentityOf<T>>::insert(user_key, to);
+
+        <Metadata<T>>::insert(user_key, metadata);
+        Ok(())
+    }
+
+    fn ensure_new_key(
+        metadata: &MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        key_type: KeyType,
+        public: &[u8],
+    ) -> DispatchResult {
+        ensure!(public.len() == key_type.public_len(), Error::<T>::InvalidKey);
+        ensure!(
+            !metadata.keys.iter().any(|k| k.public == public),
+            Error::<T>::KeyExists
+        );
+        Ok(())
+    }
+
+    fn push_key(
+        metadata: &mut MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        key_type: KeyType,
+        public: Vec<u8>,
+        now: T::Moment,
+    ) {
+        metadata.keys.push(AuthKey {
+            key_type,
+            public: public.clone(),
+            added_time: now,
+        });
+        Self::record_key_event(metadata, KeyAction::Added, key_type, public, now);
+    }
+
+    fn take_key(
+        metadata: &mut MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        public: &[u8],
+        now: T::Moment,
+    ) -> DispatchResult {
+        let pos = metadata
+            .keys
+            .iter()
+            .position(|k| k.public == public)
+            .ok_or(Error::<T>::KeyNotExists)?;
+        let AuthKey {
+            key_type, public, ..
+        } = metadata.keys.remove(pos);
+        Self::record_key_event(metadata, KeyAction::Removed, key_type, public, now);
+        Ok(())
+    }
+
+    fn record_key_event(
+        metadata: &mut MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        action: KeyAction,
+        key_type: KeyType,
+        public: Vec<u8>,
+        time: T::Moment,
+    ) {
+        // drop the oldest entries once the history is full
+        let max_history = T::MaxKeyHistory::get() as usize;
+        while !metadata.key_history.is_empty() && metadata.key_history.len() >= max_history {
+            metadata.key_history.remove(0);
+        }
+        metadata.key_history.push(KeyEvent {
+            action,
+            key_type,
+            public,
+            time,
+        });
+    }
+
+    fn ensure_valid_service(id: &[u8], service_type: &[u8], endpoint: &[u8]) -> DispatchResult {
+        let max_type_len = T::MaxServiceTypeLength::get() as usize;
+        let max_url_len = T::MaxServiceUrlLength::get() as usize;
+
+        ensure!(
+            !id.is_empty() && id.len() <= max_type_len,
+            Error::<T>::InvalidService
+        );
+        ensure!(
+            !service_type.is_empty() && service_type.len() <= max_type_len,
+            Error::<T>::InvalidService
+        );
+        ensure!(
+            !endpoint.is_empty() && endpoint.len() <= max_url_len,
+            Error::<T>::InvalidService
+        );
+        Ok(())
+    }
+
+    fn verify_address_proof(
+        chain: check::ChainType,
+        address: &[u8],
+        challenge: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        match chain {
+            check::ChainType::Btc => match check::btc_pubkey_hash(address) {
+                Some(pubkey_hash) => proof::verify_btc_signature(&pubkey_hash, challenge, signature),
+                None => false,
             },
-        ) in StorageKeyIterator::<
-            T::Hash,
-            OldMetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
-            Twox64Concat,
-        >::new(b"Did", b"Metadata")
-        .drain()
-        {
-            let donate = if is_partner {
-                Some(Self::fee_to_previous())
-            } else {
-                None
-            };
-            let new_metadata = MetadataRecord {
-                address,
-                superior,
-                creator,
-                did,
-                locked_records,
-                unlocked_records,
-                donate,
-                social_account,
-                subordinate_count,
-                group_name,
-                external_address,
-            };
-            Metadata::<T>::insert(who, new_metadata)
+            check::ChainType::Eth => match proof::recover_eth_address(challenge, signature) {
+                Some(signer) => check::to_hex(&signer) == check::normalize_eth_address(address),
+                None => false,
+            },
+            check::ChainType::Polkadot => match check::ss58_public_key(address) {
+                Some(public) => proof::verify_sr25519(&public, challenge, signature),
+                None => false,
+            },
+            check::ChainType::Solana => match check::solana_public_key(address) {
+                Some(public) => proof::verify_ed25519(&public, challenge, signature),
+                None => false,
+            },
+            check::ChainType::Eos | check::ChainType::Tron | check::ChainType::Other => true,
         }
     }
 
@@ -590,19 +1765,6 @@ impl<T: Config> Module<T> {
         input.saturated_into::<u64>()
     }
 
-    fn is_sub(mut haystack: &[u8], needle: &[u8]) -> bool {
-        if needle.len() == 0 {
-            return true;
-        }
-        while !haystack.is_empty() {
-            if haystack.starts_with(needle) {
-                return true;
-            }
-            haystack = &haystack[1..];
-        }
-        false
-    }
-
     fn generate_did(pubkey: &[u8], did_type: &[u8]) -> Vec<u8> {
         // 通过公钥生成hash值
         let hash = blake2_256(pubkey);
@@ -625,12 +1787,59 @@ impl<T: Config> Module<T> {
 }
 
 impl<T: Config> Module<T> {
+    /// The message an external wallet signs to link its address to the DID.
+    pub fn proof_challenge(user_key: T::Hash) -> Vec<u8> {
+        let did = Self::metadata(&user_key).did;
+        proof::challenge(&Self::did_uri(&did), Self::proof_nonce(&user_key))
+    }
+
+    /// Move `value` between the accounts of two DIDs, `memo` is carried in the event as is.
     pub fn transfer_by_did(
         from_user: T::Hash,
         to_user: T::Hash,
         value: T::Balance,
+        kind: TransferKind,
         memo: Vec<u8>,
     ) -> DispatchResult {
+        let (from_did, to_did, ..) = Self::move_funds(from_user, to_user, value, kind)?;
+
+        Self::deposit_event(RawEvent::Transfered(from_did, to_did, value, kind, memo));
+
+        Ok(())
+    }
+
+    /// Transfer to every entry in turn, each split on its own. The caller rolls back the
+    /// transfers already made if one fails.
+    pub fn transfer_batch_by_did(
+        from_user: T::Hash,
+        transfers: Vec<(T::Hash, T::Balance, Vec<u8>)>,
+        kind: TransferKind,
+    ) -> DispatchResult {
+        ensure!(
+            !transfers.is_empty() && transfers.len() as u32 <= T::MaxBatchTransfers::get(),
+            Error::<T>::InvalidBatch
+        );
+
+        let mut from_did = Vec::new();
+        let mut results = Vec::with_capacity(transfers.len());
+        for (to_user, value, memo) in transfers {
+            let (did, to_did, received, to_superior) = Self::move_funds(from_user, to_user, value, kind)?;
+            from_did = did;
+            results.push((to_did, received, to_superior, memo));
+        }
+
+        Self::deposit_event(RawEvent::BatchTransfered(from_did, kind, results));
+
+        Ok(())
+    }
+
+    /// Returns both DIDs and what the receiver and its superior got.
+    fn move_funds(
+        from_user: T::Hash,
+        to_user: T::Hash,
+        value: T::Balance,
+        kind: TransferKind,
+    ) -> Result<(Did, Did, T::Balance, T::Balance), DispatchError> {
         ensure!(
             <Metadata<T>>::contains_key(&to_user),
             Error::<T>::DidNotExists
@@ -664,17 +1873,11 @@ impl<T: Config> Module<T> {
             .ok_or(Error::<T>::Overflow)?;
 
         // proceeds split
-        let fee_type = b"ads";
-        if Self::is_sub(&memo, fee_type) {
+        if kind == TransferKind::AdReward {
             let superior_address =
                 Self::identity_of(superior).ok_or(Error::<T>::SuperiorNotExists)?;
 
-            let MetadataRecord { locked_records, .. } = Self::metadata(superior);
-            let rewards_ratio = if locked_records.is_some() {
-                locked_records.unwrap().rewards_ratio
-            } else {
-                0
-            };
+            let rewards_ratio = Self::rewards_ratio_of(&Self::metadata(superior));
 
             let fee_to_superior = value.clone() * Self::u128_to_balance(rewards_ratio.into())
                 / Self::u128_to_balance(100);
@@ -693,6 +1896,8 @@ impl<T: Config> Module<T> {
                 fee_to_user,
                 ExistenceRequirement::AllowDeath,
             )?;
+
+            Ok((from_did, to_did, fee_to_user, fee_to_superior))
         } else {
             <pallet_balances::Module<T> as Currency<_>>::transfer(
                 &from_address,
@@ -700,10 +1905,8 @@ impl<T: Config> Module<T> {
                 value,
                 ExistenceRequirement::AllowDeath,
             )?;
-        }
 
-        Self::deposit_event(RawEvent::Transfered(from_did, to_did, value, memo));
-
-        Ok(())
+            Ok((from_did, to_did, value, Zero::zero()))
+        }
     }
 }
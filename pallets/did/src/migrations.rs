@@ -0,0 +1,570 @@
+use super::*;
+use frame_support::{
+    storage::migration::{
+        get_storage_value, put_storage_value, take_storage_value, StorageIterator,
+        StorageKeyIterator,
+    },
+    IterableStorageDoubleMap, IterableStorageMap, StorageHasher, StoragePrefixedMap,
+    Twox64Concat,
+};
+#[cfg(any(feature = "try-runtime", test))]
+use sp_io::hashing::twox_128;
+
+/// Layouts the pallet's storage went through, each migration steps one version ahead.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Releases {
+    /// `Metadata` under the `Did` prefix, partners flagged by `is_partner`.
+    V0LegacyPrefix,
+    /// Partners recorded by what they donated.
+    V1Donate,
+    /// DIDs carry a key set and key history.
+    V2KeySet,
+    /// External addresses keyed by chain id and indexed by address.
+    V3ExternalAddresses,
+    /// A DID may hold several lock positions.
+    V4LockPositions,
+    /// Locks are rebated by `ReferralSchedule`.
+    V5RewardSchedule,
+    /// Subordinates are indexed under their superior.
+    V6ChildIndex,
+    /// DID types are registered with their capabilities.
+    V7DidTypes,
+    /// Subordinates know their place in the index.
+    V8ChildPositions,
+}
+
+impl Releases {
+    pub const LATEST: Releases = Releases::V8ChildPositions;
+}
+
+/// Module prefix `Metadata` was stored under before the pallet was renamed.
+const LEGACY_PREFIX: &[u8] = b"Did";
+
+/// Flags marking finished migrations before `StorageVersion`, with the version each one
+/// reached, latest first.
+const LEGACY_FLAGS: [(&[u8], Releases); 5] = [
+    (b"UpgradedToChildIndex", Releases::V6ChildIndex),
+    (b"UpgradedToRewardSchedule", Releases::V5RewardSchedule),
+    (b"UpgradedToLockPositions", Releases::V4LockPositions),
+    (
+        b"UpgradedToExternalAddresses",
+        Releases::V3ExternalAddresses,
+    ),
+    (b"UpgradedToKeySet", Releases::V2KeySet),
+];
+
+/// Where the `try-runtime` hooks keep the number of DIDs between `pre_upgrade` and
+/// `post_upgrade`.
+#[cfg(feature = "try-runtime")]
+pub const TRY_RUNTIME_DIDS: &[u8] = b":did:try_runtime:dids";
+
+/// `MetadataRecord` layout under the legacy prefix.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct LegacyMetadataRecord<AccountId, Hash, Balance, Moment> {
+    address: AccountId,
+    superior: Hash,
+    creator: AccountId,
+    did: Did,
+    locked_records: Option<SingleLockedRecords<Balance, Moment>>,
+    unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
+    is_partner: bool,
+    social_account: Option<Hash>,
+    subordinate_count: u64,
+    group_name: Option<Vec<u8>>,
+    external_address: ExternalAddress,
+}
+
+/// The fixed one-address-per-chain slots `MetadataRecord` used to carry.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct ExternalAddress {
+    btc: Vec<u8>,
+    eth: Vec<u8>,
+    eos: Vec<u8>,
+}
+
+/// The single lock `LockedRecords` used to carry, a new lock merged into it.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct SingleLockedRecords<Balance, Moment> {
+    locked_time: Moment,
+    locked_period: Moment,
+    locked_funds: Balance,
+    rewards_ratio: u64,
+    max_quota: u64,
+}
+
+/// `MetadataRecord` layout before authentication keys were tracked.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct MetadataRecordWithoutKeys<AccountId, Hash, Balance, Moment> {
+    address: AccountId,
+    superior: Hash,
+    creator: AccountId,
+    did: Did,
+    locked_records: Option<SingleLockedRecords<Balance, Moment>>,
+    unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
+    donate: Option<Balance>,
+    social_account: Option<Hash>,
+    subordinate_count: u64,
+    group_name: Option<Vec<u8>>,
+    external_address: ExternalAddress,
+}
+
+/// `MetadataRecord` layout before external addresses were keyed by chain id.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct MetadataRecordWithExternalAddress<AccountId, Hash, Balance, Moment> {
+    address: AccountId,
+    superior: Hash,
+    creator: AccountId,
+    did: Did,
+    locked_records: Option<SingleLockedRecords<Balance, Moment>>,
+    unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
+    donate: Option<Balance>,
+    social_account: Option<Hash>,
+    subordinate_count: u64,
+    group_name: Option<Vec<u8>>,
+    external_address: ExternalAddress,
+    keys: Vec<AuthKey<Moment>>,
+    key_history: Vec<KeyEvent<Moment>>,
+}
+
+/// `MetadataRecord` layout before a DID could hold several lock positions.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct MetadataRecordWithSingleLock<AccountId, Hash, Balance, Moment> {
+    address: AccountId,
+    superior: Hash,
+    creator: AccountId,
+    did: Did,
+    locked_records: Option<SingleLockedRecords<Balance, Moment>>,
+    unlocked_records: Option<UnlockedRecords<Balance, Moment>>,
+    donate: Option<Balance>,
+    social_account: Option<Hash>,
+    subordinate_count: u64,
+    group_name: Option<Vec<u8>>,
+    external_addresses: BTreeMap<ChainId, Vec<Vec<u8>>>,
+    keys: Vec<AuthKey<Moment>>,
+    key_history: Vec<KeyEvent<Moment>>,
+}
+
+/// The version of the pallet's storage, derived from the legacy flags if it predates
+/// `StorageVersion`.
+pub fn on_chain_version<T: Config>() -> Releases {
+    if let Some(version) = <Module<T>>::storage_version() {
+        return version;
+    }
+
+    let module = <Metadata<T>>::module_prefix();
+    LEGACY_FLAGS
+        .iter()
+        .find(|(flag, _)| get_storage_value::<bool>(module, flag, &[]).unwrap_or(false))
+        .map_or(Releases::V0LegacyPrefix, |(_, version)| *version)
+}
+
+/// Run every migration between the on-chain version and `Releases::LATEST` once, in order.
+pub fn migrate<T: Config>() -> Weight {
+    let version = on_chain_version::<T>();
+    let mut weight = T::DbWeight::get().reads(1 + LEGACY_FLAGS.len() as Weight);
+    if <Module<T>>::storage_version() == Some(Releases::LATEST) {
+        return weight;
+    }
+
+    let steps: [(Releases, fn() -> Weight); 8] = [
+        (Releases::V0LegacyPrefix, migrate_from_legacy_prefix::<T>),
+        (Releases::V1Donate, migrate_to_key_set::<T>),
+        (Releases::V2KeySet, migrate_to_external_addresses::<T>),
+        (
+            Releases::V3ExternalAddresses,
+            migrate_to_lock_positions::<T>,
+        ),
+        (Releases::V4LockPositions, migrate_to_reward_schedule::<T>),
+        (Releases::V5RewardSchedule, migrate_to_child_index::<T>),
+        (Releases::V6ChildIndex, migrate_to_did_types::<T>),
+        (Releases::V7DidTypes, migrate_to_child_positions::<T>),
+    ];
+    for (from, step) in steps.iter() {
+        if version <= *from {
+            weight = weight.saturating_add(step());
+        }
+    }
+
+    let module = <Metadata<T>>::module_prefix();
+    for (flag, _) in LEGACY_FLAGS.iter() {
+        take_storage_value::<bool>(module, flag, &[]);
+    }
+    <StorageVersion>::put(Releases::LATEST);
+
+    weight.saturating_add(T::DbWeight::get().writes(1 + LEGACY_FLAGS.len() as Weight))
+}
+
+/// Raw keys of every value stored under `prefix`.
+#[cfg(any(feature = "try-runtime", test))]
+fn keys_under(prefix: &[u8]) -> Vec<Vec<u8>> {
+    let mut keys = Vec::new();
+    let mut previous = prefix.to_vec();
+    while let Some(next) = sp_io::storage::next_key(&previous) {
+        if !next.starts_with(prefix) {
+            break;
+        }
+        keys.push(next.clone());
+        previous = next;
+    }
+    keys
+}
+
+/// Check that every value under `prefix` decodes as `R`, returns how many there are.
+#[cfg(any(feature = "try-runtime", test))]
+fn check_layout<R: codec::DecodeAll>(prefix: &[u8]) -> Result<u32, &'static str> {
+    let keys = keys_under(prefix);
+    for key in keys.iter() {
+        let value = sp_io::storage::get(key).ok_or("metadata vanished")?;
+        R::decode_all(&value[..]).map_err(|_| "metadata does not match the on-chain version")?;
+    }
+    Ok(keys.len() as u32)
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+fn legacy_prefix() -> Vec<u8> {
+    [twox_128(LEGACY_PREFIX), twox_128(b"Metadata")].concat()
+}
+
+/// Check the stored records match the on-chain version, returns the number of DIDs.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn pre_upgrade<T: Config>() -> Result<u32, &'static str> {
+    let prefix = <Metadata<T>>::final_prefix();
+    let legacy = check_layout::<LegacyMetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>>(
+        &legacy_prefix(),
+    )?;
+    let current = match on_chain_version::<T>() {
+        Releases::V0LegacyPrefix | Releases::V1Donate => check_layout::<
+            MetadataRecordWithoutKeys<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        >(&prefix)?,
+        Releases::V2KeySet => check_layout::<
+            MetadataRecordWithExternalAddress<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        >(&prefix)?,
+        Releases::V3ExternalAddresses => check_layout::<
+            MetadataRecordWithSingleLock<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        >(&prefix)?,
+        _ => check_layout::<MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>>(&prefix)?,
+    };
+    if legacy > 0 && on_chain_version::<T>() > Releases::V0LegacyPrefix {
+        return Err("records left under the legacy prefix");
+    }
+
+    Ok(legacy + current)
+}
+
+/// Check the migrations ran in full and kept every DID.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn post_upgrade<T: Config>(dids: u32) -> Result<(), &'static str> {
+    ensure!(
+        <Module<T>>::storage_version() == Some(Releases::LATEST),
+        "storage version not bumped"
+    );
+    ensure!(
+        keys_under(&legacy_prefix()).is_empty(),
+        "records left under the legacy prefix"
+    );
+    let module = <Metadata<T>>::module_prefix();
+    ensure!(
+        LEGACY_FLAGS
+            .iter()
+            .all(|(flag, _)| get_storage_value::<bool>(module, flag, &[]).is_none()),
+        "legacy upgrade flags left behind"
+    );
+    ensure!(
+        check_layout::<MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>>(
+            &<Metadata<T>>::final_prefix()
+        )? == dids,
+        "number of DIDs changed"
+    );
+
+    let mut children = BTreeMap::<T::Hash, u32>::new();
+    for (user_key, metadata) in <Metadata<T>>::iter() {
+        if metadata.superior != user_key && <Metadata<T>>::contains_key(&metadata.superior) {
+            *children.entry(metadata.superior).or_default() += 1;
+        }
+    }
+    ensure!(
+        <SubordinateTotal<T>>::iter()
+            .all(|(superior, total)| children.remove(&superior) == Some(total))
+            && children.is_empty(),
+        "subordinate index out of step with superiors"
+    );
+    ensure!(
+        <Subordinates<T>>::iter().all(|(_, page, children)| children
+            .iter()
+            .enumerate()
+            .all(|(pos, child)| <Module<T>>::child_position(child) == Some((page, pos as u32)))),
+        "subordinate positions out of step with the index"
+    );
+
+    Ok(())
+}
+
+/// Rewrite every `Metadata` value from layout `O` to layout `N`, returns the number of records.
+///
+/// Unlike `translate` the new layout does not have to be the current one, so migrations
+/// can be chained.
+fn translate_metadata<T, O, N, F>(mut f: F) -> Weight
+where
+    T: Config,
+    O: Decode,
+    N: Encode,
+    F: FnMut(O) -> N,
+{
+    let module = <Metadata<T>>::module_prefix();
+    let item = <Metadata<T>>::storage_prefix();
+
+    let mut count: Weight = 0;
+    for (key, old) in StorageIterator::<O>::new(module, item) {
+        put_storage_value(module, item, &key, f(old));
+        count += 1;
+    }
+    count
+}
+
+/// Move the fixed `btc`, `eth` and `eos` slots under their CAIP-2 chain ids.
+pub fn external_address_map(old: ExternalAddress) -> BTreeMap<ChainId, Vec<Vec<u8>>> {
+    let ExternalAddress { btc, eth, eos } = old;
+
+    let mut map = BTreeMap::new();
+    for (chain_id, address) in [
+        (check::BTC_CHAIN_ID, btc),
+        (check::ETH_CHAIN_ID, eth),
+        (check::EOS_CHAIN_ID, eos),
+    ]
+    .iter()
+    {
+        if address.is_empty() {
+            continue;
+        }
+        if let Some(chain) = check::ChainType::from_chain_id(chain_id) {
+            map.insert(
+                chain_id.to_vec(),
+                sp_std::vec![chain.normalize_address(address)],
+            );
+        }
+    }
+    map
+}
+
+/// Turn a merged lock into a single position, keeping its quota and rewards ratio.
+pub fn lock_positions<Balance: Zero, Moment>(
+    old: SingleLockedRecords<Balance, Moment>,
+) -> LockedRecords<Balance, Moment> {
+    let SingleLockedRecords {
+        locked_time,
+        locked_period,
+        locked_funds,
+        rewards_ratio,
+        max_quota,
+    } = old;
+
+    let mut positions = Vec::new();
+    if !locked_funds.is_zero() {
+        positions.push(LockPosition {
+            amount: locked_funds,
+            locked_time,
+            locked_period,
+        });
+    }
+    LockedRecords {
+        positions,
+        rewards_ratio,
+        max_quota,
+    }
+}
+
+/// Move records under the legacy prefix to the pallet's prefix, partners donated
+/// `FeeToPrevious`.
+pub fn migrate_from_legacy_prefix<T: Config>() -> Weight {
+    let fee_to_previous = <Module<T>>::fee_to_previous();
+
+    let mut count: Weight = 0;
+    let records = StorageKeyIterator::<
+        T::Hash,
+        LegacyMetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        Twox64Concat,
+    >::new(LEGACY_PREFIX, b"Metadata")
+    .drain();
+    for (user_key, old) in records {
+        let key = user_key.using_encoded(Twox64Concat::hash);
+        put_storage_value(
+            <Metadata<T>>::module_prefix(),
+            <Metadata<T>>::storage_prefix(),
+            &key,
+            MetadataRecordWithoutKeys {
+                address: old.address,
+                superior: old.superior,
+                creator: old.creator,
+                did: old.did,
+                locked_records: old.locked_records,
+                unlocked_records: old.unlocked_records,
+                donate: if old.is_partner {
+                    Some(fee_to_previous)
+                } else {
+                    None
+                },
+                social_account: old.social_account,
+                subordinate_count: old.subordinate_count,
+                group_name: old.group_name,
+                external_address: old.external_address,
+            },
+        );
+        count += 1;
+    }
+
+    T::DbWeight::get().reads_writes(count + 1, count * 2)
+}
+
+/// Give every existing DID an empty key set and key history.
+pub fn migrate_to_key_set<T: Config>() -> Weight {
+    let count = translate_metadata::<
+        T,
+        MetadataRecordWithoutKeys<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        MetadataRecordWithExternalAddress<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        _,
+    >(|old| MetadataRecordWithExternalAddress {
+        address: old.address,
+        superior: old.superior,
+        creator: old.creator,
+        did: old.did,
+        locked_records: old.locked_records,
+        unlocked_records: old.unlocked_records,
+        donate: old.donate,
+        social_account: old.social_account,
+        subordinate_count: old.subordinate_count,
+        group_name: old.group_name,
+        external_address: old.external_address,
+        keys: Vec::new(),
+        key_history: Vec::new(),
+    });
+
+    T::DbWeight::get().reads_writes(count + 1, count + 1)
+}
+
+/// Key external addresses by chain id and index the verifiable ones by address.
+pub fn migrate_to_external_addresses<T: Config>() -> Weight {
+    let count = translate_metadata::<
+        T,
+        MetadataRecordWithExternalAddress<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        MetadataRecordWithSingleLock<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        _,
+    >(|old| MetadataRecordWithSingleLock {
+        address: old.address,
+        superior: old.superior,
+        creator: old.creator,
+        did: old.did,
+        locked_records: old.locked_records,
+        unlocked_records: old.unlocked_records,
+        donate: old.donate,
+        social_account: old.social_account,
+        subordinate_count: old.subordinate_count,
+        group_name: old.group_name,
+        external_addresses: external_address_map(old.external_address),
+        keys: old.keys,
+        key_history: old.key_history,
+    });
+
+    // legacy addresses were linked without proofs, the first DID to claim one keeps it
+    let mut indexed: Weight = 0;
+    let records = StorageIterator::<
+        MetadataRecordWithSingleLock<T::AccountId, T::Hash, T::Balance, T::Moment>,
+    >::new(
+        <Metadata<T>>::module_prefix(),
+        <Metadata<T>>::storage_prefix(),
+    );
+    for (_, metadata) in records {
+        let user_key = T::Hashing::hash(&metadata.did);
+        for (chain_id, addresses) in metadata.external_addresses {
+            let verifiable = check::ChainType::from_chain_id(&chain_id)
+                .map_or(false, |chain| chain.is_verifiable());
+            if !verifiable {
+                continue;
+            }
+            for address in addresses {
+                if !<ExternalAddressOwner<T>>::contains_key(&chain_id, &address) {
+                    <ExternalAddressOwner<T>>::insert(&chain_id, address, user_key);
+                    indexed += 1;
+                }
+            }
+        }
+    }
+
+    T::DbWeight::get().reads_writes(count * 2 + 1, count + indexed + 1)
+}
+
+/// Split every merged lock into its own position.
+pub fn migrate_to_lock_positions<T: Config>() -> Weight {
+    let count = translate_metadata::<
+        T,
+        MetadataRecordWithSingleLock<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        _,
+    >(|old| MetadataRecord {
+        address: old.address,
+        superior: old.superior,
+        creator: old.creator,
+        did: old.did,
+        locked_records: old.locked_records.map(lock_positions),
+        unlocked_records: old.unlocked_records,
+        donate: old.donate,
+        social_account: old.social_account,
+        subordinate_count: old.subordinate_count,
+        group_name: old.group_name,
+        external_addresses: old.external_addresses,
+        keys: old.keys,
+        key_history: old.key_history,
+    });
+
+    T::DbWeight::get().reads_writes(count + 1, count + 1)
+}
+
+/// Rebate locks the way they were before the schedule could be changed.
+pub fn migrate_to_reward_schedule<T: Config>() -> Weight {
+    <ReferralSchedule<T>>::put(RewardSchedule::legacy(<Module<T>>::fee_to_previous()));
+
+    T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Index every DID under its superior, in storage order rather than by creation.
+pub fn migrate_to_child_index<T: Config>() -> Weight {
+    let mut count: Weight = 0;
+    let mut indexed: Weight = 0;
+    for (user_key, metadata) in <Metadata<T>>::iter() {
+        count += 1;
+        if metadata.superior != user_key && <Metadata<T>>::contains_key(&metadata.superior) {
+            <Module<T>>::add_child(metadata.superior, user_key);
+            indexed += 1;
+        }
+    }
+
+    T::DbWeight::get().reads_writes(count * 2 + indexed * 2 + 1, indexed * 2 + 1)
+}
+
+/// Record where every subordinate sits in its superior's pages, so it is removed without
+/// a scan.
+pub fn migrate_to_child_positions<T: Config>() -> Weight {
+    let mut pages: Weight = 0;
+    let mut indexed: Weight = 0;
+    for (_, page, children) in <Subordinates<T>>::iter() {
+        pages += 1;
+        for (pos, child) in children.into_iter().enumerate() {
+            <ChildPositions<T>>::insert(&child, (page, pos as u32));
+            indexed += 1;
+        }
+    }
+
+    T::DbWeight::get().reads_writes(pages, indexed)
+}
+
+/// Register the types DIDs were created with before the registry, types set since are kept.
+pub fn migrate_to_did_types<T: Config>() -> Weight {
+    let mut written: Weight = 0;
+    for (did_type, info) in did_type::default_did_types::<T::AccountId, T::Balance>() {
+        if !<DidTypes<T>>::contains_key(&did_type) {
+            <DidTypes<T>>::insert(&did_type, info);
+            written += 1;
+        }
+    }
+
+    T::DbWeight::get().reads_writes(2, written)
+}
@@ -0,0 +1,154 @@
+use crate::check;
+use ripemd160::{Digest, Ripemd160};
+use sp_core::{ed25519, sr25519};
+use sp_io::{
+    crypto::{ed25519_verify, secp256k1_ecdsa_recover, sr25519_verify},
+    hashing::{keccak_256, sha2_256},
+};
+use sp_std::prelude::Vec;
+
+const ETH_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+const BTC_MESSAGE_PREFIX: &[u8] = b"\x18Bitcoin Signed Message:\n";
+const BYTES_PREFIX: &[u8] = b"<Bytes>";
+const BYTES_SUFFIX: &[u8] = b"</Bytes>";
+
+/// The challenge an external wallet signs to prove it controls an address.
+pub fn challenge(did_uri: &[u8], nonce: u64) -> Vec<u8> {
+    let mut message = b"Link to ".to_vec();
+    message.extend_from_slice(did_uri);
+    message.extend_from_slice(b" with nonce ");
+    message.append(&mut check::to_decimal(nonce));
+    message
+}
+
+/// The statement an attestor signs after verifying `platform:handle` belongs to the DID.
+pub fn social_statement(platform: &[u8], handle: &[u8], did_uri: &[u8], nonce: u64) -> Vec<u8> {
+    let mut message = b"Bind ".to_vec();
+    message.extend_from_slice(platform);
+    message.push(b':');
+    message.extend_from_slice(handle);
+    message.extend_from_slice(b" to ");
+    message.extend_from_slice(did_uri);
+    message.extend_from_slice(b" with nonce ");
+    message.append(&mut check::to_decimal(nonce));
+    message
+}
+
+/// EIP-191 `personal_sign` digest of a message.
+pub fn eth_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = ETH_MESSAGE_PREFIX.to_vec();
+    data.append(&mut check::to_decimal(message.len() as u64));
+    data.extend_from_slice(message);
+    keccak_256(&data)
+}
+
+/// Bitcoin signed message digest of a message.
+pub fn btc_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = BTC_MESSAGE_PREFIX.to_vec();
+    // compact size of the message length
+    let len = message.len();
+    if len < 0xfd {
+        data.push(len as u8);
+    } else {
+        data.push(0xfd);
+        data.extend_from_slice(&(len as u16).to_le_bytes());
+    }
+    data.extend_from_slice(message);
+    sha2_256(&sha2_256(&data))
+}
+
+/// Recover the ethereum address which signed the message, `signature` is `r || s || v`.
+pub fn recover_eth_address(message: &[u8], signature: &[u8]) -> Option<[u8; 20]> {
+    if signature.len() != 65 {
+        return None;
+    }
+
+    let mut sig = [0u8; 65];
+    sig.copy_from_slice(signature);
+    if sig[64] >= 27 {
+        sig[64] -= 27;
+    }
+
+    let public = secp256k1_ecdsa_recover(&sig, &eth_message_hash(message)).ok()?;
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&keccak_256(&public)[12..]);
+    Some(address)
+}
+
+/// Check the key behind a bitcoin public key hash signed the message,
+/// `signature` is `header || r || s`.
+pub fn verify_btc_signature(pubkey_hash: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if signature.len() != 65 {
+        return false;
+    }
+
+    // 27 - 30 uncompressed, 31 - 34 compressed P2PKH, 39 - 42 P2WPKH keys (BIP-137),
+    // P2SH-P2WPKH pays to a script hash and can not be checked against the key
+    let header = signature[0];
+    if header < 27 || header > 42 || (header >= 35 && header <= 38) {
+        return false;
+    }
+    let compressed = header >= 31;
+
+    let mut sig = [0u8; 65];
+    sig[..64].copy_from_slice(&signature[1..]);
+    sig[64] = (header - 27) & 3;
+
+    let public = match secp256k1_ecdsa_recover(&sig, &btc_message_hash(message)) {
+        Ok(public) => public,
+        Err(_) => return false,
+    };
+
+    let mut serialized = Vec::with_capacity(65);
+    if compressed {
+        serialized.push(0x02 + (public[63] & 1));
+        serialized.extend_from_slice(&public[..32]);
+    } else {
+        serialized.push(0x04);
+        serialized.extend_from_slice(&public);
+    }
+
+    Ripemd160::digest(&sha2_256(&serialized))[..] == pubkey_hash[..]
+}
+
+fn raw_key(public: &[u8], signature: &[u8]) -> Option<([u8; 32], [u8; 64])> {
+    if public.len() != 32 || signature.len() != 64 {
+        return None;
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(public);
+    let mut sig = [0u8; 64];
+    sig.copy_from_slice(signature);
+    Some((key, sig))
+}
+
+/// Check an sr25519 signature of the message, polkadot.js extensions sign it
+/// wrapped in `<Bytes>...</Bytes>` so both forms are accepted.
+pub fn verify_sr25519(public: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (key, sig) = match raw_key(public, signature) {
+        Some(raw) => raw,
+        None => return false,
+    };
+    let key = sr25519::Public::from_raw(key);
+    let sig = sr25519::Signature::from_raw(sig);
+
+    let mut wrapped = BYTES_PREFIX.to_vec();
+    wrapped.extend_from_slice(message);
+    wrapped.extend_from_slice(BYTES_SUFFIX);
+
+    sr25519_verify(&sig, message, &key) || sr25519_verify(&sig, &wrapped, &key)
+}
+
+/// Check an ed25519 signature of the message, as produced by Solana wallets' `signMessage`.
+pub fn verify_ed25519(public: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    match raw_key(public, signature) {
+        Some((key, sig)) => ed25519_verify(
+            &ed25519::Signature::from_raw(sig),
+            message,
+            &ed25519::Public::from_raw(key),
+        ),
+        None => false,
+    }
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+/// How the quota of subordinates follows locked funds, and the superior's share of ads
+/// proceeds follows how far the subordinates exceed that quota.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct RewardsCurve {
+    /// Subordinates one unit of locked funds makes room for.
+    pub quota_per_unit: u64,
+    /// Percent the superior takes while its subordinates fit in its quota.
+    pub base_ratio: u64,
+    /// Percent the superior takes as its quota shrinks to nothing.
+    pub max_ratio: u64,
+}
+
+impl RewardsCurve {
+    /// Ten subordinates per unit locked, 20% rising to 100% past the quota.
+    pub fn legacy() -> Self {
+        RewardsCurve {
+            quota_per_unit: 10,
+            base_ratio: 20,
+            max_ratio: 100,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.quota_per_unit > 0 && self.base_ratio <= self.max_ratio && self.max_ratio <= 100
+    }
+
+    pub fn max_quota(&self, locked_funds: u64) -> u64 {
+        locked_funds.saturating_mul(self.quota_per_unit)
+    }
+
+    /// `base_ratio` up to the quota, beyond it the share of subordinates the quota does not
+    /// cover moves the ratio towards `max_ratio`.
+    pub fn rewards_ratio(&self, max_quota: u64, subordinate_count: u64) -> u64 {
+        if subordinate_count <= max_quota {
+            return self.base_ratio;
+        }
+
+        let uncovered = (subordinate_count - max_quota) as u128;
+        let spread = self.max_ratio.saturating_sub(self.base_ratio) as u128;
+        self.base_ratio + (spread * uncovered / subordinate_count as u128) as u64
+    }
+}
+
+impl Default for RewardsCurve {
+    fn default() -> Self {
+        Self::legacy()
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Derive quota and rewards ratio from the funds of all lock positions.
+    pub(crate) fn refresh_quota(
+        locked_records: &mut LockedRecords<T::Balance, T::Moment>,
+        subordinate_count: u64,
+    ) {
+        let curve = Self::rewards_curve();
+        let max_quota = curve.max_quota(Self::balance_to_u64(locked_records.locked_funds()));
+
+        locked_records.rewards_ratio = curve.rewards_ratio(max_quota, subordinate_count);
+        locked_records.max_quota = max_quota;
+    }
+
+    /// The percent of ads proceeds a DID takes from its subordinates under the current curve.
+    pub(crate) fn rewards_ratio_of(
+        metadata: &MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+    ) -> u64 {
+        metadata.locked_records.as_ref().map_or(0, |records| {
+            let curve = Self::rewards_curve();
+            let max_quota = curve.max_quota(Self::balance_to_u64(records.locked_funds()));
+            curve.rewards_ratio(max_quota, metadata.subordinate_count)
+        })
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+/// Guardian DIDs nominated to move a DID to a new controlling account.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct RecoveryConfig<Hash> {
+    pub guardians: Vec<Hash>,
+    /// Number of guardians who must vouch for the same account.
+    pub threshold: u32,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct ActiveRecovery<AccountId, Hash, Moment> {
+    /// Accounts vouched for with their guardians, a guardian backs one account at a time.
+    candidates: Vec<(AccountId, Vec<Hash>)>,
+    /// The account `threshold` guardians backed and when it may claim the DID, replaced once
+    /// `threshold` guardians back another account.
+    ready: Option<(AccountId, Moment)>,
+}
+
+impl<T: Config> Module<T> {
+    pub(crate) fn ensure_valid_recovery(
+        user_key: T::Hash,
+        guardians: &[T::Hash],
+        threshold: u32,
+    ) -> DispatchResult {
+        ensure!(
+            guardians.len() as u32 <= T::MaxGuardians::get(),
+            Error::<T>::TooManyGuardians
+        );
+        ensure!(
+            threshold > 0 && threshold as usize <= guardians.len(),
+            Error::<T>::InvalidRecovery
+        );
+
+        for (i, guardian) in guardians.iter().enumerate() {
+            ensure!(*guardian != user_key, Error::<T>::InvalidRecovery);
+            ensure!(
+                !guardians[..i].contains(guardian),
+                Error::<T>::InvalidRecovery
+            );
+            ensure!(
+                <Metadata<T>>::contains_key(guardian),
+                Error::<T>::GuardianNotExists
+            );
+        }
+        Ok(())
+    }
+
+    /// Record a guardian's vouch, moving any earlier vouch of the guardian to `new_account`.
+    /// Returns when the recovery can be claimed if this vouch made `new_account` reach the
+    /// threshold.
+    pub(crate) fn record_vouch(
+        lost: T::Hash,
+        guardian: T::Hash,
+        new_account: T::AccountId,
+    ) -> Result<Option<T::Moment>, DispatchError> {
+        let config = Self::recovery_config(&lost).ok_or(Error::<T>::RecoveryNotExists)?;
+        ensure!(
+            config.guardians.contains(&guardian),
+            Error::<T>::NotGuardian
+        );
+
+        let mut recovery = Self::active_recovery(&lost).unwrap_or_default();
+        let already = recovery
+            .candidates
+            .iter()
+            .any(|(account, vouchers)| *account == new_account && vouchers.contains(&guardian));
+        ensure!(!already, Error::<T>::AlreadyVouched);
+
+        for (_, vouchers) in recovery.candidates.iter_mut() {
+            vouchers.retain(|voucher| *voucher != guardian);
+        }
+        recovery
+            .candidates
+            .retain(|(_, vouchers)| !vouchers.is_empty());
+
+        let backing = match recovery
+            .candidates
+            .iter_mut()
+            .find(|(account, _)| *account == new_account)
+        {
+            Some((_, vouchers)) => {
+                vouchers.push(guardian);
+                vouchers.len() as u32
+            }
+            None => {
+                recovery
+                    .candidates
+                    .push((new_account.clone(), sp_std::vec![guardian]));
+                1
+            }
+        };
+
+        // the delay restarts whenever another account takes over
+        let mut reached = None;
+        let is_ready = recovery
+            .ready
+            .as_ref()
+            .map_or(false, |(account, _)| *account == new_account);
+        if !is_ready && backing >= config.threshold {
+            let now = <pallet_timestamp::Module<T>>::get();
+            let ready_at = now
+                .checked_add(&T::RecoveryDelay::get())
+                .ok_or(Error::<T>::Overflow)?;
+            recovery.ready = Some((new_account, ready_at));
+            reached = Some(ready_at);
+        }
+        <ActiveRecoveries<T>>::insert(&lost, recovery);
+
+        Ok(reached)
+    }
+
+    /// Hand a DID whose recovery delay passed to the vouched account, returns the DID.
+    pub(crate) fn finish_recovery(lost: T::Hash, claimer: &T::AccountId) -> Result<Did, DispatchError> {
+        let recovery = Self::active_recovery(&lost).ok_or(Error::<T>::RecoveryNotExists)?;
+        let (account, ready_at) = match recovery.ready {
+            Some(ready) => ready,
+            None => {
+                let vouched = recovery.candidates.iter().any(|(account, _)| account == claimer);
+                ensure!(vouched, Error::<T>::NotEligible);
+                return Err(Error::<T>::RecoveryNotReady.into());
+            }
+        };
+        ensure!(account == *claimer, Error::<T>::NotEligible);
+
+        let now = <pallet_timestamp::Module<T>>::get();
+        ensure!(now >= ready_at, Error::<T>::RecoveryNotReady);
+
+        let metadata = Self::metadata(&lost);
+        let old_account = metadata.address.clone();
+
+        // only the funds the did pallet reserved follow the DID
+        Self::move_reserved(lost, &old_account, claimer)?;
+        Self::rebind_account(lost, &old_account, claimer)?;
+        <ActiveRecoveries<T>>::remove(&lost);
+
+        Ok(metadata.did)
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+/// Memo of the transfers paying referral rewards.
+pub const REFERRAL_MEMO: &str = "抵押分成";
+
+/// How the rewards of one superior level are paid.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct RewardLevel<Balance> {
+    /// Share of the rebate paid to the superior at this level.
+    pub share: Permill,
+    /// Most a single payout to this level may be.
+    pub max_payout: Option<Balance>,
+}
+
+/// How much of a lock is paid to the superiors of a DID and how it is split between them.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct RewardSchedule<Balance> {
+    /// Share of every locked amount rebated to the superiors.
+    pub rebate: Permill,
+    /// Most a DID rebates over all of its locks.
+    pub cap: Balance,
+    /// The nearest superior first, shares of levels past the root of the tree go to the last
+    /// superior found.
+    pub levels: Vec<RewardLevel<Balance>>,
+}
+
+impl<Balance> RewardSchedule<Balance> {
+    /// Half of a lock up to `cap`, 80% to the superior and 20% to the one above it.
+    pub fn legacy(cap: Balance) -> Self {
+        RewardSchedule {
+            rebate: Permill::from_percent(50),
+            cap,
+            levels: sp_std::vec![
+                RewardLevel {
+                    share: Permill::from_percent(80),
+                    max_payout: None,
+                },
+                RewardLevel {
+                    share: Permill::from_percent(20),
+                    max_payout: None,
+                },
+            ],
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    pub(crate) fn ensure_valid_schedule(schedule: &RewardSchedule<T::Balance>) -> DispatchResult {
+        ensure!(
+            !schedule.levels.is_empty()
+                && schedule.levels.len() as u32 <= T::MaxRewardLevels::get(),
+            Error::<T>::InvalidSchedule
+        );
+
+        let total = schedule
+            .levels
+            .iter()
+            .fold(0u32, |total, level| total.saturating_add(level.share.deconstruct()));
+        ensure!(
+            total <= Permill::one().deconstruct(),
+            Error::<T>::InvalidSchedule
+        );
+        Ok(())
+    }
+
+    /// The rebate owed on locking `value`, given what the DID has rebated so far.
+    pub(crate) fn referral_rebate(value: T::Balance, donated: T::Balance) -> T::Balance {
+        let schedule = Self::reward_schedule();
+        (schedule.rebate * value).min(schedule.cap.saturating_sub(donated))
+    }
+
+    /// Pay `rebate` to the superiors of `user_key` by the reward schedule, returns the amount
+    /// paid which may be less than `rebate` if levels are capped.
+    pub(crate) fn pay_referral_rewards(
+        user_key: T::Hash,
+        superior: T::Hash,
+        rebate: T::Balance,
+    ) -> Result<T::Balance, DispatchError> {
+        let levels = Self::reward_schedule().levels;
+
+        let mut superiors = Vec::new();
+        let mut next = superior;
+        while superiors.len() < levels.len() && <Metadata<T>>::contains_key(&next) {
+            superiors.push(next);
+            next = Self::metadata(&next).superior;
+        }
+
+        let mut shares: Vec<Permill> = levels.iter().map(|l| l.share).collect();
+        if let Some(last) = superiors.len().checked_sub(1) {
+            let missing = shares[superiors.len()..]
+                .iter()
+                .fold(Permill::zero(), |sum, share| sum.saturating_add(*share));
+            shares[last] = shares[last].saturating_add(missing);
+        }
+
+        let from_did = Self::metadata(&user_key).did;
+        let mut paid: T::Balance = Zero::zero();
+        for (level, to_user) in superiors.into_iter().enumerate() {
+            let mut payout = shares[level] * rebate;
+            if let Some(max_payout) = levels[level].max_payout {
+                payout = payout.min(max_payout);
+            }
+            if payout.is_zero() {
+                continue;
+            }
+
+            Self::transfer_by_did(
+                user_key,
+                to_user,
+                payout,
+                TransferKind::ReferralRebate,
+                REFERRAL_MEMO.as_bytes().to_vec(),
+            )?;
+            paid += payout;
+
+            let to_did = Self::metadata(&to_user).did;
+            Self::deposit_event(RawEvent::ReferralRewarded(
+                from_did.clone(),
+                to_did,
+                level as u32 + 1,
+                payout,
+            ));
+        }
+
+        Ok(paid)
+    }
+}
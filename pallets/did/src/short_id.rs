@@ -0,0 +1,60 @@
+use super::*;
+use crate::harsh::{Harsh, HarshBuilder};
+
+/// What a short ID resolves to.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct ShortIdRecord<AccountId, Hash> {
+    /// Value of `AllDidCount` when the DID was created.
+    pub sequence: u64,
+    pub user_key: Hash,
+    /// The `did:ad3:...` string of the DID.
+    pub did: Vec<u8>,
+    pub account: AccountId,
+}
+
+impl<T: Config> Module<T> {
+    fn harsher() -> Option<Harsh> {
+        HarshBuilder::new()
+            .salt(Self::harsh_salt())
+            .length(Self::harsh_length() as usize)
+            .init()
+            .ok()
+    }
+
+    /// The short ID of the DID created as number `sequence`.
+    pub(crate) fn short_id_of(sequence: u64) -> Option<Vec<u8>> {
+        Self::harsher()?.encode(&[sequence])
+    }
+
+    /// Decode a short ID back to its sequence number, only the canonical encoding of a single
+    /// number is accepted.
+    pub fn decode_short_id(short_id: &[u8]) -> Option<u64> {
+        let harsher = Self::harsher()?;
+        if short_id.len() > harsher.encode(&[u64::MAX])?.len() {
+            return None;
+        }
+        match harsher.decode(short_id)?.as_slice() {
+            [sequence] if harsher.encode(&[*sequence])? == short_id => Some(*sequence),
+            _ => None,
+        }
+    }
+
+    /// Resolve a short ID of a live DID to its user key, DID and account.
+    pub fn resolve_short_id(short_id: Vec<u8>) -> Option<ShortIdRecord<T::AccountId, T::Hash>> {
+        let sequence = Self::decode_short_id(&short_id)?;
+
+        let idx_hash = T::Hashing::hash(&short_id);
+        if !<UserKeys<T>>::contains_key(&idx_hash) {
+            return None;
+        }
+        let user_key = Self::key_by_index(&idx_hash);
+        let account = Self::identity_of(&user_key)?;
+
+        Some(ShortIdRecord {
+            sequence,
+            user_key,
+            did: Self::did_uri(&Self::metadata(&user_key).did),
+            account,
+        })
+    }
+}
@@ -0,0 +1,193 @@
+use super::*;
+
+/// The longest platform name or handle an attestor may vouch for.
+pub const MAX_HANDLE_LENGTH: usize = 64;
+
+/// An account registered to verify social accounts of one platform, e.g. `twitter`.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Attestor {
+    pub platform: Vec<u8>,
+    /// sr25519 key the attestor signs binding statements with.
+    pub public: Vec<u8>,
+}
+
+/// An attestor's signature over `proof::social_statement` for a DID.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct SocialProof<AccountId> {
+    pub attestor: AccountId,
+    pub platform: Vec<u8>,
+    pub handle: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Attestation<AccountId, Moment> {
+    attestor: AccountId,
+    platform: Vec<u8>,
+    handle: Vec<u8>,
+    time: Moment,
+}
+
+impl<AccountId, Moment> Attestation<AccountId, Moment> {
+    /// Stands in for the attestation of a social account carried over at genesis.
+    pub(crate) fn imported(attestor: AccountId, time: Moment) -> Self {
+        Attestation {
+            attestor,
+            platform: Vec::new(),
+            handle: Vec::new(),
+            time,
+        }
+    }
+}
+
+/// A claim on a social account which is bound to another DID, pending a root decision.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct SocialDispute<AccountId, Hash, Balance, Moment> {
+    claimant: Hash,
+    who: AccountId,
+    deposit: Balance,
+    attestation: Attestation<AccountId, Moment>,
+}
+
+impl<T: Config> Module<T> {
+    /// Key of `platform:handle` in `SocialAccount`, scoped by the DID type like `social_superior`.
+    pub fn social_hash(platform: &[u8], handle: &[u8], did_type: &[u8]) -> T::Hash {
+        let mut value = platform.to_vec();
+        value.push(b':');
+        value.extend_from_slice(handle);
+        value.extend_from_slice(did_type);
+        T::Hashing::hash(&value)
+    }
+
+    /// Check an attestor vouched for the social account of the DID, returns the account's hash
+    /// and the attestation to record.
+    pub(crate) fn check_social_proof(
+        user_key: T::Hash,
+        did: &[u8],
+        proof: &SocialProof<T::AccountId>,
+    ) -> Result<(T::Hash, Attestation<T::AccountId, T::Moment>), DispatchError> {
+        let attestor = Self::attestors(&proof.attestor).ok_or(Error::<T>::AttestorNotExists)?;
+        ensure!(
+            attestor.platform == proof.platform,
+            Error::<T>::InvalidAttestation
+        );
+        ensure!(
+            !proof.handle.is_empty() && proof.handle.len() <= MAX_HANDLE_LENGTH,
+            Error::<T>::InvalidAttestation
+        );
+
+        let statement = proof::social_statement(
+            &proof.platform,
+            &proof.handle,
+            &Self::did_uri(did),
+            Self::proof_nonce(&user_key),
+        );
+        ensure!(
+            proof::verify_sr25519(&attestor.public, &statement, &proof.signature),
+            Error::<T>::InvalidAttestation
+        );
+
+        let did_type = Self::did_type_of(did);
+        let attestation = Attestation {
+            attestor: proof.attestor.clone(),
+            platform: proof.platform.clone(),
+            handle: proof.handle.clone(),
+            time: <pallet_timestamp::Module<T>>::get(),
+        };
+        Ok((
+            Self::social_hash(&proof.platform, &proof.handle, did_type),
+            attestation,
+        ))
+    }
+
+    /// Social accounts bound before attestations existed may be taken over by an attested claim.
+    pub(crate) fn ensure_social_available(social_hash: T::Hash, user_key: T::Hash) -> DispatchResult {
+        let bound = <SocialAccount<T>>::contains_key(&social_hash)
+            && Self::social_account(&social_hash) != user_key
+            && <SocialAttestation<T>>::contains_key(&social_hash);
+        ensure!(!bound, Error::<T>::SocialAccountBound);
+        Ok(())
+    }
+
+    /// Bind a social account to the DID, the caller stores the DID's metadata. A DID the account
+    /// is taken from gets a `SocialUnbound` event here.
+    pub(crate) fn bind_social(
+        user_key: T::Hash,
+        metadata: &mut MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+        social_hash: T::Hash,
+        attestation: Attestation<T::AccountId, T::Moment>,
+    ) {
+        if <SocialAccount<T>>::contains_key(&social_hash) {
+            let holder = Self::social_account(&social_hash);
+            if holder != user_key && <Metadata<T>>::contains_key(&holder) {
+                let displaced = <Metadata<T>>::mutate(&holder, |m| {
+                    if m.social_account == Some(social_hash) {
+                        m.social_account = None;
+                        Some(m.did.clone())
+                    } else {
+                        None
+                    }
+                });
+                if let Some(did) = displaced {
+                    Self::deposit_event(RawEvent::SocialUnbound(did, social_hash));
+                }
+            }
+        }
+
+        <SocialAccount<T>>::insert(&social_hash, &user_key);
+        <SocialAttestation<T>>::insert(&social_hash, attestation);
+        <ProofNonce<T>>::mutate(&user_key, |nonce| *nonce += 1);
+        metadata.social_account = Some(social_hash);
+    }
+
+    /// Release the DID's social account, refunding any dispute on it.
+    pub(crate) fn unbind_social(
+        user_key: T::Hash,
+        metadata: &mut MetadataRecord<T::AccountId, T::Hash, T::Balance, T::Moment>,
+    ) -> Option<T::Hash> {
+        let social_hash = metadata.social_account.take()?;
+
+        if Self::social_account(&social_hash) == user_key {
+            <SocialAccount<T>>::remove(&social_hash);
+            <SocialAttestation<T>>::remove(&social_hash);
+        }
+        if let Some(dispute) = <SocialDisputes<T>>::take(&social_hash) {
+            <pallet_balances::Module<T>>::unreserve(&dispute.who, dispute.deposit);
+        }
+
+        Some(social_hash)
+    }
+
+    /// Hand a disputed social account to the claimant or keep it with its holder.
+    pub(crate) fn settle_dispute(social_hash: T::Hash, in_favor_of_claimant: bool) -> DispatchResult {
+        let SocialDispute {
+            claimant,
+            who,
+            deposit,
+            attestation,
+        } = <SocialDisputes<T>>::take(&social_hash).ok_or(Error::<T>::DisputeNotExists)?;
+
+        if !in_favor_of_claimant {
+            let _ = <pallet_balances::Module<T>>::slash_reserved(&who, deposit);
+            return Ok(());
+        }
+
+        <pallet_balances::Module<T>>::unreserve(&who, deposit);
+
+        // the claimant may have dropped its DID in the meantime
+        if <Metadata<T>>::contains_key(&claimant) {
+            let mut metadata = Self::metadata(&claimant);
+            let did = metadata.did.clone();
+            if let Some(old_hash) = Self::unbind_social(claimant, &mut metadata) {
+                Self::deposit_event(RawEvent::SocialUnbound(did.clone(), old_hash));
+            }
+            let attestor = attestation.attestor.clone();
+            Self::bind_social(claimant, &mut metadata, social_hash, attestation);
+            <Metadata<T>>::insert(&claimant, metadata);
+
+            Self::deposit_event(RawEvent::SocialBound(did, social_hash, attestor));
+        }
+
+        Ok(())
+    }
+}
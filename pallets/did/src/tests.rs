@@ -2,52 +2,61 @@
 
 use super::*;
 
-use frame_support::{assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
+use frame_support::{
+    assert_noop, assert_ok, construct_runtime, parameter_types,
+    storage::migration::{get_storage_value, put_storage_value},
+    traits::{LockableCurrency, OnRuntimeUpgrade, WithdrawReasons},
+    weights::{DispatchInfo, PostDispatchInfo},
+    StorageHasher, StoragePrefixedMap, Twox64Concat,
+};
 use sp_core::H256;
 // The testing primitives are very useful for avoiding having to work with signatures
 // or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
-use frame_system::{self as system, EventRecord, Phase};
+use frame_system::{EventRecord, Phase};
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
+    traits::{BlakeTwo256, IdentityLookup, SignedExtension},
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
 };
 
 pub type AccountId = u64;
 pub type BlockNumber = u64;
 pub type Balance = u64;
 
-impl_outer_origin! {
-  pub enum Origin for Test {}
-}
+type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, u32, ()>;
 
 mod did {
     pub use super::super::*;
 }
 
-impl_outer_event! {
-  pub enum TestEvent for Test {
-    did<T>,
-    pallet_balances<T>,
-    frame_system<T>,
-  }
-}
 // For testing the module, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of modules we want to use.
-#[derive(Clone, Eq, PartialEq)]
-pub struct Test;
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage},
+        DidModule: did::{Module, Call, Storage, Event<T>},
+    }
+);
 
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
-    pub const MaximumBlockWeight: Weight = 1024;
-    pub const MaximumBlockLength: u32 = 2 * 1024;
-    pub const AvailableBlockRatio: Perbill = Perbill::one();
+    pub const SS58Prefix: u8 = 42;
 }
 
-impl frame_system::Trait for Test {
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
     type Origin = Origin;
-    type Call = ();
+    type Call = Call;
     type Index = u64;
     type BlockNumber = u64;
     type Hash = H256;
@@ -55,41 +64,41 @@ impl frame_system::Trait for Test {
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = TestEvent;
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
-    type MaximumBlockWeight = MaximumBlockWeight;
     type DbWeight = ();
-    type BlockExecutionWeight = ();
-    type ExtrinsicBaseWeight = ();
-    type MaximumBlockLength = MaximumBlockLength;
-    type AvailableBlockRatio = AvailableBlockRatio;
     type Version = ();
-    type ModuleToIndex = ();
+    type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
 }
 
 parameter_types! {
   pub const ExistentialDeposit: u64 = 1;
 }
 
-impl pallet_balances::Trait for Test {
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
     type Balance = u64;
-    type Event = TestEvent;
+    type Event = Event;
     type DustRemoval = ();
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = frame_system::Module<Test>;
+    type WeightInfo = ();
 }
 
 parameter_types! {
   pub const MinimumPeriod: u64 = 1;
 }
 
-impl pallet_timestamp::Trait for Test {
+impl pallet_timestamp::Config for Test {
     type Moment = u64;
     type OnTimestampSet = ();
     type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
 }
 
 parameter_types! {
@@ -97,24 +106,79 @@ parameter_types! {
   pub const MinLength: usize = 3;
   pub const MaxLength: usize = 16;
   pub const One: u64 = 1;
+  pub const MaxKeys: u32 = 3;
+  pub const MaxKeyHistory: u32 = 4;
+  pub const ServiceDeposit: u64 = 10;
+  pub const MaxServices: u32 = 2;
+  pub const MaxServiceTypeLength: u32 = 16;
+  pub const MaxServiceUrlLength: u32 = 32;
+  pub const MaxChains: u32 = 3;
+  pub const MaxAddressesPerChain: u32 = 2;
+  pub const DisputeDeposit: u64 = 100;
+  pub const MaxGuardians: u32 = 3;
+  pub const RecoveryDelay: u64 = 10;
+  pub const MaxLockPositions: u32 = 3;
+  pub const UnbondingPeriod: u64 = 10;
+  pub const MaxUnbondingChunks: u32 = 2;
+  pub const MaxUnlockHistory: u32 = 3;
+  pub const MaxRewardLevels: u32 = 3;
+  pub const MaxBatchTransfers: u32 = 3;
+  pub const FeelessEra: u64 = 10;
+  pub const FeelessLockUnit: u64 = 500;
+  pub const GroupDeposit: u64 = 10;
+  pub const MaxGroupMembers: u32 = 3;
+  pub const MaxGroupsPerDid: u32 = 2;
 }
 
-impl Trait for Test {
-    type Event = TestEvent;
+impl Config for Test {
+    type Event = Event;
+    type MaxKeys = MaxKeys;
+    type MaxKeyHistory = MaxKeyHistory;
+    type ServiceDeposit = ServiceDeposit;
+    type MaxServices = MaxServices;
+    type MaxServiceTypeLength = MaxServiceTypeLength;
+    type MaxServiceUrlLength = MaxServiceUrlLength;
+    type MaxChains = MaxChains;
+    type MaxAddressesPerChain = MaxAddressesPerChain;
+    type DisputeDeposit = DisputeDeposit;
+    type MaxGuardians = MaxGuardians;
+    type RecoveryDelay = RecoveryDelay;
+    type MaxLockPositions = MaxLockPositions;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxUnbondingChunks = MaxUnbondingChunks;
+    type MaxUnlockHistory = MaxUnlockHistory;
+    type RewardOrigin = frame_system::EnsureRoot<u64>;
+    type DidTypeOrigin = frame_system::EnsureRoot<u64>;
+    type MaxRewardLevels = MaxRewardLevels;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type FeelessCalls = ();
+    type FeelessEra = FeelessEra;
+    type FeelessLockUnit = FeelessLockUnit;
+    type GroupDeposit = GroupDeposit;
+    type MaxGroupMembers = MaxGroupMembers;
+    type MaxGroupsPerDid = MaxGroupsPerDid;
+    type WeightInfo = ();
 }
 
 const EOS_ADDRESS: &[u8; 12] = b"praqianchang";
-const BTC_ADDRESS: &[u8; 34] = b"1N75dvASxn1CCjaeguyqvwXLXJun9e54mM";
-const ETH_ADDRESS: &[u8; 40] = b"cb222a32df146ef7e3ac63725dad0fd978d33ce2";
-
-type DidModule = Module<Test>;
-type System = frame_system::Module<Test>;
-type Balances = pallet_balances::Module<Test>;
-type Timestamp = pallet_timestamp::Module<Test>;
+// addresses of the secp256k1 secret key `SIGNER_SECRET`
+const BTC_ADDRESS: &[u8; 34] = b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+const ETH_ADDRESS: &[u8; 40] = b"7e5f4552091a69125d5dfcb7b8c2659029395bdf";
+const TRON_ADDRESS: &[u8; 34] = b"TJRabPrwbZy45sbavfcjinPJC18kjpRTv8";
+const COSMOS_CHAIN_ID: &[u8; 18] = b"cosmos:cosmoshub-4";
+const COSMOS_ADDRESS: &[u8; 45] = b"cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0";
+const ATTESTOR: u64 = 9;
+const SIGNER_SECRET: [u8; 32] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+];
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
-fn new_test_ext() -> sp_io::TestExternalities {
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    new_test_ext_with_dids(vec![])
+}
+
+fn new_test_ext_with_dids(dids: Vec<GenesisDid<u64, H256, u64>>) -> sp_io::TestExternalities {
     let mut t = frame_system::GenesisConfig::default()
         .build_storage::<Test>()
         .unwrap();
@@ -125,11 +189,14 @@ fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
 
-    GenesisConfig::<Test> {
+    did::GenesisConfig::<Test> {
         genesis_account: 1u64,
         min_deposit: 10,
         base_quota: 250,
         fee_to_previous: 25,
+        harsh_salt: b"parami did".to_vec(),
+        harsh_length: 6,
+        dids,
     }
     .assimilate_storage(&mut t)
     .unwrap();
@@ -137,7 +204,89 @@ fn new_test_ext() -> sp_io::TestExternalities {
     t.into()
 }
 
+fn sign_digest(digest: &[u8; 32]) -> (Vec<u8>, u8) {
+    let secret = secp256k1::SecretKey::parse(&SIGNER_SECRET).unwrap();
+    let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(digest), &secret);
+    (signature.serialize().to_vec(), recovery_id.serialize())
+}
+
+fn sign_eth(account: u64) -> Vec<u8> {
+    let (user_key, _) = DidModule::identity(&account).unwrap();
+    let challenge = DidModule::proof_challenge(user_key);
+    let (mut signature, recovery_id) = sign_digest(&proof::eth_message_hash(&challenge));
+    signature.push(recovery_id + 27);
+    signature
+}
+
+fn sign_btc(account: u64) -> Vec<u8> {
+    let (user_key, _) = DidModule::identity(&account).unwrap();
+    let challenge = DidModule::proof_challenge(user_key);
+    let (signature, recovery_id) = sign_digest(&proof::btc_message_hash(&challenge));
+    // compressed public key
+    [&[31 + recovery_id][..], &signature[..]].concat()
+}
+
+fn sign_sr25519(account: u64) -> (Vec<u8>, Vec<u8>) {
+    use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+    let (user_key, _) = DidModule::identity(&account).unwrap();
+    let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+    let signature = pair.sign(&DidModule::proof_challenge(user_key));
+    (pair.public().to_ss58check().into_bytes(), signature.0.to_vec())
+}
+
+fn sign_ed25519(account: u64) -> (Vec<u8>, Vec<u8>) {
+    use sp_core::{ed25519, Pair};
+
+    let (user_key, _) = DidModule::identity(&account).unwrap();
+    let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+    let signature = pair.sign(&DidModule::proof_challenge(user_key));
+    (check::to_base58(&pair.public().0), signature.0.to_vec())
+}
+
+fn attestor_pair() -> sp_core::sr25519::Pair {
+    use sp_core::Pair;
+    sp_core::sr25519::Pair::from_seed(&[2u8; 32])
+}
+
+fn register_attestor() {
+    assert_ok!(DidModule::register_attestor(
+        Origin::root(),
+        ATTESTOR,
+        b"twitter".to_vec(),
+        attestor_pair().public().0.to_vec()
+    ));
+}
+
+fn attest(did: &[u8], handle: &[u8]) -> SocialProof<u64> {
+    use sp_core::Pair;
+
+    let statement = proof::social_statement(
+        b"twitter",
+        handle,
+        &DidModule::did_uri(did),
+        DidModule::proof_nonce(BlakeTwo256::hash(did)),
+    );
+    SocialProof {
+        attestor: ATTESTOR,
+        platform: b"twitter".to_vec(),
+        handle: handle.to_vec(),
+        signature: attestor_pair().sign(&statement).0.to_vec(),
+    }
+}
+
+fn attest_pubkey(pubkey: &[u8], handle: &[u8]) -> SocialProof<u64> {
+    attest(&DidModule::generate_did(pubkey, b"1"), handle)
+}
+
+fn attest_account(account: u64, handle: &[u8]) -> SocialProof<u64> {
+    let (_, did) = DidModule::identity(&account).unwrap();
+    attest(&did, handle)
+}
+
 fn prepare_dids_for_test() {
+    register_attestor();
+
     // genesis account
     assert_ok!(DidModule::create(
         Origin::signed(1),
@@ -145,7 +294,7 @@ fn prepare_dids_for_test() {
         1u64,
         "1".as_bytes().to_vec(),
         H256::zero(),
-        Some("first".as_bytes().to_vec()),
+        Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"first")),
         None
     ));
 
@@ -156,8 +305,8 @@ fn prepare_dids_for_test() {
         2u64,
         "1".as_bytes().to_vec(),
         H256::zero(),
-        Some("second".as_bytes().to_vec()),
-        Some("first".as_bytes().to_vec())
+        Some(attest_pubkey(b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d", b"second")),
+        Some(b"twitter:first".to_vec())
     ));
 
     // lock funds
@@ -170,8 +319,8 @@ fn prepare_dids_for_test() {
         3u64,
         "1".as_bytes().to_vec(),
         H256::zero(),
-        Some("third".as_bytes().to_vec()),
-        Some("second".as_bytes().to_vec())
+        Some(attest_pubkey(b"0x5e9c79234b5e55348fc60f38b28c2cc60d8bb4bd2862eae2179a05ec39e62658", b"third")),
+        Some(b"twitter:second".to_vec())
     ));
 }
 
@@ -179,6 +328,7 @@ fn prepare_dids_for_test() {
 fn should_pass_create() {
     new_test_ext().execute_with(|| {
         System::set_block_number(0);
+        register_attestor();
 
         // genesis account
         assert_ok!(DidModule::create(
@@ -187,7 +337,7 @@ fn should_pass_create() {
             1u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("first".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"first")),
             None
         ));
     });
@@ -197,6 +347,7 @@ fn should_pass_create() {
 fn same_pubkey_should_not_pass_create() {
     new_test_ext().execute_with(|| {
         System::set_block_number(0);
+        register_attestor();
 
         assert_ok!(DidModule::create(
             Origin::signed(1),
@@ -204,7 +355,7 @@ fn same_pubkey_should_not_pass_create() {
             1u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("first".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"first")),
             None
         ));
 
@@ -215,8 +366,8 @@ fn same_pubkey_should_not_pass_create() {
                 2u64,
                 "1".as_bytes().to_vec(),
                 H256::zero(),
-                Some("second".as_bytes().to_vec()),
-                Some("first".as_bytes().to_vec())
+                Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"second")),
+                Some(b"twitter:first".to_vec())
             ),
             Error::<Test>::DidExists
         );
@@ -227,6 +378,7 @@ fn same_pubkey_should_not_pass_create() {
 fn same_social_account_should_not_pass_create() {
     new_test_ext().execute_with(|| {
         System::set_block_number(0);
+        register_attestor();
 
         assert_ok!(DidModule::create(
             Origin::signed(1),
@@ -234,7 +386,7 @@ fn same_social_account_should_not_pass_create() {
             1u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("first".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"first")),
             None
         ));
 
@@ -245,7 +397,7 @@ fn same_social_account_should_not_pass_create() {
                 2u64,
                 "1".as_bytes().to_vec(),
                 H256::zero(),
-                Some("first".as_bytes().to_vec()),
+                Some(attest_pubkey(b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d", b"first")),
                 None
             ),
             Error::<Test>::SocialAccountBound
@@ -257,6 +409,7 @@ fn same_social_account_should_not_pass_create() {
 fn superior_not_exists_should_not_pass_create() {
     new_test_ext().execute_with(|| {
         System::set_block_number(0);
+        register_attestor();
 
         assert_ok!(DidModule::create(
             Origin::signed(1),
@@ -264,7 +417,7 @@ fn superior_not_exists_should_not_pass_create() {
             1u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("first".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", b"first")),
             None
         ));
 
@@ -275,8 +428,22 @@ fn superior_not_exists_should_not_pass_create() {
                 2u64,
                 "1".as_bytes().to_vec(),
                 H256::zero(),
-                Some("second".as_bytes().to_vec()),
-                Some("firsts".as_bytes().to_vec())
+                Some(attest_pubkey(b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d", b"second")),
+                Some(b"twitter:firsts".to_vec())
+            ),
+            Error::<Test>::SuperiorNotExists
+        );
+
+        // only the genesis account creates dids without a superior
+        assert_noop!(
+            DidModule::create(
+                Origin::signed(2),
+                b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d".to_vec(),
+                2u64,
+                "1".as_bytes().to_vec(),
+                BlakeTwo256::hash(b"unknown"),
+                None,
+                None
             ),
             Error::<Test>::SuperiorNotExists
         );
@@ -293,6 +460,20 @@ fn should_pass_update() {
         assert_ok!(DidModule::update(Origin::signed(3), 4u64));
         assert_eq!(Balances::free_balance(&3), 0);
         assert_eq!(Balances::free_balance(&4), 20000);
+
+        // the locked funds follow the DID and are released to the new account
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let locked = DidModule::metadata(&second).locked_records.unwrap().locked_funds();
+        let reserved = Balances::reserved_balance(&2);
+        assert_ok!(DidModule::update(Origin::signed(2), 5u64));
+        assert_eq!(Balances::reserved_balance(&2), reserved - locked);
+        assert_eq!(Balances::reserved_balance(&5), locked);
+
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::unlock(Origin::signed(5), locked));
+        Timestamp::set_timestamp(15);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(5)));
+        assert_eq!(Balances::reserved_balance(&5), 0);
     });
 }
 
@@ -339,8 +520,8 @@ fn should_pass_lock() {
             4u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("four".as_bytes().to_vec()),
-            Some("third".as_bytes().to_vec())
+            Some(attest_pubkey(b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20", b"four")),
+            Some(b"twitter:third".to_vec())
         ));
 
         assert_ok!(DidModule::lock(Origin::signed(4), 1000, 5));
@@ -371,7 +552,7 @@ fn without_did_should_not_pass_lock() {
             4u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("four".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20", b"four")),
             None
         ));
 
@@ -381,13 +562,197 @@ fn without_did_should_not_pass_lock() {
         );
     });
 }
+#[test]
+fn should_pass_reward_schedule() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::set_reward_schedule(
+            Origin::root(),
+            RewardSchedule {
+                rebate: Permill::from_percent(10),
+                cap: 100,
+                levels: vec![
+                    RewardLevel {
+                        share: Permill::from_percent(50),
+                        max_payout: Some(3),
+                    },
+                    RewardLevel {
+                        share: Permill::from_percent(30),
+                        max_payout: None,
+                    },
+                    RewardLevel {
+                        share: Permill::from_percent(20),
+                        max_payout: None,
+                    },
+                ],
+            }
+        ));
+
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            "1".as_bytes().to_vec(),
+            H256::zero(),
+            Some(attest_pubkey(b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20", b"four")),
+            Some(b"twitter:third".to_vec())
+        ));
+
+        // 20 rebated, the first level is capped at 3 and keeps the rest with the locker
+        assert_ok!(DidModule::lock(Origin::signed(4), 200, 5));
+        assert_eq!(Balances::free_balance(&3), 10003);
+        assert_eq!(Balances::free_balance(&2), 9006);
+        assert_eq!(Balances::free_balance(&1), 10029);
+        assert_eq!(Balances::reserved_balance(&4), 187);
+
+        let (_, from_did) = DidModule::identity(&4).unwrap();
+        let (_, to_did) = DidModule::identity(&1).unwrap();
+        assert!(System::events().iter().any(|record| record.event
+            == Event::did(RawEvent::ReferralRewarded(from_did.clone(), to_did.clone(), 3, 4))));
+
+        // a top up is rebated until the cap is reached
+        assert_ok!(DidModule::lock(Origin::signed(4), 100, 5));
+        assert_eq!(Balances::free_balance(&1), 10031);
+        assert_eq!(Balances::reserved_balance(&4), 279);
+    });
+}
+
+#[test]
+fn should_not_pass_reward_schedule() {
+    new_test_ext().execute_with(|| {
+        let level = |percent| RewardLevel {
+            share: Permill::from_percent(percent),
+            max_payout: None,
+        };
+        let schedule = |levels| RewardSchedule {
+            rebate: Permill::from_percent(50),
+            cap: 25,
+            levels,
+        };
+
+        assert_noop!(
+            DidModule::set_reward_schedule(Origin::signed(1), schedule(vec![level(100)])),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DidModule::set_reward_schedule(Origin::root(), schedule(Vec::new())),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_noop!(
+            DidModule::set_reward_schedule(Origin::root(), schedule(vec![level(10); 4])),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_noop!(
+            DidModule::set_reward_schedule(Origin::root(), schedule(vec![level(80), level(40)])),
+            Error::<Test>::InvalidSchedule
+        );
+
+        assert_eq!(DidModule::reward_schedule(), RewardSchedule::legacy(25));
+    });
+}
+
+#[test]
+fn should_compute_rewards_ratio() {
+    let curve = RewardsCurve::legacy();
+    assert_eq!(curve.max_quota(0), 0);
+    assert_eq!(curve.max_quota(u64::max_value()), u64::max_value());
+
+    // no subordinates or within the quota
+    assert_eq!(curve.rewards_ratio(0, 0), 20);
+    assert_eq!(curve.rewards_ratio(10, 5), 20);
+    assert_eq!(curve.rewards_ratio(10, 10), 20);
+
+    // the ratio rises with the share of subordinates beyond the quota
+    assert_eq!(curve.rewards_ratio(10, 20), 60);
+    assert_eq!(curve.rewards_ratio(10, 40), 80);
+    assert_eq!(curve.rewards_ratio(0, 4), 100);
+
+    let curve = RewardsCurve {
+        quota_per_unit: 1,
+        base_ratio: 0,
+        max_ratio: 50,
+    };
+    assert_eq!(curve.rewards_ratio(1, 3), 33);
+    assert_eq!(curve.rewards_ratio(u64::max_value() - 1, u64::max_value()), 0);
+    assert!(curve.is_valid());
+    assert!(!RewardsCurve { quota_per_unit: 0, ..curve.clone() }.is_valid());
+    assert!(!RewardsCurve { base_ratio: 60, ..curve.clone() }.is_valid());
+    assert!(!RewardsCurve { max_ratio: 101, ..curve }.is_valid());
+}
+
+#[test]
+fn should_pass_rewards_curve() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (second, _) = DidModule::identity(&2).unwrap();
+        assert_eq!(DidModule::metadata(&second).locked_records.unwrap().max_quota, 9750);
+
+        assert_ok!(DidModule::set_rewards_curve(
+            Origin::root(),
+            RewardsCurve {
+                quota_per_unit: 1,
+                base_ratio: 10,
+                max_ratio: 50,
+            }
+        ));
+
+        // proceeds are split by the new curve at once
+        let (user_key, _) = DidModule::identity(&3).unwrap();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        assert_ok!(DidModule::transfer_by_did(first, user_key, 100, TransferKind::AdReward, Vec::new()));
+        assert_eq!(Balances::free_balance(&2), 9010);
+        assert_eq!(Balances::free_balance(&3), 10090);
+
+        // the stored quota follows on the next change of subordinates
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            "1".as_bytes().to_vec(),
+            second,
+            None,
+            None
+        ));
+        let locked_records = DidModule::metadata(&second).locked_records.unwrap();
+        assert_eq!(locked_records.max_quota, 975);
+        assert_eq!(locked_records.rewards_ratio, 10);
+
+        assert_ok!(DidModule::deactivate(Origin::signed(4)));
+        assert_eq!(DidModule::metadata(&second).subordinate_count, 1);
+    });
+}
+
+#[test]
+fn should_not_pass_rewards_curve() {
+    new_test_ext().execute_with(|| {
+        let curve = RewardsCurve::legacy();
+
+        assert_noop!(
+            DidModule::set_rewards_curve(Origin::signed(1), curve.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DidModule::set_rewards_curve(Origin::root(), RewardsCurve { quota_per_unit: 0, ..curve.clone() }),
+            Error::<Test>::InvalidCurve
+        );
+        assert_noop!(
+            DidModule::set_rewards_curve(Origin::root(), RewardsCurve { base_ratio: 101, max_ratio: 101, ..curve }),
+            Error::<Test>::InvalidCurve
+        );
+    });
+}
+
 #[test]
 fn should_pass_force_lock() {
     new_test_ext().execute_with(|| {
         prepare_dids_for_test();
 
         let (user_key, _) = DidModule::identity(&2).unwrap();
-        assert_ok!(DidModule::force_lock(Origin::ROOT, user_key, 100));
+        assert_ok!(DidModule::force_lock(Origin::root(), user_key, 100));
 
         assert_eq!(Balances::free_balance(&2), 8900);
     });
@@ -404,7 +769,69 @@ fn should_pass_unlock() {
 
         assert_ok!(DidModule::unlock(Origin::signed(2), 100));
 
+        // unlocked funds stay reserved for the unbonding period
+        assert_eq!(Balances::free_balance(&2), 9000);
+        assert_noop!(
+            DidModule::withdraw_unbonded(Origin::signed(2)),
+            Error::<Test>::NoUnbonded
+        );
+
+        Timestamp::set_timestamp(55);
+        assert_ok!(DidModule::unlock(Origin::signed(2), 200));
+
+        Timestamp::set_timestamp(60);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(2)));
         assert_eq!(Balances::free_balance(&2), 9100);
+
+        let (user_key, _) = DidModule::identity(&2).unwrap();
+        assert_eq!(
+            DidModule::unbonding(&user_key),
+            vec![UnbondingChunk {
+                amount: 200,
+                release_time: 65
+            }]
+        );
+
+        Timestamp::set_timestamp(65);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(2)));
+        assert_eq!(Balances::free_balance(&2), 9300);
+        assert!(DidModule::unbonding(&user_key).is_empty());
+        assert_eq!(DidModule::unlock_history(&user_key).len(), 2);
+    });
+}
+
+#[test]
+fn should_not_pass_withdraw_unbonded() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::unlock(Origin::signed(2), 10));
+        // unlocks of the same moment share a chunk
+        assert_ok!(DidModule::unlock(Origin::signed(2), 10));
+        Timestamp::set_timestamp(6);
+        assert_ok!(DidModule::unlock(Origin::signed(2), 10));
+        Timestamp::set_timestamp(7);
+        assert_noop!(
+            DidModule::unlock(Origin::signed(2), 10),
+            Error::<Test>::TooManyUnbonding
+        );
+
+        // the history keeps the latest unlocks only
+        let (user_key, _) = DidModule::identity(&2).unwrap();
+        assert_eq!(DidModule::unbonding(&user_key).len(), 2);
+        assert_eq!(DidModule::unlock_history(&user_key).len(), 3);
+
+        Timestamp::set_timestamp(15);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(2)));
+        assert_ok!(DidModule::unlock(Origin::signed(2), 10));
+        assert_eq!(DidModule::unlock_history(&user_key).len(), 3);
+        assert_eq!(Balances::reserved_balance(&2), 955);
+
+        assert_noop!(
+            DidModule::withdraw_unbonded(Origin::signed(3)),
+            Error::<Test>::NoUnbonded
+        );
     });
 }
 
@@ -419,6 +846,11 @@ fn should_not_pass_unlock() {
             DidModule::unlock(Origin::signed(2), 2000),
             Error::<Test>::UnreservedFundsExceed
         );
+        // a zero unlock would only fill the unbonding and history slots
+        assert_noop!(
+            DidModule::unlock(Origin::signed(2), 0),
+            Error::<Test>::ZeroUnlock
+        );
 
         assert_ok!(Balances::reserve(&3, 100));
 
@@ -443,6 +875,89 @@ fn should_not_pass_unlock() {
     });
 }
 
+#[test]
+fn should_pass_lock_positions() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (user_key, _) = DidModule::identity(&2).unwrap();
+
+        // a second lock keeps the first one's clock
+        Timestamp::set_timestamp(3);
+        assert_ok!(DidModule::lock(Origin::signed(2), 500, 20));
+        let locked_records = DidModule::metadata(&user_key).locked_records.unwrap();
+        assert_eq!(locked_records.positions.len(), 2);
+        assert_eq!(locked_records.locked_funds(), 1475);
+        assert_eq!(locked_records.max_quota, 14750);
+        assert_eq!(Balances::reserved_balance(&2), 1475);
+
+        assert_noop!(
+            DidModule::unlock(Origin::signed(2), 100),
+            Error::<Test>::UnlockTimeNotReach
+        );
+
+        Timestamp::set_timestamp(5);
+        assert_noop!(
+            DidModule::unlock(Origin::signed(2), 976),
+            Error::<Test>::UnreservedFundsExceed
+        );
+        assert_ok!(DidModule::unlock(Origin::signed(2), 975));
+        let locked_records = DidModule::metadata(&user_key).locked_records.unwrap();
+        assert_eq!(
+            locked_records.positions,
+            vec![LockPosition {
+                amount: 500,
+                locked_time: 3,
+                locked_period: 20
+            }]
+        );
+        assert_eq!(locked_records.max_quota, 5000);
+
+        Timestamp::set_timestamp(23);
+        assert_ok!(DidModule::unlock(Origin::signed(2), 200));
+        assert_ok!(DidModule::unlock(Origin::signed(2), 300));
+        let locked_records = DidModule::metadata(&user_key).locked_records.unwrap();
+        assert!(locked_records.positions.is_empty());
+        assert_eq!(locked_records.max_quota, 0);
+
+        Timestamp::set_timestamp(33);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(2)));
+        assert_eq!(Balances::reserved_balance(&2), 0);
+    });
+}
+
+#[test]
+fn should_not_pass_lock_positions() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::lock(Origin::signed(2), 100, 5));
+        assert_ok!(DidModule::lock(Origin::signed(2), 100, 10));
+        assert_noop!(
+            DidModule::lock(Origin::signed(2), 100, 15),
+            Error::<Test>::TooManyLocks
+        );
+        // a zero top-up would only take a position
+        assert_noop!(
+            DidModule::lock(Origin::signed(3), 0, 5),
+            Error::<Test>::ZeroLock
+        );
+
+        // the rebate paid to the superiors is undone when the funds cannot be reserved
+        Balances::set_lock(*b"reserves", &3, 10000, WithdrawReasons::RESERVE);
+        assert_noop!(
+            DidModule::lock(Origin::signed(3), 1000, 5),
+            pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::LiquidityRestrictions
+        );
+        Balances::remove_lock(*b"reserves", &3);
+
+        // matured positions make room again
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::unlock(Origin::signed(2), 1075));
+        assert_ok!(DidModule::lock(Origin::signed(2), 100, 15));
+    });
+}
+
 #[test]
 fn should_pass_transfer() {
     new_test_ext().execute_with(|| {
@@ -467,7 +982,7 @@ fn should_pass_transfer() {
             events[events.len() - 1],
             EventRecord {
                 phase: Phase::Initialization,
-                event: TestEvent::did(RawEvent::Transfered(from_did, to_did, 100, memo.to_vec())),
+                event: Event::did(RawEvent::Transfered(from_did, to_did, 100, TransferKind::Plain, memo.to_vec())),
                 topics: vec![],
             }
         );
@@ -475,7 +990,7 @@ fn should_pass_transfer() {
         assert_eq!(Balances::free_balance(&2), 8900);
         assert_eq!(Balances::free_balance(&1), 10125);
 
-        // ads fee split
+        // the memo does not decide the split
         let (user_key, _) = DidModule::identity(&3).unwrap();
         assert_ok!(DidModule::transfer(
             Origin::signed(1),
@@ -483,9 +998,22 @@ fn should_pass_transfer() {
             1000,
             b"ads fee".to_vec()
         ));
-        assert_eq!(Balances::free_balance(&3), 10800);
-        assert_eq!(Balances::free_balance(&2), 9100);
+        assert_eq!(Balances::free_balance(&3), 11000);
+        assert_eq!(Balances::free_balance(&2), 8900);
         assert_eq!(Balances::free_balance(&1), 9125);
+
+        // ad rewards are split with the superior
+        let (from_user, _) = DidModule::identity(&1).unwrap();
+        assert_ok!(DidModule::transfer_by_did(
+            from_user,
+            user_key,
+            1000,
+            TransferKind::AdReward,
+            Vec::new()
+        ));
+        assert_eq!(Balances::free_balance(&3), 11800);
+        assert_eq!(Balances::free_balance(&2), 9100);
+        assert_eq!(Balances::free_balance(&1), 8125);
     });
 }
 
@@ -524,20 +1052,127 @@ fn should_not_pass_transfer() {
             4u64,
             "1".as_bytes().to_vec(),
             H256::zero(),
-            Some("four".as_bytes().to_vec()),
+            Some(attest_pubkey(b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20", b"four")),
             None
         ));
 
-        let memo = b"ads fee";
+        let (user_key_1, _) = DidModule::identity(&1).unwrap();
         let (user_key_4, _) = DidModule::identity(&4).unwrap();
 
         assert_noop!(
-            DidModule::transfer(Origin::signed(1), user_key_4, 1000, memo.to_vec()),
+            DidModule::transfer_by_did(user_key_1, user_key_4, 1000, TransferKind::AdReward, Vec::new()),
             Error::<Test>::SuperiorNotExists
         );
     });
 }
 
+#[test]
+fn should_pass_transfer_batch() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        prepare_dids_for_test();
+
+        let (first, first_did) = DidModule::identity(&1).unwrap();
+        let (_, second_did) = DidModule::identity(&2).unwrap();
+        let (third, third_did) = DidModule::identity(&3).unwrap();
+        assert_ok!(DidModule::transfer_batch(
+            Origin::signed(2),
+            vec![(first, 100, b"a".to_vec()), (third, 200, b"b".to_vec())]
+        ));
+        assert_eq!(Balances::free_balance(&2), 8700);
+        assert_eq!(Balances::free_balance(&1), 10125);
+        assert_eq!(Balances::free_balance(&3), 10200);
+
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 1].event,
+            Event::did(RawEvent::BatchTransfered(
+                second_did,
+                TransferKind::Plain,
+                vec![
+                    (first_did.clone(), 100, 0, b"a".to_vec()),
+                    (third_did.clone(), 200, 0, b"b".to_vec())
+                ]
+            ))
+        );
+
+        // a signed batch is plain, the receiver's superior gets nothing
+        assert_ok!(DidModule::transfer_batch(Origin::signed(1), vec![(third, 100, Vec::new())]));
+        assert_eq!(Balances::free_balance(&2), 8700);
+        assert_eq!(Balances::free_balance(&3), 10300);
+
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 1].event,
+            Event::did(RawEvent::BatchTransfered(
+                first_did.clone(),
+                TransferKind::Plain,
+                vec![(third_did.clone(), 100, 0, Vec::new())]
+            ))
+        );
+
+        // every ad reward is split on its own
+        assert_ok!(DidModule::transfer_batch_by_did(
+            first,
+            vec![(third, 1000, Vec::new()), (third, 10, Vec::new())],
+            TransferKind::AdReward
+        ));
+        assert_eq!(Balances::free_balance(&3), 11108);
+        assert_eq!(Balances::free_balance(&2), 8902);
+
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 1].event,
+            Event::did(RawEvent::BatchTransfered(
+                first_did,
+                TransferKind::AdReward,
+                vec![(third_did.clone(), 800, 200, Vec::new()), (third_did, 8, 2, Vec::new())]
+            ))
+        );
+    });
+}
+
+#[test]
+fn should_not_pass_transfer_batch() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        assert_noop!(
+            DidModule::transfer_batch(Origin::signed(4), vec![(first, 100, Vec::new())]),
+            Error::<Test>::DidNotExists
+        );
+        assert_noop!(
+            DidModule::transfer_batch(Origin::signed(2), Vec::new()),
+            Error::<Test>::InvalidBatch
+        );
+        assert_noop!(
+            DidModule::transfer_batch(Origin::signed(2), vec![(first, 1, Vec::new()); 4]),
+            Error::<Test>::InvalidBatch
+        );
+
+        // a failing entry rolls back the ones before it
+        assert_noop!(
+            DidModule::transfer_batch(
+                Origin::signed(2),
+                vec![(first, 100, Vec::new()), (H256::zero(), 100, Vec::new())]
+            ),
+            Error::<Test>::DidNotExists
+        );
+        assert_noop!(
+            DidModule::transfer_batch(
+                Origin::signed(2),
+                vec![(first, 5000, Vec::new()), (third, 5000, Vec::new())]
+            ),
+            Error::<Test>::NotEnoughBalance
+        );
+        assert_eq!(Balances::free_balance(&2), 9000);
+    });
+}
+
 #[test]
 fn should_pass_add_external_address() {
     new_test_ext().execute_with(|| {
@@ -548,32 +1183,1949 @@ fn should_pass_add_external_address() {
         assert_ok!(DidModule::add_external_address(
             Origin::signed(1),
             b"eos".to_vec(),
-            EOS_ADDRESS.to_vec()
+            EOS_ADDRESS.to_vec(),
+            Vec::new()
         ));
         assert_ok!(DidModule::add_external_address(
             Origin::signed(1),
             b"eth".to_vec(),
-            ETH_ADDRESS.to_vec()
+            ETH_ADDRESS.to_vec(),
+            sign_eth(1)
         ));
         assert_ok!(DidModule::add_external_address(
             Origin::signed(1),
             b"btc".to_vec(),
-            BTC_ADDRESS.to_vec()
+            BTC_ADDRESS.to_vec(),
+            sign_btc(1)
         ));
     });
 }
 
 #[test]
-fn should_pass_set_group_name() {
+fn should_not_pass_add_external_address() {
     new_test_ext().execute_with(|| {
-        System::set_block_number(0);
-
         prepare_dids_for_test();
 
-        assert_ok!(DidModule::lock(Origin::signed(2), 100, 5));
-        assert_ok!(DidModule::set_group_name(
-            Origin::signed(2),
-            b"btc group".to_vec()
-        ));
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"doge".to_vec(),
+                ETH_ADDRESS.to_vec(),
+                sign_eth(1)
+            ),
+            Error::<Test>::InvalidType
+        );
+        // broken base58check checksum and EIP-55 checksum
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"btc".to_vec(),
+                b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMh".to_vec(),
+                sign_btc(1)
+            ),
+            Error::<Test>::InvalidAddressFormat
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"eth".to_vec(),
+                b"7E5f4552091a69125d5dfcb7b8c2659029395bdf".to_vec(),
+                sign_eth(1)
+            ),
+            Error::<Test>::InvalidAddressFormat
+        );
+        // oversized input is refused before it is decoded
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"btc".to_vec(),
+                vec![b'1'; 129],
+                sign_btc(1)
+            ),
+            Error::<Test>::InvalidAddressFormat
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"btc".to_vec(),
+                BTC_ADDRESS.to_vec(),
+                vec![0; 66]
+            ),
+            Error::<Test>::InvalidProof
+        );
+
+        // signed for another did
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"eth".to_vec(),
+                ETH_ADDRESS.to_vec(),
+                sign_eth(2)
+            ),
+            Error::<Test>::InvalidProof
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"eth".to_vec(),
+                b"cb222a32df146ef7e3ac63725dad0fd978d33ce2".to_vec(),
+                sign_eth(1)
+            ),
+            Error::<Test>::InvalidProof
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"btc".to_vec(),
+                b"1N75dvASxn1CCjaeguyqvwXLXJun9e54mM".to_vec(),
+                sign_btc(1)
+            ),
+            Error::<Test>::InvalidProof
+        );
+
+        // a proof can not be replayed once the nonce moved on
+        let signature = sign_eth(1);
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"eth".to_vec(),
+            ETH_ADDRESS.to_vec(),
+            signature.clone()
+        ));
+        assert_ok!(DidModule::remove_external_address(
+            Origin::signed(1),
+            b"eth".to_vec(),
+            ETH_ADDRESS.to_vec()
+        ));
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"eth".to_vec(),
+                ETH_ADDRESS.to_vec(),
+                signature
+            ),
+            Error::<Test>::InvalidProof
+        );
+    });
+}
+
+#[test]
+fn should_pass_external_addresses() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        prepare_dids_for_test();
+        let (user_key, did) = DidModule::identity(&1).unwrap();
+
+        // checksummed and prefixed, stored lowercase under the CAIP-2 id
+        let mut checksummed = b"0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_vec();
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"eip155:1".to_vec(),
+            checksummed.clone(),
+            sign_eth(1)
+        ));
+        let eth = [&b"0x"[..], &ETH_ADDRESS[..]].concat();
+        assert_eq!(
+            DidModule::external_address_owner(b"eip155:1".to_vec(), &eth),
+            Some(user_key)
+        );
+
+        let (polkadot, signature) = sign_sr25519(1);
+        let polkadot_id = b"polkadot:91b171bb158e2d3848fa23a9f1c25182".to_vec();
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            polkadot_id.clone(),
+            polkadot.clone(),
+            signature
+        ));
+
+        // unverifiable chains take no signature
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            COSMOS_CHAIN_ID.to_vec(),
+            COSMOS_ADDRESS.to_vec(),
+            Vec::new()
+        ));
+
+        let (solana, signature) = sign_ed25519(1);
+        let solana_id = b"solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".to_vec();
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                solana_id.clone(),
+                solana.clone(),
+                signature.clone()
+            ),
+            Error::<Test>::TooManyChains
+        );
+
+        assert_ok!(DidModule::remove_external_address(
+            Origin::signed(1),
+            polkadot_id.clone(),
+            polkadot.clone()
+        ));
+        assert_eq!(DidModule::external_address_owner(&polkadot_id, &polkadot), None);
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            solana_id.clone(),
+            solana.clone(),
+            signature
+        ));
+
+        let metadata = DidModule::metadata(&user_key);
+        assert_eq!(metadata.external_addresses.len(), 3);
+        assert_eq!(metadata.external_addresses[&b"eip155:1".to_vec()], vec![eth.clone()]);
+        assert_eq!(
+            DidModule::resolve_by_key(user_key).unwrap().also_known_as,
+            vec![
+                [&COSMOS_CHAIN_ID[..], b":", &COSMOS_ADDRESS[..]].concat(),
+                [&b"eip155:1:"[..], &eth[..]].concat(),
+                [&solana_id[..], b":", &solana[..]].concat(),
+            ]
+        );
+
+        // removal matches the address in any spelling
+        checksummed.make_ascii_uppercase();
+        assert_ok!(DidModule::remove_external_address(
+            Origin::signed(1),
+            b"eth".to_vec(),
+            [&b"0x"[..], &checksummed[2..]].concat()
+        ));
+        assert!(!DidModule::metadata(&user_key)
+            .external_addresses
+            .contains_key(&b"eip155:1".to_vec()));
+        assert_eq!(DidModule::external_address_owner(b"eip155:1".to_vec(), &eth), None);
+
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 1],
+            EventRecord {
+                phase: Phase::Initialization,
+                event: Event::did(RawEvent::AddressRemoved(did, b"eip155:1".to_vec(), eth)),
+                topics: vec![],
+            }
+        );
+    });
+}
+
+#[test]
+fn should_not_pass_external_addresses() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"EIP155:1".to_vec(),
+                ETH_ADDRESS.to_vec(),
+                sign_eth(1)
+            ),
+            Error::<Test>::InvalidType
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                COSMOS_CHAIN_ID.to_vec(),
+                b"cosmos:address".to_vec(),
+                Vec::new()
+            ),
+            Error::<Test>::InvalidAddressFormat
+        );
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"tron:mainnet".to_vec(),
+                BTC_ADDRESS.to_vec(),
+                Vec::new()
+            ),
+            Error::<Test>::InvalidAddressFormat
+        );
+
+        // a polkadot proof made for another did
+        let (polkadot, signature) = sign_sr25519(2);
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"polkadot:91b171bb158e2d3848fa23a9f1c25182".to_vec(),
+                polkadot,
+                signature
+            ),
+            Error::<Test>::InvalidProof
+        );
+
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"tron:mainnet".to_vec(),
+            TRON_ADDRESS.to_vec(),
+            Vec::new()
+        ));
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"tron:mainnet".to_vec(),
+                TRON_ADDRESS.to_vec(),
+                Vec::new()
+            ),
+            Error::<Test>::AddressExists
+        );
+
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"eos".to_vec(),
+            EOS_ADDRESS.to_vec(),
+            Vec::new()
+        ));
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"eos".to_vec(),
+            b"eosio.token".to_vec(),
+            Vec::new()
+        ));
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(1),
+                b"eos".to_vec(),
+                b"eosio".to_vec(),
+                Vec::new()
+            ),
+            Error::<Test>::TooManyAddresses
+        );
+
+        assert_noop!(
+            DidModule::remove_external_address(
+                Origin::signed(1),
+                b"eos".to_vec(),
+                b"eosio".to_vec()
+            ),
+            Error::<Test>::AddressNotExists
+        );
+        assert_noop!(
+            DidModule::remove_external_address(
+                Origin::signed(2),
+                b"tron:mainnet".to_vec(),
+                TRON_ADDRESS.to_vec()
+            ),
+            Error::<Test>::AddressNotExists
+        );
+    });
+}
+
+#[test]
+fn should_pass_lookup_address() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_eq!(DidModule::lookup_address(b"eth".to_vec(), ETH_ADDRESS.to_vec()), None);
+
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(2),
+            b"eth".to_vec(),
+            ETH_ADDRESS.to_vec(),
+            sign_eth(2)
+        ));
+
+        let (_, did) = DidModule::identity(&2).unwrap();
+        let uri = Some(DidModule::did_uri(&did));
+        assert_eq!(DidModule::lookup_address(b"eth".to_vec(), ETH_ADDRESS.to_vec()), uri);
+        assert_eq!(
+            DidModule::lookup_address(
+                b"eip155:1".to_vec(),
+                b"0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_vec()
+            ),
+            uri
+        );
+        assert_eq!(DidModule::lookup_address(b"eip155:137".to_vec(), ETH_ADDRESS.to_vec()), None);
+
+        // unverified addresses may be linked by several DIDs and are not indexed
+        for account in 1..=2 {
+            assert_ok!(DidModule::add_external_address(
+                Origin::signed(account),
+                b"eos".to_vec(),
+                EOS_ADDRESS.to_vec(),
+                Vec::new()
+            ));
+        }
+        assert_eq!(DidModule::lookup_address(b"eos".to_vec(), EOS_ADDRESS.to_vec()), None);
+    });
+}
+
+#[test]
+fn should_not_pass_claimed_address() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(1),
+            b"btc".to_vec(),
+            BTC_ADDRESS.to_vec(),
+            sign_btc(1)
+        ));
+        assert_noop!(
+            DidModule::add_external_address(
+                Origin::signed(2),
+                b"btc".to_vec(),
+                BTC_ADDRESS.to_vec(),
+                sign_btc(2)
+            ),
+            Error::<Test>::AddressClaimed
+        );
+
+        // another did can not release the claim
+        assert_noop!(
+            DidModule::remove_external_address(
+                Origin::signed(2),
+                b"btc".to_vec(),
+                BTC_ADDRESS.to_vec()
+            ),
+            Error::<Test>::AddressNotExists
+        );
+
+        assert_ok!(DidModule::remove_external_address(
+            Origin::signed(1),
+            b"btc".to_vec(),
+            BTC_ADDRESS.to_vec()
+        ));
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(2),
+            b"btc".to_vec(),
+            BTC_ADDRESS.to_vec(),
+            sign_btc(2)
+        ));
+
+        let (_, did) = DidModule::identity(&2).unwrap();
+        assert_eq!(
+            DidModule::lookup_address(b"btc".to_vec(), BTC_ADDRESS.to_vec()),
+            Some(DidModule::did_uri(&did))
+        );
+    });
+}
+
+#[test]
+fn should_pass_social_binding() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (user_key, _) = DidModule::identity(&3).unwrap();
+        let third = DidModule::social_hash(b"twitter", b"third", b"1");
+        assert_eq!(DidModule::social_account(&third), user_key);
+        assert!(DidModule::social_attestation(&third).is_some());
+
+        // rename the handle
+        assert_ok!(DidModule::rebind_social_account(
+            Origin::signed(3),
+            attest_account(3, b"renamed")
+        ));
+        let renamed = DidModule::social_hash(b"twitter", b"renamed", b"1");
+        assert!(!SocialAccount::<Test>::contains_key(&third));
+        assert_eq!(DidModule::social_account(&renamed), user_key);
+        assert_eq!(DidModule::metadata(&user_key).social_account, Some(renamed));
+
+        assert_ok!(DidModule::unbind_social_account(Origin::signed(3)));
+        assert!(!SocialAccount::<Test>::contains_key(&renamed));
+        assert!(DidModule::social_attestation(&renamed).is_none());
+        assert_eq!(DidModule::metadata(&user_key).social_account, None);
+
+        // the released handle can be bound by another did
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            [5u8; 32].to_vec(),
+            4u64,
+            "1".as_bytes().to_vec(),
+            H256::zero(),
+            None,
+            None
+        ));
+        assert_ok!(DidModule::bind_social_account(
+            Origin::signed(4),
+            attest_account(4, b"third")
+        ));
+        let (user_key_4, _) = DidModule::identity(&4).unwrap();
+        assert_eq!(DidModule::social_account(&third), user_key_4);
+
+        // accounts bound on first use are taken over by an attested claim
+        let legacy = DidModule::social_hash(b"twitter", b"legacy", b"1");
+        SocialAccount::<Test>::insert(&legacy, user_key_4);
+        assert_ok!(DidModule::bind_social_account(
+            Origin::signed(3),
+            attest_account(3, b"legacy")
+        ));
+        assert_eq!(DidModule::social_account(&legacy), user_key);
+        assert_eq!(DidModule::metadata(&user_key_4).social_account, Some(third));
+    });
+}
+
+#[test]
+fn should_not_pass_social_binding() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), attest_account(3, b"other")),
+            Error::<Test>::AlreadyBound
+        );
+        assert_ok!(DidModule::unbind_social_account(Origin::signed(3)));
+        assert_noop!(
+            DidModule::unbind_social_account(Origin::signed(3)),
+            Error::<Test>::NotBound
+        );
+
+        // taken, attested for another did, unknown attestor, other platform
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), attest_account(3, b"second")),
+            Error::<Test>::SocialAccountBound
+        );
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), attest_account(2, b"third")),
+            Error::<Test>::InvalidAttestation
+        );
+        let mut proof = attest_account(3, b"third");
+        proof.attestor = 8;
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), proof),
+            Error::<Test>::AttestorNotExists
+        );
+        let mut proof = attest_account(3, b"third");
+        proof.platform = b"telegram".to_vec();
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), proof),
+            Error::<Test>::InvalidAttestation
+        );
+
+        // an attestation is spent once used
+        let proof = attest_account(3, b"third");
+        assert_ok!(DidModule::bind_social_account(Origin::signed(3), proof.clone()));
+        assert_ok!(DidModule::unbind_social_account(Origin::signed(3)));
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), proof),
+            Error::<Test>::InvalidAttestation
+        );
+
+        assert_noop!(
+            DidModule::register_attestor(Origin::signed(1), 8, b"twitter".to_vec(), [0u8; 32].to_vec()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(DidModule::remove_attestor(Origin::root(), ATTESTOR));
+        assert_noop!(
+            DidModule::bind_social_account(Origin::signed(3), attest_account(3, b"third")),
+            Error::<Test>::AttestorNotExists
+        );
+    });
+}
+
+#[test]
+fn should_pass_social_dispute() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        prepare_dids_for_test();
+
+        let (user_key_2, _) = DidModule::identity(&2).unwrap();
+        let (user_key_3, _) = DidModule::identity(&3).unwrap();
+        let second = DidModule::social_hash(b"twitter", b"second", b"1");
+
+        assert_noop!(
+            DidModule::dispute_social_account(Origin::signed(3), attest_account(3, b"second")),
+            Error::<Test>::AlreadyBound
+        );
+        assert_ok!(DidModule::unbind_social_account(Origin::signed(3)));
+
+        // the claim is rejected and the deposit slashed
+        assert_ok!(DidModule::dispute_social_account(
+            Origin::signed(3),
+            attest_account(3, b"second")
+        ));
+        assert_eq!(Balances::reserved_balance(&3), 100);
+        assert_noop!(
+            DidModule::dispute_social_account(Origin::signed(3), attest_account(3, b"second")),
+            Error::<Test>::DisputeExists
+        );
+        assert_ok!(DidModule::resolve_social_dispute(Origin::root(), second, false));
+        assert_eq!(Balances::reserved_balance(&3), 0);
+        assert_eq!(Balances::free_balance(&3), 9900);
+        assert_eq!(DidModule::social_account(&second), user_key_2);
+
+        // the claim is upheld and the account moves over
+        assert_ok!(DidModule::dispute_social_account(
+            Origin::signed(3),
+            attest_account(3, b"second")
+        ));
+        assert_ok!(DidModule::resolve_social_dispute(Origin::root(), second, true));
+        assert_eq!(Balances::free_balance(&3), 9900);
+        assert_eq!(DidModule::social_account(&second), user_key_3);
+        assert_eq!(DidModule::metadata(&user_key_3).social_account, Some(second));
+        assert_eq!(DidModule::metadata(&user_key_2).social_account, None);
+
+        // the displaced did is told it lost the account
+        let (_, did_2) = DidModule::identity(&2).unwrap();
+        let (_, did_3) = DidModule::identity(&3).unwrap();
+        let events = System::events();
+        assert!(events.iter().any(|record| record.event
+            == Event::did(RawEvent::SocialUnbound(did_2.clone(), second))));
+        assert!(events.iter().any(|record| record.event
+            == Event::did(RawEvent::SocialBound(did_3.clone(), second, ATTESTOR))));
+
+        assert_noop!(
+            DidModule::resolve_social_dispute(Origin::root(), second, true),
+            Error::<Test>::DisputeNotExists
+        );
+    });
+}
+
+#[test]
+fn should_pass_deactivate() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (user_key, _) = DidModule::identity(&3).unwrap();
+        let (superior_key, _) = DidModule::identity(&2).unwrap();
+        assert_eq!(DidModule::metadata(&superior_key).subordinate_count, 1);
+
+        assert_ok!(DidModule::add_service(
+            Origin::signed(3),
+            b"inbox".to_vec(),
+            b"MessagingService".to_vec(),
+            b"https://inbox.parami.io/3".to_vec()
+        ));
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(3),
+            b"eth".to_vec(),
+            ETH_ADDRESS.to_vec(),
+            sign_eth(3)
+        ));
+        assert_ok!(DidModule::lock(Origin::signed(3), 100, 0));
+        assert_ok!(DidModule::unlock(Origin::signed(3), 10));
+        assert_eq!(DidModule::unlock_history(&user_key).len(), 1);
+        Timestamp::set_timestamp(10);
+
+        assert_ok!(DidModule::deactivate(Origin::signed(3)));
+        assert_eq!(Balances::reserved_balance(&3), 0);
+        assert!(DidModule::unlock_history(&user_key).is_empty());
+        assert_eq!(DidModule::identity(&3), None);
+        assert_eq!(DidModule::identity_of(&user_key), None);
+        assert!(!<Metadata<Test>>::contains_key(&user_key));
+        assert!(DidModule::services(&user_key).is_empty());
+        assert_eq!(DidModule::lookup_address(b"eth".to_vec(), ETH_ADDRESS.to_vec()), None);
+        assert_eq!(DidModule::metadata(&superior_key).subordinate_count, 0);
+        assert_eq!(DidModule::tombstone(&user_key), Some(10));
+
+        // the social account is free again, the DID is not
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            "1".as_bytes().to_vec(),
+            H256::zero(),
+            Some(attest_pubkey(b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20", b"third")),
+            Some(b"twitter:second".to_vec())
+        ));
+        assert_noop!(
+            DidModule::create(
+                Origin::signed(1),
+                b"0x5e9c79234b5e55348fc60f38b28c2cc60d8bb4bd2862eae2179a05ec39e62658".to_vec(),
+                3u64,
+                "1".as_bytes().to_vec(),
+                H256::zero(),
+                None,
+                None
+            ),
+            Error::<Test>::DidDeactivated
+        );
+    });
+}
+
+#[test]
+fn should_not_pass_deactivate() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            DidModule::deactivate(Origin::signed(4)),
+            Error::<Test>::DidNotExists
+        );
+        assert_noop!(
+            DidModule::deactivate(Origin::signed(1)),
+            Error::<Test>::NotEligible
+        );
+        assert_noop!(
+            DidModule::deactivate(Origin::signed(2)),
+            Error::<Test>::HasSubordinates
+        );
+
+        assert_ok!(DidModule::deactivate(Origin::signed(3)));
+        assert_noop!(
+            DidModule::deactivate(Origin::signed(2)),
+            Error::<Test>::UnlockTimeNotReach
+        );
+
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::deactivate(Origin::signed(2)));
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        assert_eq!(Balances::free_balance(&2), 9975);
+    });
+}
+
+#[test]
+fn should_pass_subordinate_index() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+        for (pubkey, account) in [(b"four".to_vec(), 4u64), (b"five".to_vec(), 5u64)].iter() {
+            assert_ok!(DidModule::create(
+                Origin::signed(1),
+                pubkey.clone(),
+                *account,
+                "1".as_bytes().to_vec(),
+                first,
+                None,
+                None
+            ));
+        }
+        let (fourth, _) = DidModule::identity(&4).unwrap();
+        let (fifth, _) = DidModule::identity(&5).unwrap();
+
+        assert_eq!(DidModule::child_count(&first), 3);
+        assert_eq!(DidModule::children(first, 0), vec![second, fourth, fifth]);
+        assert!(DidModule::children(first, 1).is_empty());
+        assert_eq!(
+            DidModule::descendants(first, 2, 0, 10),
+            vec![(second, 1), (fourth, 1), (fifth, 1), (third, 2)]
+        );
+        assert_eq!(DidModule::descendants(first, 1, 0, 10).len(), 3);
+        assert_eq!(DidModule::descendants(first, 2, 2, 1), vec![(fifth, 1)]);
+        assert!(DidModule::descendants(first, 2, 4, 10).is_empty());
+        assert_eq!(
+            DidModule::descendants(first, 2, 3, u32::max_value()),
+            vec![(third, 2)]
+        );
+        assert_eq!(DidModule::ancestors(third), vec![second, first]);
+        assert!(DidModule::ancestors(first).is_empty());
+
+        // the last child fills the gap
+        assert_ok!(DidModule::deactivate(Origin::signed(4)));
+        assert_eq!(DidModule::children(first, 0), vec![second, fifth]);
+        assert_eq!(DidModule::child_count(&first), 2);
+        assert_eq!(DidModule::child_position(&fifth), Some((0, 1)));
+        assert_eq!(DidModule::child_position(&fourth), None);
+
+        let extra: Vec<H256> = (0..=SUBORDINATE_PAGE_SIZE as u8).map(H256::repeat_byte).collect();
+        for child in extra.iter() {
+            DidModule::add_child(third, *child);
+        }
+        assert_eq!(DidModule::children(third, 1), vec![extra[64]]);
+        DidModule::remove_child(third, extra[64]);
+        DidModule::remove_child(third, extra[0]);
+        assert!(!<Subordinates<Test>>::contains_key(&third, 1));
+        assert_eq!(DidModule::children(third, 0)[0], extra[63]);
+        assert_eq!(DidModule::child_count(&third), 63);
+    });
+}
+
+#[test]
+fn should_pass_recovery() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (user_key, did) = DidModule::identity(&2).unwrap();
+        let (guardian1, _) = DidModule::identity(&1).unwrap();
+        let (guardian2, _) = DidModule::identity(&3).unwrap();
+        assert_ok!(DidModule::set_recovery(Origin::signed(2), vec![guardian1, guardian2], 2));
+
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(1), user_key, 5));
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(5), user_key),
+            Error::<Test>::RecoveryNotReady
+        );
+
+        // the delay starts once the threshold is reached
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(3), user_key, 5));
+        Timestamp::set_timestamp(14);
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(5), user_key),
+            Error::<Test>::RecoveryNotReady
+        );
+
+        Timestamp::set_timestamp(15);
+        assert_ok!(DidModule::claim_recovery(Origin::signed(5), user_key));
+        assert_eq!(DidModule::identity(&5), Some((user_key, did)));
+        assert_eq!(DidModule::identity(&2), None);
+        assert_eq!(DidModule::identity_of(&user_key), Some(5));
+        assert_eq!(DidModule::active_recovery(&user_key), None);
+
+        // locked funds follow the DID, the free balance stays behind
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        assert_eq!(Balances::free_balance(&2), 9000);
+        assert_eq!(Balances::reserved_balance(&5), 975);
+        assert_eq!(Balances::free_balance(&5), 10000);
+
+        let metadata = DidModule::metadata(&user_key);
+        assert!(metadata.keys.iter().any(|k| k.public == 5u64.encode()));
+        assert!(!metadata.keys.iter().any(|k| k.public == 2u64.encode()));
+
+        Timestamp::set_timestamp(20);
+        assert_ok!(DidModule::unlock(Origin::signed(5), 100));
+        Timestamp::set_timestamp(30);
+        assert_ok!(DidModule::withdraw_unbonded(Origin::signed(5)));
+        assert_eq!(Balances::reserved_balance(&5), 875);
+    });
+}
+
+#[test]
+fn should_pass_recovery_with_competing_accounts() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            [4u8; 32].to_vec(),
+            4u64,
+            "1".as_bytes().to_vec(),
+            H256::zero(),
+            None,
+            None
+        ));
+
+        let (user_key, _) = DidModule::identity(&2).unwrap();
+        let guardians: Vec<H256> = [1u64, 3, 4]
+            .iter()
+            .map(|account| DidModule::identity(account).unwrap().0)
+            .collect();
+        assert_ok!(DidModule::set_recovery(Origin::signed(2), guardians, 2));
+
+        // split vouches, then the first account backed by the threshold wins
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(1), user_key, 5));
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(3), user_key, 6));
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(5), user_key),
+            Error::<Test>::RecoveryNotReady
+        );
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(4), user_key, 6));
+
+        // a threshold of guardians replaces the stale attempt, its delay starts over
+        Timestamp::set_timestamp(5);
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(3), user_key, 5));
+        Timestamp::set_timestamp(10);
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(6), user_key),
+            Error::<Test>::NotEligible
+        );
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(5), user_key),
+            Error::<Test>::RecoveryNotReady
+        );
+
+        Timestamp::set_timestamp(15);
+        assert_ok!(DidModule::claim_recovery(Origin::signed(5), user_key));
+        assert_eq!(DidModule::identity_of(&user_key), Some(5));
+    });
+}
+
+#[test]
+fn should_not_pass_recovery() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let (user_key, _) = DidModule::identity(&3).unwrap();
+        let (guardian1, _) = DidModule::identity(&1).unwrap();
+        let (guardian2, _) = DidModule::identity(&2).unwrap();
+
+        assert_noop!(
+            DidModule::vouch_recovery(Origin::signed(1), user_key, 5),
+            Error::<Test>::RecoveryNotExists
+        );
+        assert_noop!(
+            DidModule::set_recovery(Origin::signed(3), vec![guardian1, guardian2], 0),
+            Error::<Test>::InvalidRecovery
+        );
+        assert_noop!(
+            DidModule::set_recovery(Origin::signed(3), vec![guardian1, guardian1], 1),
+            Error::<Test>::InvalidRecovery
+        );
+        assert_noop!(
+            DidModule::set_recovery(Origin::signed(3), vec![guardian1, user_key], 1),
+            Error::<Test>::InvalidRecovery
+        );
+        assert_noop!(
+            DidModule::set_recovery(Origin::signed(3), vec![guardian1, H256::zero()], 1),
+            Error::<Test>::GuardianNotExists
+        );
+        assert_noop!(
+            DidModule::set_recovery(Origin::signed(3), vec![guardian1; 4], 1),
+            Error::<Test>::TooManyGuardians
+        );
+
+        assert_ok!(DidModule::set_recovery(Origin::signed(3), vec![guardian1], 1));
+        assert_noop!(
+            DidModule::vouch_recovery(Origin::signed(2), user_key, 5),
+            Error::<Test>::NotGuardian
+        );
+        assert_noop!(
+            DidModule::vouch_recovery(Origin::signed(1), user_key, 2),
+            Error::<Test>::PublicKeyUsed
+        );
+
+        assert_ok!(DidModule::set_recovery(Origin::signed(3), vec![guardian1, guardian2], 1));
+        assert_ok!(DidModule::vouch_recovery(Origin::signed(1), user_key, 5));
+        assert_noop!(
+            DidModule::vouch_recovery(Origin::signed(1), user_key, 5),
+            Error::<Test>::AlreadyVouched
+        );
+        assert_noop!(
+            DidModule::remove_recovery(Origin::signed(3)),
+            Error::<Test>::RecoveryInProgress
+        );
+
+        Timestamp::set_timestamp(10);
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(4), user_key),
+            Error::<Test>::NotEligible
+        );
+
+        // the current key cancels a recovery it did not ask for
+        assert_ok!(DidModule::cancel_recovery(Origin::signed(3)));
+        assert_noop!(
+            DidModule::claim_recovery(Origin::signed(5), user_key),
+            Error::<Test>::RecoveryNotExists
+        );
+        assert_ok!(DidModule::remove_recovery(Origin::signed(3)));
+        assert_eq!(DidModule::recovery_config(&user_key), None);
+    });
+}
+
+#[test]
+fn should_pass_set_group_name() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::lock(Origin::signed(2), 100, 5));
+        assert_ok!(DidModule::set_group_name(
+            Origin::signed(2),
+            b"btc group".to_vec()
+        ));
+    });
+}
+
+#[test]
+fn should_pass_key_management() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        let device_key = [7u8; 32].to_vec();
+        let new_key = [8u8; 33].to_vec();
+
+        assert_ok!(DidModule::add_key(
+            Origin::signed(2),
+            KeyType::Ed25519,
+            device_key.clone()
+        ));
+        assert_ok!(DidModule::rotate_key(
+            Origin::signed(2),
+            device_key.clone(),
+            KeyType::Ecdsa,
+            new_key.clone()
+        ));
+
+        let (user_key, _) = DidModule::identity(&2).unwrap();
+        let metadata = DidModule::metadata(&user_key);
+        assert_eq!(metadata.keys.len(), 2);
+        assert_eq!(metadata.keys[1].public, new_key);
+        // created, added, removed and added again
+        assert_eq!(metadata.key_history.len(), 4);
+
+        assert_ok!(DidModule::remove_key(Origin::signed(2), new_key));
+
+        // key changes never touch balances or reserved funds
+        assert_eq!(Balances::free_balance(&2), 9000);
+        assert_eq!(Balances::reserved_balance(&2), 975);
+
+        // rotating the controlling key hands the did and its reserved funds over
+        let mut controller = 5u64.encode();
+        controller.resize(32, 0);
+        assert_ok!(DidModule::rotate_key(
+            Origin::signed(2),
+            2u64.encode(),
+            KeyType::Sr25519,
+            controller
+        ));
+        assert_eq!(DidModule::identity(&2), None);
+        assert_eq!(DidModule::identity(&5).map(|(key, _)| key), Some(user_key));
+        assert_eq!(DidModule::identity_of(&user_key), Some(5));
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        assert_eq!(Balances::reserved_balance(&5), 975);
+    });
+}
+
+#[test]
+fn should_not_pass_key_management() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            DidModule::add_key(Origin::signed(2), KeyType::Sr25519, [7u8; 33].to_vec()),
+            Error::<Test>::InvalidKey
+        );
+
+        assert_ok!(DidModule::add_key(
+            Origin::signed(2),
+            KeyType::Sr25519,
+            [7u8; 32].to_vec()
+        ));
+        assert_noop!(
+            DidModule::add_key(Origin::signed(2), KeyType::Ed25519, [7u8; 32].to_vec()),
+            Error::<Test>::KeyExists
+        );
+
+        assert_ok!(DidModule::add_key(
+            Origin::signed(2),
+            KeyType::Sr25519,
+            [8u8; 32].to_vec()
+        ));
+        assert_noop!(
+            DidModule::add_key(Origin::signed(2), KeyType::Sr25519, [9u8; 32].to_vec()),
+            Error::<Test>::TooManyKeys
+        );
+
+        assert_noop!(
+            DidModule::remove_key(Origin::signed(2), [9u8; 32].to_vec()),
+            Error::<Test>::KeyNotExists
+        );
+
+        assert_noop!(
+            DidModule::remove_key(Origin::signed(3), 3u64.encode()),
+            Error::<Test>::LastKey
+        );
+        assert_noop!(
+            DidModule::remove_key(Origin::signed(2), 2u64.encode()),
+            Error::<Test>::ControllerKey
+        );
+        assert_noop!(
+            DidModule::rotate_key(Origin::signed(2), 2u64.encode(), KeyType::Ed25519, [9u8; 32].to_vec()),
+            Error::<Test>::InvalidKey
+        );
+        assert_noop!(
+            DidModule::rotate_key(Origin::signed(2), 2u64.encode(), KeyType::Sr25519, {
+                let mut taken = 3u64.encode();
+                taken.resize(32, 0);
+                taken
+            }),
+            Error::<Test>::PublicKeyUsed
+        );
+    });
+}
+
+#[test]
+fn should_pass_resolve() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::add_external_address(
+            Origin::signed(2),
+            b"eth".to_vec(),
+            ETH_ADDRESS.to_vec(),
+            sign_eth(2)
+        ));
+
+        let (user_key, did) = DidModule::identity(&2).unwrap();
+        let document = DidModule::resolve_by_key(user_key).unwrap();
+
+        let mut id = b"did:ad3:".to_vec();
+        id.append(&mut check::to_base58(&did));
+        assert_eq!(document.id, id);
+        assert_eq!(document.controller, id);
+        assert_eq!(document.verification_method.len(), 1);
+        let mut multikey = vec![0xef, 0x01];
+        multikey.append(&mut 2u64.encode());
+        let mut key_id = [&id[..], b"#z"].concat();
+        key_id.append(&mut check::to_base58(&multikey));
+        assert_eq!(document.authentication, vec![key_id.clone()]);
+        assert_eq!(document.verification_method[0].id, key_id);
+        assert_eq!(document.verification_method[0].public_key_multibase, key_id[id.len() + 1..].to_vec());
+        assert_eq!(
+            document.also_known_as,
+            vec![[&b"eip155:1:0x"[..], &ETH_ADDRESS[..]].concat()]
+        );
+
+        assert_eq!(DidModule::resolve(id), Some(document));
+        assert_eq!(DidModule::resolve(b"did:ad3:unknown".to_vec()), None);
+        assert_eq!(DidModule::resolve_by_key(H256::zero()), None);
+    });
+}
+
+#[test]
+fn should_pass_service_endpoints() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_ok!(DidModule::add_service(
+            Origin::signed(3),
+            b"inbox".to_vec(),
+            b"MessagingService".to_vec(),
+            b"https://inbox.parami.io/3".to_vec()
+        ));
+        assert_eq!(Balances::reserved_balance(&3), 10);
+
+        assert_ok!(DidModule::update_service(
+            Origin::signed(3),
+            b"inbox".to_vec(),
+            b"MessagingService".to_vec(),
+            b"https://inbox.parami.io/three".to_vec()
+        ));
+
+        let (user_key, _) = DidModule::identity(&3).unwrap();
+        let document = DidModule::resolve_by_key(user_key).unwrap();
+        assert_eq!(document.service.len(), 1);
+        assert_eq!(
+            document.service[0].service_endpoint,
+            b"https://inbox.parami.io/three".to_vec()
+        );
+
+        // the deposit goes back to the account which paid it
+        assert_ok!(DidModule::update(Origin::signed(3), 4u64));
+        assert_eq!(Balances::reserved_balance(&3), 10);
+        assert_ok!(DidModule::remove_service(Origin::signed(4), b"inbox".to_vec()));
+        assert_eq!(Balances::reserved_balance(&3), 0);
+        assert_eq!(Balances::reserved_balance(&4), 0);
+        assert!(DidModule::services(user_key).is_empty());
+    });
+}
+
+#[test]
+fn should_not_pass_service_endpoints() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            DidModule::add_service(
+                Origin::signed(3),
+                b"inbox".to_vec(),
+                b"MessagingService".to_vec(),
+                [b'a'; 33].to_vec()
+            ),
+            Error::<Test>::InvalidService
+        );
+
+        assert_ok!(DidModule::add_service(
+            Origin::signed(3),
+            b"inbox".to_vec(),
+            b"MessagingService".to_vec(),
+            b"https://inbox.parami.io".to_vec()
+        ));
+        assert_noop!(
+            DidModule::add_service(
+                Origin::signed(3),
+                b"inbox".to_vec(),
+                b"ProfileHub".to_vec(),
+                b"https://hub.parami.io".to_vec()
+            ),
+            Error::<Test>::ServiceExists
+        );
+
+        assert_ok!(DidModule::add_service(
+            Origin::signed(3),
+            b"hub".to_vec(),
+            b"ProfileHub".to_vec(),
+            b"https://hub.parami.io".to_vec()
+        ));
+        assert_noop!(
+            DidModule::add_service(
+                Origin::signed(3),
+                b"agent".to_vec(),
+                b"AdAgent".to_vec(),
+                b"https://agent.parami.io".to_vec()
+            ),
+            Error::<Test>::TooManyServices
+        );
+
+        assert_noop!(
+            DidModule::remove_service(Origin::signed(3), b"agent".to_vec()),
+            Error::<Test>::ServiceNotExists
+        );
+
+        // service deposits are not locked funds
+        assert_noop!(
+            DidModule::unlock(Origin::signed(3), 10),
+            Error::<Test>::NotLockFunds
+        );
+    });
+}
+
+#[test]
+fn should_resolve_short_id() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        for (sequence, account) in [1u64, 2, 3].iter().enumerate() {
+            let (user_key, did) = DidModule::identity(account).unwrap();
+            let short_id = DidModule::index_by_key(&user_key);
+            assert_eq!(short_id.len(), 6);
+            assert_eq!(DidModule::decode_short_id(&short_id), Some(sequence as u64));
+            assert_eq!(
+                DidModule::resolve_short_id(short_id),
+                Some(ShortIdRecord {
+                    sequence: sequence as u64,
+                    user_key,
+                    did: DidModule::did_uri(&did),
+                    account: *account,
+                })
+            );
+        }
+
+        // issued by the same salt but to no DID yet
+        let unused = DidModule::short_id_of(10).unwrap();
+        assert_eq!(DidModule::decode_short_id(&unused), Some(10));
+        assert_eq!(DidModule::resolve_short_id(unused), None);
+
+        assert_eq!(DidModule::decode_short_id(b"$$$$$$"), None);
+        assert_eq!(DidModule::resolve_short_id(b"$$$$$$".to_vec()), None);
+
+        // nothing longer than the largest sequence is decoded
+        let largest = DidModule::short_id_of(u64::MAX).unwrap();
+        assert_eq!(DidModule::decode_short_id(&largest), Some(u64::MAX));
+        assert_eq!(DidModule::decode_short_id(&[largest.clone(), largest].concat()), None);
+    });
+}
+
+/// A flat fee of 5, standing in for `ChargeTransactionPayment`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+struct ChargeFee;
+
+impl SignedExtension for ChargeFee {
+    const IDENTIFIER: &'static str = "ChargeFee";
+    type AccountId = u64;
+    type Call = Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn pre_dispatch(self, who: &u64, _: &Call, _: &DispatchInfo, _: usize) -> Result<(), TransactionValidityError> {
+        if Balances::free_balance(who) < 5 {
+            return Err(InvalidTransaction::Payment.into());
+        }
+        let _ = Balances::slash(who, 5);
+        Ok(())
+    }
+}
+
+fn charge() -> FeelessOrCharge<Test, ChargeFee> {
+    ChargeFee.into()
+}
+
+fn remark() -> Call {
+    Call::System(frame_system::Call::remark(Vec::new()))
+}
+
+fn info() -> DispatchInfo {
+    Default::default()
+}
+
+#[test]
+fn should_pass_feeless_calls() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        // 1000 locked earns two calls, nothing locked earns none
+        assert_eq!(DidModule::feeless_quota(first), 0);
+        assert_eq!(DidModule::feeless_quota(second), 2);
+
+        let balance = Balances::free_balance(&2);
+        for _ in 0..2 {
+            assert_ok!(charge().validate(&2, &remark(), &info(), 0));
+            assert_eq!(charge().pre_dispatch(&2, &remark(), &info(), 0), Ok(None));
+        }
+        assert_eq!(Balances::free_balance(&2), balance);
+        assert_eq!(DidModule::feeless_calls_left(second), 0);
+
+        // out of quota the fee is charged
+        assert_eq!(charge().pre_dispatch(&2, &remark(), &info(), 0), Ok(Some(())));
+        assert_eq!(Balances::free_balance(&2), balance - 5);
+        assert_ok!(FeelessOrCharge::<Test, ChargeFee>::post_dispatch(None, &info(), &PostDispatchInfo::default(), 0, &Ok(())));
+
+        // the quota starts over every era
+        System::set_block_number(10);
+        assert_eq!(DidModule::feeless_calls_left(second), 2);
+        assert_eq!(charge().pre_dispatch(&2, &remark(), &info(), 0), Ok(None));
+        assert_eq!(DidModule::feeless_usage(&second), (1, 1));
+
+        <FeelessUsage<Test>>::insert(&third, (1, 1));
+        assert_ok!(DidModule::deactivate(Origin::signed(3)));
+        assert_eq!(DidModule::feeless_usage(&third), (0, 0));
+    });
+}
+
+#[test]
+fn should_tag_feeless_calls() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(3);
+        prepare_dids_for_test();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let tag = |index: u32| ("DidFeeless", (second, 0u64, index)).encode();
+
+        // a second call of the same index replaces the first in the pool
+        let valid = charge().validate(&2, &remark(), &info(), 0).unwrap();
+        assert_eq!(valid.provides, vec![tag(0)]);
+        assert_eq!(valid.priority, 0);
+        assert_eq!(valid.longevity, 7);
+        assert_eq!(charge().validate(&2, &remark(), &info(), 0).unwrap().provides, vec![tag(0)]);
+
+        assert_eq!(charge().pre_dispatch(&2, &remark(), &info(), 0), Ok(None));
+        assert_eq!(charge().validate(&2, &remark(), &info(), 0).unwrap().provides, vec![tag(1)]);
+    });
+}
+
+#[test]
+fn should_not_pass_feeless_calls() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        // accounts without a did always pay
+        let balance = Balances::free_balance(&4);
+        assert_eq!(charge().pre_dispatch(&4, &remark(), &info(), 0), Ok(Some(())));
+        assert_eq!(Balances::free_balance(&4), balance - 5);
+
+        // so do dids without locked funds
+        let balance = Balances::free_balance(&3);
+        assert_eq!(charge().pre_dispatch(&3, &remark(), &info(), 0), Ok(Some(())));
+        assert_eq!(Balances::free_balance(&3), balance - 5);
+
+        assert_eq!(
+            charge().pre_dispatch(&6, &remark(), &info(), 0),
+            Err(InvalidTransaction::Payment.into())
+        );
+    });
+}
+
+fn agency(creators: DidTypeCreators<u64>, deposit: u64) -> DidTypeInfo<u64, u64> {
+    DidTypeInfo {
+        name: b"agency".to_vec(),
+        creators,
+        deposit,
+        capabilities: vec![Capability::PublishAds, Capability::PublishAds],
+    }
+}
+
+#[test]
+fn should_pass_did_types() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, first_did) = DidModule::identity(&1).unwrap();
+
+        assert_ok!(DidModule::set_did_type(
+            Origin::root(),
+            b"7".to_vec(),
+            agency(DidTypeCreators::Accounts(vec![2]), 100)
+        ));
+        assert_eq!(
+            DidModule::did_type(b"7".to_vec()).unwrap().capabilities,
+            vec![Capability::PublishAds]
+        );
+
+        assert_ok!(DidModule::create(
+            Origin::signed(2),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            b"7".to_vec(),
+            first,
+            None,
+            None
+        ));
+        let (user_key, did) = DidModule::identity(&4).unwrap();
+        assert_eq!(DidModule::did_type_of(&did), b"7");
+        assert_eq!(DidModule::type_deposit(&user_key), Some((2, 100)));
+        assert_eq!(Balances::reserved_balance(&2), 1100);
+
+        assert!(DidModule::has_capability(&did, Capability::PublishAds));
+        assert!(!DidModule::has_capability(&first_did, Capability::PublishAds));
+
+        // existing dids lose the capabilities of a removed type
+        assert_ok!(DidModule::remove_did_type(Origin::root(), b"7".to_vec()));
+        assert!(!DidModule::has_capability(&did, Capability::PublishAds));
+
+        // the creator gets the deposit back
+        assert_ok!(DidModule::deactivate(Origin::signed(4)));
+        assert_eq!(DidModule::type_deposit(&user_key), None);
+        assert_eq!(Balances::reserved_balance(&2), 1000);
+    });
+}
+
+#[test]
+fn should_not_pass_did_types() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+
+        assert_noop!(
+            DidModule::set_did_type(Origin::signed(1), b"7".to_vec(), agency(DidTypeCreators::Anyone, 0)),
+            DispatchError::BadOrigin
+        );
+        let too_many = DidTypeCreators::Accounts((0..=MAX_DID_TYPE_CREATORS as u64).collect());
+        let mut unnamed = agency(DidTypeCreators::Anyone, 0);
+        unnamed.name.clear();
+        for (did_type, info) in vec![
+            (b"".to_vec(), agency(DidTypeCreators::Anyone, 0)),
+            (vec![b'7'; MAX_DID_TYPE_LENGTH + 1], agency(DidTypeCreators::Anyone, 0)),
+            (b"7".to_vec(), unnamed),
+            (b"7".to_vec(), agency(too_many, 0)),
+        ] {
+            assert_noop!(
+                DidModule::set_did_type(Origin::root(), did_type, info),
+                Error::<Test>::InvalidType
+            );
+        }
+        assert_noop!(
+            DidModule::remove_did_type(Origin::root(), b"7".to_vec()),
+            Error::<Test>::UnknownDidType
+        );
+
+        let pubkey = b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec();
+        assert_noop!(
+            DidModule::create(Origin::signed(1), pubkey.clone(), 4u64, b"7".to_vec(), first, None, None),
+            Error::<Test>::UnknownDidType
+        );
+
+        assert_ok!(DidModule::set_did_type(
+            Origin::root(),
+            b"7".to_vec(),
+            agency(DidTypeCreators::Accounts(vec![2]), 100)
+        ));
+        assert_noop!(
+            DidModule::create(Origin::signed(1), pubkey.clone(), 4u64, b"7".to_vec(), first, None, None),
+            Error::<Test>::NotDidTypeCreator
+        );
+
+        // nothing is kept when the deposit can not be reserved
+        assert_ok!(DidModule::set_did_type(
+            Origin::root(),
+            b"7".to_vec(),
+            agency(DidTypeCreators::Anyone, 20000)
+        ));
+        assert_noop!(
+            DidModule::create(Origin::signed(1), pubkey, 4u64, b"7".to_vec(), first, None, None),
+            pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn should_pass_groups() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        let reserved = Balances::reserved_balance(&2);
+        assert_ok!(DidModule::create_group(Origin::signed(2), b"core".to_vec()));
+        assert_eq!(
+            DidModule::group(0),
+            Some(Group {
+                name: b"core".to_vec(),
+                owner: second,
+                member_count: 1,
+                invite_count: 0,
+                depositor: 2,
+                deposit: 10,
+            })
+        );
+        assert_eq!(Balances::reserved_balance(&2), reserved + 10);
+        assert_eq!(DidModule::group_by_name(b"core".to_vec()), Some(0));
+        assert_eq!(DidModule::group_member(0, &second), Some(GroupRole::Admin));
+
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 0, third, GroupRole::Member));
+        assert_eq!(DidModule::group_invite(0, &third), Some(GroupRole::Member));
+        assert_eq!(DidModule::group(0).unwrap().invite_count, 1);
+        assert_eq!(DidModule::invites_of(&third), vec![0]);
+        assert_ok!(DidModule::accept_invite(Origin::signed(3), 0));
+        assert_eq!(DidModule::group_invite(0, &third), None);
+        assert_eq!(DidModule::group(0).unwrap().invite_count, 0);
+        assert!(DidModule::invites_of(&third).is_empty());
+        assert_eq!(DidModule::groups_of(&third), vec![0]);
+
+        // admins invite and remove members
+        assert_ok!(DidModule::set_member_role(Origin::signed(2), 0, third, GroupRole::Admin));
+        assert_ok!(DidModule::invite_member(Origin::signed(3), 0, first, GroupRole::Member));
+        assert_ok!(DidModule::remove_member(Origin::signed(3), 0, first));
+        assert_eq!(DidModule::group_invite(0, &first), None);
+        assert_eq!(DidModule::group(0).unwrap().invite_count, 0);
+        assert_ok!(DidModule::invite_member(Origin::signed(3), 0, first, GroupRole::Member));
+        assert_ok!(DidModule::accept_invite(Origin::signed(1), 0));
+        assert_eq!(DidModule::group_members(0).len(), 3);
+        assert_eq!(DidModule::group(0).unwrap().member_count, 3);
+
+        // members may leave
+        assert_ok!(DidModule::remove_member(Origin::signed(1), 0, first));
+        assert!(DidModule::groups_of(&first).is_empty());
+
+        // a deactivated did leaves its groups and its invitations are withdrawn
+        assert_ok!(DidModule::create_group(Origin::signed(2), b"lab".to_vec()));
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 1, third, GroupRole::Member));
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 1, first, GroupRole::Member));
+        assert_ok!(DidModule::deactivate(Origin::signed(3)));
+        assert_eq!(DidModule::group_member(0, &third), None);
+        assert_eq!(DidModule::group(0).unwrap().member_count, 1);
+        assert_eq!(DidModule::group_invite(1, &third), None);
+        assert_eq!(DidModule::group(1).unwrap().invite_count, 1);
+        assert!(DidModule::invites_of(&third).is_empty());
+
+        // dissolving a group withdraws its invitations
+        assert_ok!(DidModule::dissolve_group(Origin::signed(2), 1));
+        assert!(DidModule::invites_of(&first).is_empty());
+
+        assert_ok!(DidModule::dissolve_group(Origin::signed(2), 0));
+        assert_eq!(DidModule::group(0), None);
+        assert_eq!(Balances::reserved_balance(&2), reserved);
+        assert_eq!(DidModule::group_by_name(b"core".to_vec()), None);
+        assert!(DidModule::groups_of(&second).is_empty());
+
+        // the name is free again
+        assert_ok!(DidModule::create_group(Origin::signed(2), b"core".to_vec()));
+        assert_eq!(DidModule::group_by_name(b"core".to_vec()), Some(2));
+    });
+}
+
+#[test]
+fn should_not_pass_groups() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        assert_noop!(
+            DidModule::create_group(Origin::signed(3), b"core".to_vec()),
+            Error::<Test>::NotEligible
+        );
+        assert_noop!(
+            DidModule::create_group(Origin::signed(2), b"".to_vec()),
+            Error::<Test>::InvalidGroupName
+        );
+        assert_noop!(
+            DidModule::create_group(Origin::signed(2), vec![b'a'; MAX_GROUP_NAME_LENGTH + 1]),
+            Error::<Test>::InvalidGroupName
+        );
+        assert_ok!(DidModule::create_group(Origin::signed(2), b"core".to_vec()));
+        assert_noop!(
+            DidModule::create_group(Origin::signed(2), b"core".to_vec()),
+            Error::<Test>::GroupNameTaken
+        );
+        assert_ok!(DidModule::create_group(Origin::signed(2), b"lab".to_vec()));
+        assert_noop!(
+            DidModule::create_group(Origin::signed(2), b"team".to_vec()),
+            Error::<Test>::TooManyGroups
+        );
+
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 9, third, GroupRole::Member),
+            Error::<Test>::GroupNotExists
+        );
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(3), 0, first, GroupRole::Member),
+            Error::<Test>::NotGroupAdmin
+        );
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 0, H256::zero(), GroupRole::Member),
+            Error::<Test>::DidNotExists
+        );
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 0, second, GroupRole::Member),
+            Error::<Test>::AlreadyGroupMember
+        );
+        assert_noop!(
+            DidModule::accept_invite(Origin::signed(3), 0),
+            Error::<Test>::InviteNotExists
+        );
+
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 0, third, GroupRole::Member));
+        assert_ok!(DidModule::accept_invite(Origin::signed(3), 0));
+
+        // only the owner grants the admin role or dissolves the group
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(3), 0, first, GroupRole::Admin),
+            Error::<Test>::NotGroupOwner
+        );
+        assert_noop!(
+            DidModule::set_member_role(Origin::signed(3), 0, third, GroupRole::Admin),
+            Error::<Test>::NotGroupOwner
+        );
+        assert_noop!(
+            DidModule::dissolve_group(Origin::signed(3), 0),
+            Error::<Test>::NotGroupOwner
+        );
+
+        // the owner stays in its group
+        assert_noop!(
+            DidModule::set_member_role(Origin::signed(2), 0, second, GroupRole::Member),
+            Error::<Test>::IsGroupOwner
+        );
+        assert_noop!(
+            DidModule::remove_member(Origin::signed(3), 0, second),
+            Error::<Test>::IsGroupOwner
+        );
+        assert_noop!(
+            DidModule::remove_member(Origin::signed(3), 0, first),
+            Error::<Test>::NotGroupMember
+        );
+        assert_noop!(
+            DidModule::deactivate(Origin::signed(2)),
+            Error::<Test>::OwnsGroups
+        );
+
+        assert_ok!(DidModule::create(
+            Origin::signed(2),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            b"1".to_vec(),
+            first,
+            None,
+            None
+        ));
+        let (fourth, _) = DidModule::identity(&4).unwrap();
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 0, first, GroupRole::Member));
+
+        // a pending invitation holds the last place
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 0, fourth, GroupRole::Member),
+            Error::<Test>::GroupFull
+        );
+        assert_ok!(DidModule::invite_member(Origin::signed(2), 0, first, GroupRole::Admin));
+        assert_eq!(DidModule::group(0).unwrap().invite_count, 1);
+        assert_ok!(DidModule::accept_invite(Origin::signed(1), 0));
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 0, fourth, GroupRole::Member),
+            Error::<Test>::GroupFull
+        );
+
+        // invitations to a did are bounded like its groups
+        <DidInvites<Test>>::insert(&fourth, vec![7, 8]);
+        assert_noop!(
+            DidModule::invite_member(Origin::signed(2), 1, fourth, GroupRole::Member),
+            Error::<Test>::TooManyInvites
+        );
+    });
+}
+
+fn genesis_did(pubkey: &[u8], address: u64, superior: H256, locked: u64) -> GenesisDid<u64, H256, u64> {
+    GenesisDid {
+        pubkey: pubkey.to_vec(),
+        address,
+        did_type: b"1".to_vec(),
+        superior,
+        social_account: None,
+        locked,
+    }
+}
+
+#[test]
+fn should_import_genesis_dids() {
+    let key_of = |pubkey: &[u8]| BlakeTwo256::hash(&DidModule::generate_did(pubkey, b"1"));
+    let first = key_of(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f");
+    let second = key_of(b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+    let third = key_of(b"0x5e9c79234b5e55348fc60f38b28c2cc60d8bb4bd2862eae2179a05ec39e62658");
+    let social_hash = BlakeTwo256::hash(b"twitter:third");
+
+    let mut with_social = genesis_did(
+        b"0x5e9c79234b5e55348fc60f38b28c2cc60d8bb4bd2862eae2179a05ec39e62658",
+        3,
+        second,
+        0,
+    );
+    with_social.social_account = Some(social_hash);
+
+    new_test_ext_with_dids(vec![
+        genesis_did(b"0x22df4b685df33f070ae6e5ee27f745de078adff099d3a803ec67afe1168acd4f", 1, H256::zero(), 0),
+        genesis_did(b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d", 2, first, 1000),
+        with_social,
+    ])
+    .execute_with(|| {
+        assert_eq!(DidModule::all_did_count(), 3);
+        assert_eq!(DidModule::identity(&2).map(|(key, _)| key), Some(second));
+        assert_eq!(DidModule::identity_of(&third), Some(3));
+        let idx = DidModule::index_by_key(&third);
+        assert_eq!(DidModule::key_by_index(BlakeTwo256::hash(&idx)), third);
+
+        // the referral tree
+        assert_eq!(DidModule::children(first, 0), vec![second]);
+        assert_eq!(DidModule::children(second, 0), vec![third]);
+        assert_eq!(DidModule::metadata(&first).subordinate_count, 0);
+        assert_eq!(DidModule::metadata(&second).subordinate_count, 1);
+
+        assert_eq!(Balances::reserved_balance(&2), 1000);
+        let locked_records = DidModule::metadata(&second).locked_records.unwrap();
+        assert_eq!(locked_records.locked_funds(), 1000);
+        assert!(locked_records.max_quota > 0);
+        assert_eq!(DidModule::social_account(social_hash), third);
+        assert!(DidModule::social_attestation(social_hash).is_some());
+
+        // imported dids behave like created ones
+        assert_ok!(DidModule::create(
+            Origin::signed(2),
+            b"0x306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20".to_vec(),
+            4u64,
+            b"1".to_vec(),
+            second,
+            None,
+            None
+        ));
+        assert_eq!(DidModule::all_did_count(), 4);
+        assert_eq!(DidModule::metadata(&second).subordinate_count, 2);
+    });
+}
+
+#[test]
+#[should_panic(expected = "superior not listed before its subordinates")]
+fn should_not_import_orphan_genesis_dids() {
+    let superior = BlakeTwo256::hash(b"unknown");
+    new_test_ext_with_dids(vec![genesis_did(
+        b"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+        2,
+        superior,
+        0,
+    )]);
+}
+
+#[test]
+#[should_panic(expected = "NotLockFunds")]
+fn should_not_import_genesis_dids_below_unlocked_superiors() {
+    let first = BlakeTwo256::hash(&DidModule::generate_did(b"first", b"1"));
+    let second = BlakeTwo256::hash(&DidModule::generate_did(b"second", b"1"));
+    new_test_ext_with_dids(vec![
+        genesis_did(b"first", 1, H256::zero(), 0),
+        genesis_did(b"second", 2, first, 0),
+        genesis_did(b"third", 3, second, 0),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "ExceedsMaxQuota")]
+fn should_not_import_genesis_dids_beyond_the_quota() {
+    let first = BlakeTwo256::hash(&DidModule::generate_did(b"first", b"1"));
+    let second = BlakeTwo256::hash(&DidModule::generate_did(b"second", b"1"));
+    let mut dids = vec![
+        genesis_did(b"first", 1, H256::zero(), 0),
+        // a quota of 10 subordinates
+        genesis_did(b"second", 2, first, 1),
+    ];
+    for i in 0..11u64 {
+        dids.push(genesis_did(&i.encode(), 10 + i, second, 0));
+    }
+    new_test_ext_with_dids(dids);
+}
+
+type LegacyLock = (u64, u64, u64, u64, u64);
+
+/// A `Metadata` record in the layout of `Releases::V0LegacyPrefix`, stored under `Did`.
+fn put_legacy_metadata(
+    did: &[u8],
+    superior: H256,
+    lock: Option<LegacyLock>,
+    is_partner: bool,
+    eth: &[u8],
+) -> H256 {
+    let user_key = BlakeTwo256::hash(did);
+    put_storage_value(
+        b"Did",
+        b"Metadata",
+        &Twox64Concat::hash(&user_key.encode()),
+        (
+            1u64,
+            superior,
+            1u64,
+            did.to_vec(),
+            lock,
+            None::<(u64, u64)>,
+            is_partner,
+            None::<H256>,
+            0u64,
+            None::<Vec<u8>>,
+            (Vec::<u8>::new(), eth.to_vec(), Vec::<u8>::new()),
+        ),
+    );
+    user_key
+}
+
+/// A `Metadata` record in the layout of `Releases::V1Donate`.
+fn put_donate_metadata(did: &[u8], superior: H256, donate: Option<u64>) -> H256 {
+    let user_key = BlakeTwo256::hash(did);
+    put_storage_value(
+        b"DidModule",
+        b"Metadata",
+        &Twox64Concat::hash(&user_key.encode()),
+        (
+            1u64,
+            superior,
+            1u64,
+            did.to_vec(),
+            None::<LegacyLock>,
+            None::<(u64, u64)>,
+            donate,
+            None::<H256>,
+            0u64,
+            None::<Vec<u8>>,
+            (Vec::<u8>::new(), Vec::<u8>::new(), Vec::<u8>::new()),
+        ),
+    );
+    user_key
+}
+
+const LEGACY_FLAGS: [&[u8]; 5] = [
+    b"UpgradedToKeySet",
+    b"UpgradedToExternalAddresses",
+    b"UpgradedToLockPositions",
+    b"UpgradedToRewardSchedule",
+    b"UpgradedToChildIndex",
+];
+
+#[test]
+fn should_migrate_from_legacy_prefix() {
+    new_test_ext().execute_with(|| {
+        <StorageVersion>::kill();
+        <ReferralSchedule<Test>>::kill();
+
+        let eth = b"0x52908400098527886E0F7030069857D2E4169EE7";
+        let first = put_legacy_metadata(b"first", H256::zero(), None, false, eth);
+        let second = put_legacy_metadata(b"second", first, Some((0, 5, 1000, 30, 100)), true, b"");
+        let third = put_donate_metadata(b"third", second, Some(7));
+
+        let dids = migrations::pre_upgrade::<Test>().unwrap();
+        assert_eq!(dids, 3);
+        DidModule::on_runtime_upgrade();
+        assert_ok!(migrations::post_upgrade::<Test>(dids));
+
+        assert_eq!(DidModule::storage_version(), Some(Releases::LATEST));
+        assert_eq!(DidModule::metadata(first).donate, None);
+        assert_eq!(DidModule::metadata(second).donate, Some(25));
+        assert_eq!(DidModule::metadata(third).donate, Some(7));
+
+        let locked = DidModule::metadata(second).locked_records.unwrap();
+        assert_eq!(
+            locked.positions,
+            vec![LockPosition {
+                amount: 1000,
+                locked_time: 0,
+                locked_period: 5,
+            }]
+        );
+        assert_eq!((locked.rewards_ratio, locked.max_quota), (30, 100));
+
+        let normalized = check::ChainType::Eth.normalize_address(eth);
+        assert_eq!(
+            DidModule::metadata(first).external_addresses.get(check::ETH_CHAIN_ID),
+            Some(&vec![normalized.clone()])
+        );
+        assert_eq!(
+            DidModule::external_address_owner(check::ETH_CHAIN_ID.to_vec(), normalized),
+            Some(first)
+        );
+
+        assert_eq!(DidModule::children(first, 0), vec![second]);
+        assert_eq!(DidModule::children(second, 0), vec![third]);
+        assert_eq!(DidModule::child_count(&third), 0);
+        assert_eq!(DidModule::reward_schedule(), RewardSchedule::legacy(25));
+
+        // a second upgrade leaves everything as it is
+        let records: Vec<_> = [first, second, third].iter().map(DidModule::metadata).collect();
+        DidModule::on_runtime_upgrade();
+        assert_ok!(migrations::post_upgrade::<Test>(dids));
+        assert_eq!(
+            [first, second, third].iter().map(DidModule::metadata).collect::<Vec<_>>(),
+            records
+        );
+        assert_eq!(DidModule::children(first, 0), vec![second]);
+        assert_eq!(DidModule::child_count(&first), 1);
+    });
+}
+
+#[test]
+fn should_infer_version_from_legacy_flags() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        // a chain upgraded up to the reward schedule before versioning
+        <StorageVersion>::kill();
+        for flag in LEGACY_FLAGS[..4].iter() {
+            put_storage_value(b"DidModule", flag, &[], true);
+        }
+        <Subordinates<Test>>::remove_all();
+        <SubordinateTotal<Test>>::remove_all();
+        <ChildPositions<Test>>::remove_all();
+        let schedule = RewardSchedule::legacy(10);
+        <ReferralSchedule<Test>>::put(schedule.clone());
+        <DidTypes<Test>>::remove(b"4".to_vec());
+        let custom = agency(DidTypeCreators::Anyone, 0);
+        <DidTypes<Test>>::insert(b"1".to_vec(), custom.clone());
+
+        let dids = migrations::pre_upgrade::<Test>().unwrap();
+        assert_eq!(dids, 3);
+        DidModule::on_runtime_upgrade();
+        assert_ok!(migrations::post_upgrade::<Test>(dids));
+
+        assert_eq!(DidModule::children(first, 0), vec![second]);
+        assert_eq!(DidModule::children(second, 0), vec![third]);
+        assert_eq!(DidModule::reward_schedule(), schedule);
+        assert!(DidModule::did_type(b"4".to_vec()).unwrap().has(Capability::PublishAds));
+        assert_eq!(DidModule::did_type(b"1".to_vec()), Some(custom));
+        for flag in LEGACY_FLAGS.iter() {
+            assert_eq!(get_storage_value::<bool>(b"DidModule", flag, &[]), None);
+        }
+        assert_eq!(DidModule::storage_version(), Some(Releases::LATEST));
+    });
+}
+
+#[test]
+fn should_migrate_to_child_positions() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+        let (third, _) = DidModule::identity(&3).unwrap();
+
+        // a chain indexed before positions were kept
+        <StorageVersion>::put(Releases::V7DidTypes);
+        <ChildPositions<Test>>::remove_all();
+
+        let dids = migrations::pre_upgrade::<Test>().unwrap();
+        DidModule::on_runtime_upgrade();
+        assert_ok!(migrations::post_upgrade::<Test>(dids));
+        assert_eq!(DidModule::child_position(&second), Some((0, 0)));
+        assert_eq!(DidModule::child_position(&third), Some((0, 0)));
+
+        DidModule::remove_child(first, second);
+        assert!(DidModule::children(first, 0).is_empty());
+        assert_eq!(DidModule::child_position(&second), None);
+    });
+}
+
+#[test]
+fn should_not_migrate_latest_version() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DidModule::storage_version(), Some(Releases::LATEST));
+        prepare_dids_for_test();
+        let (first, _) = DidModule::identity(&1).unwrap();
+        let (second, _) = DidModule::identity(&2).unwrap();
+
+        // an index the migration would rebuild differently
+        DidModule::remove_child(first, second);
+        let record = DidModule::metadata(second);
+
+        let dids = migrations::pre_upgrade::<Test>().unwrap();
+        DidModule::on_runtime_upgrade();
+        assert!(DidModule::children(first, 0).is_empty());
+        assert_eq!(DidModule::metadata(second), record);
+        assert_eq!(
+            migrations::post_upgrade::<Test>(dids),
+            Err("subordinate index out of step with superiors")
+        );
     });
 }
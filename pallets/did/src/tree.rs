@@ -0,0 +1,107 @@
+use super::*;
+
+/// Number of subordinates kept in one page of `Subordinates`.
+pub const SUBORDINATE_PAGE_SIZE: u32 = 64;
+
+/// Most DIDs a single tree query walks or returns.
+pub const MAX_TREE_QUERY: u32 = 1024;
+
+impl<T: Config> Module<T> {
+    pub(crate) fn add_child(superior: T::Hash, child: T::Hash) {
+        let total = Self::child_count(&superior);
+        let page = total / SUBORDINATE_PAGE_SIZE;
+        <Subordinates<T>>::append(&superior, page, child);
+        <ChildPositions<T>>::insert(&child, (page, total % SUBORDINATE_PAGE_SIZE));
+        <SubordinateTotal<T>>::insert(&superior, total + 1);
+    }
+
+    /// Drop a child, the last child takes its place to keep the pages dense.
+    pub(crate) fn remove_child(superior: T::Hash, child: T::Hash) {
+        let (page, pos) = match <ChildPositions<T>>::take(&child) {
+            Some(position) => position,
+            None => return,
+        };
+        let total = Self::child_count(&superior);
+        if total == 0 {
+            return;
+        }
+
+        let last_page = (total - 1) / SUBORDINATE_PAGE_SIZE;
+        let mut tail = Self::subordinate_page(&superior, last_page);
+        let last = tail.pop();
+        if tail.is_empty() {
+            <Subordinates<T>>::remove(&superior, last_page);
+        } else {
+            <Subordinates<T>>::insert(&superior, last_page, tail);
+        }
+        if let Some(last) = last.filter(|last| *last != child) {
+            <Subordinates<T>>::mutate(&superior, page, |children| {
+                if let Some(slot) = children.get_mut(pos as usize) {
+                    *slot = last;
+                }
+            });
+            <ChildPositions<T>>::insert(&last, (page, pos));
+        }
+
+        if total == 1 {
+            <SubordinateTotal<T>>::remove(&superior);
+        } else {
+            <SubordinateTotal<T>>::insert(&superior, total - 1);
+        }
+    }
+
+    /// One page of the direct subordinates of a DID.
+    pub fn children(user_key: T::Hash, page: u32) -> Vec<T::Hash> {
+        Self::subordinate_page(&user_key, page)
+    }
+
+    /// Subordinates of a DID up to `depth` levels down with their level, breadth first.
+    ///
+    /// `start` and `limit` page through the result, a page holds at most `MAX_TREE_QUERY` DIDs.
+    pub fn descendants(user_key: T::Hash, depth: u32, start: u32, limit: u32) -> Vec<(T::Hash, u32)> {
+        let limit = limit.min(MAX_TREE_QUERY) as usize;
+
+        let mut found = Vec::new();
+        let mut walked: u32 = 0;
+        let mut level = sp_std::vec![user_key];
+        for current in 1..=depth {
+            let mut below = Vec::new();
+            for key in level {
+                let pages = (Self::child_count(&key) + SUBORDINATE_PAGE_SIZE - 1) / SUBORDINATE_PAGE_SIZE;
+                for page in 0..pages {
+                    for child in Self::subordinate_page(&key, page) {
+                        if found.len() >= limit {
+                            return found;
+                        }
+                        if walked >= start {
+                            found.push((child, current));
+                        }
+                        walked = walked.saturating_add(1);
+                        below.push(child);
+                    }
+                }
+            }
+            if below.is_empty() {
+                break;
+            }
+            level = below;
+        }
+
+        found
+    }
+
+    /// The superiors of a DID, nearest first.
+    pub fn ancestors(user_key: T::Hash) -> Vec<T::Hash> {
+        let mut ancestors = Vec::new();
+        let mut next = Self::metadata(&user_key).superior;
+        while <Metadata<T>>::contains_key(&next)
+            && next != user_key
+            && !ancestors.contains(&next)
+            && (ancestors.len() as u32) < MAX_TREE_QUERY
+        {
+            ancestors.push(next);
+            next = Self::metadata(&next).superior;
+        }
+        ancestors
+    }
+}
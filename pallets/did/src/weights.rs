@@ -0,0 +1,528 @@
+//! Weights for the did pallet.
+//!
+//! The benchmarks in `benchmarking.rs` measure every call at its worst case, regenerate this file
+//! on reference hardware with:
+//!
+//! ```text
+//! parami benchmark --chain=dev --execution=wasm --wasm-execution=compiled --pallet=did
+//!     --extrinsic='*' --steps=50 --repeat=20 --heap-pages=4096 --output=pallets/did/src/weights.rs
+//! ```
+//!
+//! Not generated yet, the execution times are estimates. The storage accesses follow the
+//! worst case each benchmark sets up, e.g. `rotate_key` moving the DID to a new account.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for the did pallet.
+pub trait WeightInfo {
+    fn create() -> Weight;
+    fn update() -> Weight;
+    fn deactivate(s: u32, c: u32, g: u32, i: u32) -> Weight;
+    fn add_key() -> Weight;
+    fn remove_key() -> Weight;
+    fn rotate_key() -> Weight;
+    fn add_service() -> Weight;
+    fn update_service() -> Weight;
+    fn remove_service() -> Weight;
+    fn transfer() -> Weight;
+    fn transfer_batch(n: u32) -> Weight;
+    fn lock(l: u32) -> Weight;
+    fn force_lock() -> Weight;
+    fn set_reward_schedule(l: u32) -> Weight;
+    fn set_rewards_curve() -> Weight;
+    fn set_did_type(c: u32) -> Weight;
+    fn remove_did_type() -> Weight;
+    fn unlock() -> Weight;
+    fn withdraw_unbonded() -> Weight;
+    fn add_external_address() -> Weight;
+    fn remove_external_address() -> Weight;
+    fn register_attestor() -> Weight;
+    fn remove_attestor() -> Weight;
+    fn bind_social_account() -> Weight;
+    fn rebind_social_account() -> Weight;
+    fn unbind_social_account() -> Weight;
+    fn dispute_social_account() -> Weight;
+    fn resolve_social_dispute() -> Weight;
+    fn set_recovery(g: u32) -> Weight;
+    fn remove_recovery() -> Weight;
+    fn vouch_recovery() -> Weight;
+    fn cancel_recovery() -> Weight;
+    fn claim_recovery() -> Weight;
+    fn create_group() -> Weight;
+    fn dissolve_group(m: u32, i: u32) -> Weight;
+    fn invite_member() -> Weight;
+    fn accept_invite() -> Weight;
+    fn remove_member() -> Weight;
+    fn set_member_role() -> Weight;
+    fn set_group_name() -> Weight;
+    fn judge() -> Weight;
+}
+
+/// Weights for the did pallet using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create() -> Weight {
+        (130_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn update() -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn deactivate(s: u32, c: u32, g: u32, i: u32) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((4_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add((12_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add((5_000_000 as Weight).saturating_mul(g as Weight))
+            .saturating_add((4_000_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(T::DbWeight::get().reads(19 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(T::DbWeight::get().reads((8 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(T::DbWeight::get().writes(22 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(T::DbWeight::get().writes((8 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(i as Weight)))
+    }
+    fn add_key() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_key() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn rotate_key() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn add_service() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn update_service() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_service() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn transfer() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_batch(n: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn lock(l: u32) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(l as Weight)))
+    }
+    fn force_lock() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_reward_schedule(l: u32) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((500_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_rewards_curve() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_did_type(c: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((100_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_did_type() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn unlock() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn withdraw_unbonded() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn add_external_address() -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn remove_external_address() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn register_attestor() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_attestor() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn bind_social_account() -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn rebind_social_account() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn unbind_social_account() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn dispute_social_account() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_social_dispute() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn set_recovery(g: u32) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(g as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_recovery() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn vouch_recovery() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn cancel_recovery() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn claim_recovery() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn create_group() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn dissolve_group(m: u32, i: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((6_000_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add((4_000_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(i as Weight)))
+    }
+    fn invite_member() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn accept_invite() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn remove_member() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_member_role() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_group_name() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn judge() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create() -> Weight {
+        (130_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn update() -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn deactivate(s: u32, c: u32, g: u32, i: u32) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((4_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add((12_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add((5_000_000 as Weight).saturating_mul(g as Weight))
+            .saturating_add((4_000_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(RocksDbWeight::get().reads(19 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((8 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(22 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((8 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(i as Weight)))
+    }
+    fn add_key() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_key() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn rotate_key() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn add_service() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn update_service() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_service() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn transfer() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_batch(n: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn lock(l: u32) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(l as Weight)))
+    }
+    fn force_lock() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_reward_schedule(l: u32) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((500_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_rewards_curve() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_did_type(c: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((100_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_did_type() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn unlock() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn withdraw_unbonded() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn add_external_address() -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn remove_external_address() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn register_attestor() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_attestor() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn bind_social_account() -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn rebind_social_account() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn unbind_social_account() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn dispute_social_account() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_social_dispute() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn set_recovery(g: u32) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(g as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(g as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_recovery() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn vouch_recovery() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn cancel_recovery() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn claim_recovery() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn create_group() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn dissolve_group(m: u32, i: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((6_000_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add((4_000_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(i as Weight)))
+    }
+    fn invite_member() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn accept_invite() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn remove_member() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_member_role() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_group_name() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn judge() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}
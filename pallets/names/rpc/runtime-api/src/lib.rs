@@ -0,0 +1,20 @@
+//! Runtime API definition for the names pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use names::NAME_SUFFIX;
+
+sp_api::decl_runtime_apis! {
+    pub trait NamesApi<Hash> where
+        Hash: Codec,
+    {
+        /// The user key of the DID holding a live name, with or without its suffix.
+        fn resolve(name: Vec<u8>) -> Option<Hash>;
+
+        /// Every name held by a DID without its suffix, expired ones included.
+        fn names_of(user_key: Hash) -> Vec<Vec<u8>>;
+    }
+}
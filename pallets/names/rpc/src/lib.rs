@@ -0,0 +1,84 @@
+//! RPC interface for the names pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use names_rpc_runtime_api::NamesApi as NamesRuntimeApi;
+use names_rpc_runtime_api::NAME_SUFFIX;
+
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait NamesApi<BlockHash, Hash> {
+    /// The user key of the DID a name such as `alice.ad3` resolves to.
+    #[rpc(name = "names_resolve")]
+    fn resolve(&self, name: String, at: Option<BlockHash>) -> Result<Option<Hash>>;
+
+    /// Every name a DID holds with its suffix, expired ones included.
+    #[rpc(name = "names_of")]
+    fn names_of(&self, user_key: Hash, at: Option<BlockHash>) -> Result<Vec<String>>;
+}
+
+/// An implementation of names specific RPC methods.
+pub struct Names<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Names<C, B> {
+    /// Create new `Names` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Names {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, Hash> NamesApi<<Block as BlockT>::Hash, Hash> for Names<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: NamesRuntimeApi<Block, Hash>,
+    Hash: Codec,
+{
+    fn resolve(&self, name: String, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Hash>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        api.resolve(&at, name.into_bytes()).map_err(runtime_error)
+    }
+
+    fn names_of(&self, user_key: Hash, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<String>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let names = api.names_of(&at, user_key).map_err(runtime_error)?;
+
+        Ok(names
+            .into_iter()
+            .map(|mut name| {
+                name.extend_from_slice(NAME_SUFFIX);
+                String::from_utf8_lossy(&name).into_owned()
+            })
+            .collect())
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(err: E) -> Error {
+    Error {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime trapped".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
@@ -0,0 +1,144 @@
+//! Benchmarks for the names pallet, every call is measured at its worst case.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
+
+const SEED: u32 = 0;
+
+fn signed<T: Config>(who: &T::AccountId) -> T::Origin {
+    RawOrigin::Signed(who.clone()).into()
+}
+
+/// A funded account with a DID of its own.
+fn new_did<T: Config>(name: &'static str, index: u32) -> Result<(T::AccountId, T::Hash), &'static str> {
+    let who: T::AccountId = account(name, index, SEED);
+    let balance = T::NameDeposit::get()
+        .max(100u32.into())
+        .saturating_mul(1_000_000u32.into());
+    <pallet_balances::Module<T> as Currency<_>>::make_free_balance_be(&who, balance);
+
+    // the genesis account places it at the top of the tree
+    <did::Module<T>>::create(
+        signed::<T>(&<did::Module<T>>::genesis_account()),
+        who.encode(),
+        who.clone(),
+        b"1".to_vec(),
+        Default::default(),
+        None,
+        None,
+    )?;
+    let (user_key, _) = <did::Module<T>>::identity(&who).ok_or("did not created")?;
+    Ok((who, user_key))
+}
+
+/// A distinct name of the longest length, the index in base 26 padded with zeros.
+fn name<T: Config>(index: u32) -> Vec<u8> {
+    let mut name = Vec::new();
+    let mut rest = index;
+    loop {
+        name.push(b'a' + (rest % 26) as u8);
+        rest /= 26;
+        if rest == 0 {
+            break;
+        }
+    }
+    name.resize(T::MaxNameLength::get().max(name.len() as u32) as usize, b'0');
+    name
+}
+
+/// Register names `from..from + count` for the account's DID.
+fn fill_names<T: Config>(who: &T::AccountId, from: u32, count: u32) -> Result<(), &'static str> {
+    for i in from..from.saturating_add(count) {
+        Module::<T>::register(signed::<T>(who), name::<T>(i))?;
+    }
+    Ok(())
+}
+
+fn expire<T: Config>() {
+    let now = <pallet_timestamp::Module<T>>::get();
+    <pallet_timestamp::Module<T>>::set_timestamp(now.saturating_add(T::RegistrationPeriod::get()));
+}
+
+benchmarks! {
+    // takes over an expired name from a DID holding the most names
+    register {
+        let (holder, _) = new_did::<T>("holder", 0)?;
+        fill_names::<T>(&holder, 0, T::MaxNamesPerDid::get())?;
+        expire::<T>();
+
+        let (caller, user_key) = new_did::<T>("caller", 0)?;
+        fill_names::<T>(&caller, T::MaxNamesPerDid::get(), T::MaxNamesPerDid::get().saturating_sub(1))?;
+        let taken = name::<T>(0);
+    }: _(RawOrigin::Signed(caller), taken.clone())
+    verify {
+        assert_eq!(Module::<T>::resolve(taken), Some(user_key));
+    }
+
+    renew {
+        let (caller, user_key) = new_did::<T>("caller", 0)?;
+        fill_names::<T>(&caller, 0, 1)?;
+        expire::<T>();
+    }: _(RawOrigin::Signed(caller), name::<T>(0))
+    verify {
+        assert_eq!(Module::<T>::resolve(name::<T>(0)), Some(user_key));
+    }
+
+    // both DIDs hold the most names they may before the transfer
+    transfer_name {
+        let (caller, _) = new_did::<T>("caller", 0)?;
+        fill_names::<T>(&caller, 0, T::MaxNamesPerDid::get())?;
+        let (recipient, to) = new_did::<T>("recipient", 0)?;
+        fill_names::<T>(&recipient, T::MaxNamesPerDid::get(), T::MaxNamesPerDid::get().saturating_sub(1))?;
+    }: _(RawOrigin::Signed(caller), name::<T>(0), to)
+    verify {
+        assert_eq!(Module::<T>::resolve(name::<T>(0)), Some(to));
+    }
+
+    release {
+        let (caller, _) = new_did::<T>("caller", 0)?;
+        fill_names::<T>(&caller, 0, T::MaxNamesPerDid::get())?;
+    }: _(RawOrigin::Signed(caller), name::<T>(0))
+    verify {
+        assert_eq!(Module::<T>::resolve(name::<T>(0)), None);
+    }
+
+    transfer_to_name {
+        let (recipient, to) = new_did::<T>("recipient", 0)?;
+        fill_names::<T>(&recipient, 0, 1)?;
+        let (caller, _) = new_did::<T>("caller", 0)?;
+        let value = T::NameDeposit::get().max(100u32.into());
+    }: _(RawOrigin::Signed(caller), name::<T>(0), value, Vec::new())
+    verify {
+        assert_eq!(Module::<T>::resolve(name::<T>(0)), Some(to));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_register::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_renew::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_name::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_release::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_to_name::<Test>());
+        });
+    }
+}
@@ -0,0 +1,269 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Human-readable names, e.g. `alice.ad3`, registered for a DID.
+//!
+//! A name is held for `RegistrationPeriod` against a deposit and renewed by its owner. Once it
+//! expires anyone may register it again, the previous depositor gets the deposit back.
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode};
+use did::TransferKind;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure,
+    traits::{BalanceStatus, Get, ReservableCurrency},
+    transactional,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+    traits::{CheckedAdd, Zero},
+    RuntimeDebug,
+};
+use sp_std::vec::Vec;
+
+/// Suffix of every name, it may be left out wherever a name is taken.
+pub const NAME_SUFFIX: &[u8] = b".ad3";
+
+pub trait Config: did::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// The amount reserved for every registered name.
+    type NameDeposit: Get<Self::Balance>;
+    /// How long a registration or renewal holds a name.
+    type RegistrationPeriod: Get<Self::Moment>;
+    /// The minimum length of a name without its suffix.
+    type MinNameLength: Get<u32>;
+    /// The maximum length of a name without its suffix.
+    type MaxNameLength: Get<u32>;
+    /// The maximum number of names one DID may hold.
+    type MaxNamesPerDid: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct NameRecord<AccountId, Hash, Balance, Moment> {
+    pub owner: Hash,
+    pub expires: Moment,
+    depositor: AccountId,
+    deposit: Balance,
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// name is too short or long, or not made of lowercase letters, digits and inner hyphens
+        InvalidName,
+        /// name is held by another did
+        NameTaken,
+        /// name is not registered or has expired
+        NameNotExists,
+        /// you are not the owner of the name
+        NotOwner,
+        /// too many names are held by the did
+        TooManyNames,
+        /// receiving did does not exist
+        RecipientNotExists,
+        /// overflow
+        Overflow,
+        /// the deposit of the name could not be moved in full
+        DepositNotMoved,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as NamesModule {
+        /// Registrations by name without its suffix, expired ones stay until taken or released.
+        pub Names get(fn name_record): map hasher(blake2_128_concat) Vec<u8> => Option<NameRecord<T::AccountId, T::Hash, T::Balance, T::Moment>>;
+        pub NamesOf get(fn names_of): map hasher(twox_64_concat) T::Hash => Vec<Vec<u8>>;
+    }
+}
+
+decl_event! {
+  pub enum Event<T>
+  where
+    <T as frame_system::Config>::Hash,
+    <T as pallet_timestamp::Config>::Moment,
+    {
+        Registered(Hash, Vec<u8>, Moment),
+        Renewed(Hash, Vec<u8>, Moment),
+        NameTransferred(Vec<u8>, Hash, Hash),
+        Released(Hash, Vec<u8>),
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Register a free or expired name for the sender's DID, names of deactivated DIDs are
+        /// free again.
+        #[weight = T::WeightInfo::register()]
+        pub fn register(origin, name: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let name = Self::normalize(name)?;
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            let previous = Self::name_record(&name);
+            if let Some(record) = previous.as_ref() {
+                ensure!(!Self::is_live(record, now), Error::<T>::NameTaken);
+            }
+
+            // the DID may take back its own expired name
+            let mut names = Self::names_of(&user_key);
+            names.retain(|n| *n != name);
+            ensure!((names.len() as u32) < T::MaxNamesPerDid::get(), Error::<T>::TooManyNames);
+            let expires = now.checked_add(&T::RegistrationPeriod::get()).ok_or(Error::<T>::Overflow)?;
+
+            let deposit = T::NameDeposit::get();
+            <pallet_balances::Module<T>>::reserve(&sender, deposit)?;
+
+            if let Some(record) = previous {
+                <pallet_balances::Module<T>>::unreserve(&record.depositor, record.deposit);
+                if record.owner != user_key {
+                    <NamesOf<T>>::mutate(&record.owner, |held| held.retain(|n| *n != name));
+                }
+            }
+
+            <Names<T>>::insert(&name, NameRecord {
+                owner: user_key,
+                expires,
+                depositor: sender,
+                deposit,
+            });
+            names.push(name.clone());
+            <NamesOf<T>>::insert(&user_key, names);
+
+            Self::deposit_event(RawEvent::Registered(user_key, name, expires));
+        }
+
+        /// Hold a name for another full period from now, an expired name can be renewed until
+        /// someone else registers it.
+        #[weight = T::WeightInfo::renew()]
+        pub fn renew(origin, name: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let name = Self::normalize(name)?;
+            let mut record = Self::name_record(&name).ok_or(Error::<T>::NameNotExists)?;
+            ensure!(record.owner == user_key, Error::<T>::NotOwner);
+
+            let now = <pallet_timestamp::Module<T>>::get();
+            record.expires = now.checked_add(&T::RegistrationPeriod::get()).ok_or(Error::<T>::Overflow)?;
+            let expires = record.expires;
+            <Names<T>>::insert(&name, record);
+
+            Self::deposit_event(RawEvent::Renewed(user_key, name, expires));
+        }
+
+        /// Hand a name to another DID, its deposit moves along.
+        #[weight = T::WeightInfo::transfer_name()]
+        #[transactional]
+        pub fn transfer_name(origin, name: Vec<u8>, to: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let name = Self::normalize(name)?;
+            let mut record = Self::live_record(&name).ok_or(Error::<T>::NameNotExists)?;
+            ensure!(record.owner == user_key, Error::<T>::NotOwner);
+
+            ensure!(to != user_key, <did::Error<T>>::SentToSelf);
+            let to_account = <did::Module<T>>::identity_of(&to).ok_or(Error::<T>::RecipientNotExists)?;
+            let mut names = Self::names_of(&to);
+            ensure!((names.len() as u32) < T::MaxNamesPerDid::get(), Error::<T>::TooManyNames);
+
+            let missing = <pallet_balances::Module<T> as ReservableCurrency<_>>::repatriate_reserved(
+                &record.depositor,
+                &to_account,
+                record.deposit,
+                BalanceStatus::Reserved,
+            )?;
+            ensure!(missing.is_zero(), Error::<T>::DepositNotMoved);
+
+            <NamesOf<T>>::mutate(&user_key, |held| held.retain(|n| *n != name));
+            names.push(name.clone());
+            <NamesOf<T>>::insert(&to, names);
+
+            record.owner = to;
+            record.depositor = to_account;
+            <Names<T>>::insert(&name, record);
+
+            Self::deposit_event(RawEvent::NameTransferred(name, user_key, to));
+        }
+
+        /// Give up a name and get its deposit back.
+        #[weight = T::WeightInfo::release()]
+        pub fn release(origin, name: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (user_key, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let name = Self::normalize(name)?;
+            let record = Self::name_record(&name).ok_or(Error::<T>::NameNotExists)?;
+            ensure!(record.owner == user_key, Error::<T>::NotOwner);
+
+            <Names<T>>::remove(&name);
+            <NamesOf<T>>::mutate(&user_key, |held| held.retain(|n| *n != name));
+            <pallet_balances::Module<T>>::unreserve(&record.depositor, record.deposit);
+
+            Self::deposit_event(RawEvent::Released(user_key, name));
+        }
+
+        /// `did::transfer` to the DID a name resolves to.
+        #[weight = T::WeightInfo::transfer_to_name()]
+        pub fn transfer_to_name(origin, name: Vec<u8>, value: T::Balance, memo: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+
+            let (from_user, _) = <did::Module<T>>::identity(&sender).ok_or(<did::Error<T>>::DidNotExists)?;
+            let to_user = Self::resolve(name).ok_or(Error::<T>::NameNotExists)?;
+            <did::Module<T>>::transfer_by_did(from_user, to_user, value, TransferKind::Plain, memo)?;
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Strip the suffix and check what is left is a valid name.
+    fn normalize(mut name: Vec<u8>) -> Result<Vec<u8>, Error<T>> {
+        if name.ends_with(NAME_SUFFIX) {
+            name.truncate(name.len() - NAME_SUFFIX.len());
+        }
+
+        let len = name.len() as u32;
+        ensure!(
+            len >= T::MinNameLength::get() && len <= T::MaxNameLength::get(),
+            Error::<T>::InvalidName
+        );
+        ensure!(
+            name.iter().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-'),
+            Error::<T>::InvalidName
+        );
+        ensure!(
+            name.first() != Some(&b'-') && name.last() != Some(&b'-'),
+            Error::<T>::InvalidName
+        );
+        Ok(name)
+    }
+
+    /// Whether a registration still holds its name, it lapses on expiry or when its DID is
+    /// deactivated.
+    fn is_live(record: &NameRecord<T::AccountId, T::Hash, T::Balance, T::Moment>, now: T::Moment) -> bool {
+        record.expires > now && !<did::Tombstones<T>>::contains_key(&record.owner)
+    }
+
+    fn live_record(name: &[u8]) -> Option<NameRecord<T::AccountId, T::Hash, T::Balance, T::Moment>> {
+        let now = <pallet_timestamp::Module<T>>::get();
+        Self::name_record(name).filter(|record| Self::is_live(record, now))
+    }
+
+    /// The user key of the DID holding a name, with or without its suffix.
+    pub fn resolve(name: Vec<u8>) -> Option<T::Hash> {
+        let name = Self::normalize(name).ok()?;
+        Self::live_record(&name).map(|record| record.owner)
+    }
+}
@@ -0,0 +1,354 @@
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{assert_noop, assert_ok, construct_runtime, parameter_types};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, u32, ()>;
+
+mod names {
+    pub use super::super::*;
+}
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage},
+        DidModule: did::{Module, Call, Storage, Event<T>},
+        NamesModule: names::{Module, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+  pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = frame_system::Module<Test>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const MaxKeys: u32 = 3;
+  pub const MaxKeyHistory: u32 = 4;
+  pub const ServiceDeposit: u64 = 10;
+  pub const MaxServices: u32 = 2;
+  pub const MaxServiceTypeLength: u32 = 16;
+  pub const MaxServiceUrlLength: u32 = 32;
+  pub const MaxChains: u32 = 3;
+  pub const MaxAddressesPerChain: u32 = 2;
+  pub const DisputeDeposit: u64 = 100;
+  pub const MaxGuardians: u32 = 3;
+  pub const RecoveryDelay: u64 = 10;
+  pub const MaxLockPositions: u32 = 3;
+  pub const UnbondingPeriod: u64 = 10;
+  pub const MaxUnbondingChunks: u32 = 2;
+  pub const MaxUnlockHistory: u32 = 3;
+  pub const MaxRewardLevels: u32 = 3;
+  pub const MaxBatchTransfers: u32 = 3;
+  pub const FeelessEra: u64 = 10;
+  pub const FeelessLockUnit: u64 = 500;
+  pub const GroupDeposit: u64 = 10;
+  pub const MaxGroupMembers: u32 = 3;
+  pub const MaxGroupsPerDid: u32 = 2;
+}
+
+impl did::Config for Test {
+    type Event = Event;
+    type MaxKeys = MaxKeys;
+    type MaxKeyHistory = MaxKeyHistory;
+    type ServiceDeposit = ServiceDeposit;
+    type MaxServices = MaxServices;
+    type MaxServiceTypeLength = MaxServiceTypeLength;
+    type MaxServiceUrlLength = MaxServiceUrlLength;
+    type MaxChains = MaxChains;
+    type MaxAddressesPerChain = MaxAddressesPerChain;
+    type DisputeDeposit = DisputeDeposit;
+    type MaxGuardians = MaxGuardians;
+    type RecoveryDelay = RecoveryDelay;
+    type MaxLockPositions = MaxLockPositions;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxUnbondingChunks = MaxUnbondingChunks;
+    type MaxUnlockHistory = MaxUnlockHistory;
+    type RewardOrigin = frame_system::EnsureRoot<u64>;
+    type DidTypeOrigin = frame_system::EnsureRoot<u64>;
+    type MaxRewardLevels = MaxRewardLevels;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type FeelessCalls = ();
+    type FeelessEra = FeelessEra;
+    type FeelessLockUnit = FeelessLockUnit;
+    type GroupDeposit = GroupDeposit;
+    type MaxGroupMembers = MaxGroupMembers;
+    type MaxGroupsPerDid = MaxGroupsPerDid;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const NameDeposit: u64 = 10;
+  pub const RegistrationPeriod: u64 = 100;
+  pub const MinNameLength: u32 = 3;
+  pub const MaxNameLength: u32 = 8;
+  pub const MaxNamesPerDid: u32 = 2;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type NameDeposit = NameDeposit;
+    type RegistrationPeriod = RegistrationPeriod;
+    type MinNameLength = MinNameLength;
+    type MaxNameLength = MaxNameLength;
+    type MaxNamesPerDid = MaxNamesPerDid;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(1, 10000), (2, 10000), (3, 10000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    did::GenesisConfig::<Test> {
+        genesis_account: 1u64,
+        min_deposit: 10,
+        base_quota: 250,
+        fee_to_previous: 25,
+        harsh_salt: b"parami did".to_vec(),
+        harsh_length: 6,
+        dids: vec![],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    t.into()
+}
+
+fn prepare_dids_for_test() -> (H256, H256, H256) {
+    for account in 1..=3u64 {
+        assert_ok!(DidModule::create(
+            Origin::signed(1),
+            [account as u8; 32].to_vec(),
+            account,
+            b"1".to_vec(),
+            H256::zero(),
+            None,
+            None
+        ));
+    }
+
+    (
+        DidModule::identity(&1).unwrap().0,
+        DidModule::identity(&2).unwrap().0,
+        DidModule::identity(&3).unwrap().0,
+    )
+}
+
+#[test]
+fn should_pass_register() {
+    new_test_ext().execute_with(|| {
+        let (first, second, _) = prepare_dids_for_test();
+        Timestamp::set_timestamp(10);
+
+        assert_ok!(NamesModule::register(Origin::signed(1), b"alice.ad3".to_vec()));
+        assert_ok!(NamesModule::register(Origin::signed(2), b"bob-2".to_vec()));
+        assert_eq!(Balances::reserved_balance(&1), 10);
+        assert_eq!(NamesModule::resolve(b"alice".to_vec()), Some(first));
+        assert_eq!(NamesModule::resolve(b"bob-2.ad3".to_vec()), Some(second));
+        assert_eq!(NamesModule::names_of(&first), vec![b"alice".to_vec()]);
+        assert_eq!(NamesModule::name_record(b"alice".to_vec()).unwrap().expires, 110);
+
+        // renewal holds the name for a full period from now
+        Timestamp::set_timestamp(60);
+        assert_ok!(NamesModule::renew(Origin::signed(1), b"alice".to_vec()));
+        assert_eq!(NamesModule::name_record(b"alice".to_vec()).unwrap().expires, 160);
+
+        // an expired name resolves to nothing and anyone may take it
+        Timestamp::set_timestamp(160);
+        assert_eq!(NamesModule::resolve(b"alice".to_vec()), None);
+        assert_ok!(NamesModule::register(Origin::signed(2), b"alice".to_vec()));
+        assert_eq!(NamesModule::resolve(b"alice".to_vec()), Some(second));
+        assert_eq!(Balances::reserved_balance(&1), 0);
+        assert_eq!(Balances::reserved_balance(&2), 20);
+        assert!(NamesModule::names_of(&first).is_empty());
+
+        assert_ok!(NamesModule::release(Origin::signed(2), b"bob-2".to_vec()));
+        assert_eq!(NamesModule::resolve(b"bob-2".to_vec()), None);
+        assert_eq!(NamesModule::names_of(&second), vec![b"alice".to_vec()]);
+        assert_eq!(Balances::reserved_balance(&2), 10);
+    });
+}
+
+#[test]
+fn should_pass_register_name_of_deactivated_did() {
+    new_test_ext().execute_with(|| {
+        let (_, second, third) = prepare_dids_for_test();
+
+        assert_ok!(NamesModule::register(Origin::signed(3), b"carol".to_vec()));
+        assert_ok!(DidModule::deactivate(Origin::signed(3)));
+        assert_eq!(NamesModule::resolve(b"carol".to_vec()), None);
+
+        // the name is free before it expires, the deposit goes back
+        assert_ok!(NamesModule::register(Origin::signed(2), b"carol".to_vec()));
+        assert_eq!(NamesModule::resolve(b"carol".to_vec()), Some(second));
+        assert!(NamesModule::names_of(&third).is_empty());
+        assert_eq!(Balances::reserved_balance(&3), 0);
+    });
+}
+
+#[test]
+fn should_not_pass_register() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_noop!(
+            NamesModule::register(Origin::signed(4), b"alice".to_vec()),
+            did::Error::<Test>::DidNotExists
+        );
+        for name in [&b"al"[..], b"alice-bob-c", b"Alice", b"-alice", b"ali.ce", b".ad3"].iter() {
+            assert_noop!(
+                NamesModule::register(Origin::signed(1), name.to_vec()),
+                Error::<Test>::InvalidName
+            );
+        }
+
+        assert_ok!(NamesModule::register(Origin::signed(1), b"alice".to_vec()));
+        assert_noop!(
+            NamesModule::register(Origin::signed(2), b"alice.ad3".to_vec()),
+            Error::<Test>::NameTaken
+        );
+        assert_noop!(
+            NamesModule::renew(Origin::signed(2), b"alice".to_vec()),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            NamesModule::release(Origin::signed(1), b"carol".to_vec()),
+            Error::<Test>::NameNotExists
+        );
+
+        assert_ok!(NamesModule::register(Origin::signed(1), b"carol".to_vec()));
+        assert_noop!(
+            NamesModule::register(Origin::signed(1), b"dave".to_vec()),
+            Error::<Test>::TooManyNames
+        );
+    });
+}
+
+#[test]
+fn should_pass_transfer_name() {
+    new_test_ext().execute_with(|| {
+        let (first, second, third) = prepare_dids_for_test();
+
+        assert_ok!(NamesModule::register(Origin::signed(1), b"alice".to_vec()));
+        assert_noop!(
+            NamesModule::transfer_name(Origin::signed(2), b"alice".to_vec(), third),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            NamesModule::transfer_name(Origin::signed(1), b"alice".to_vec(), first),
+            did::Error::<Test>::SentToSelf
+        );
+        assert_noop!(
+            NamesModule::transfer_name(Origin::signed(1), b"alice".to_vec(), H256::zero()),
+            Error::<Test>::RecipientNotExists
+        );
+
+        // the deposit follows the name
+        assert_ok!(NamesModule::transfer_name(Origin::signed(1), b"alice".to_vec(), second));
+        assert_eq!(NamesModule::resolve(b"alice".to_vec()), Some(second));
+        assert!(NamesModule::names_of(&first).is_empty());
+        assert_eq!(NamesModule::names_of(&second), vec![b"alice".to_vec()]);
+        assert_eq!(Balances::reserved_balance(&1), 0);
+        assert_eq!(Balances::reserved_balance(&2), 10);
+        assert_eq!(Balances::free_balance(&1), 9990);
+
+        // a deposit released behind the pallet's back is not made up for
+        Balances::unreserve(&2, 5);
+        assert_noop!(
+            NamesModule::transfer_name(Origin::signed(2), b"alice".to_vec(), third),
+            Error::<Test>::DepositNotMoved
+        );
+
+        Timestamp::set_timestamp(100);
+        assert_noop!(
+            NamesModule::transfer_name(Origin::signed(2), b"alice".to_vec(), third),
+            Error::<Test>::NameNotExists
+        );
+    });
+}
+
+#[test]
+fn should_pass_transfer_to_name() {
+    new_test_ext().execute_with(|| {
+        prepare_dids_for_test();
+
+        assert_ok!(NamesModule::register(Origin::signed(2), b"bob".to_vec()));
+        assert_ok!(NamesModule::transfer_to_name(Origin::signed(3), b"bob.ad3".to_vec(), 100, b"hi".to_vec()));
+        assert_eq!(Balances::free_balance(&3), 9900);
+        assert_eq!(Balances::free_balance(&2), 9990 + 100);
+
+        assert_noop!(
+            NamesModule::transfer_to_name(Origin::signed(3), b"carol".to_vec(), 100, Vec::new()),
+            Error::<Test>::NameNotExists
+        );
+    });
+}
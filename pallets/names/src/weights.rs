@@ -0,0 +1,86 @@
+//! Weights for the names pallet.
+//!
+//! The benchmarks in `benchmarking.rs` measure every call at its worst case, regenerate this file
+//! on reference hardware with:
+//!
+//! ```text
+//! parami benchmark --chain=dev --execution=wasm --wasm-execution=compiled --pallet=names
+//!     --extrinsic='*' --steps=50 --repeat=20 --heap-pages=4096 --output=pallets/names/src/weights.rs
+//! ```
+//!
+//! Not generated yet, the execution times are estimates. The storage accesses follow the
+//! worst case each benchmark sets up, e.g. `register` taking over an expired name.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for the names pallet.
+pub trait WeightInfo {
+    fn register() -> Weight;
+    fn renew() -> Weight;
+    fn transfer_name() -> Weight;
+    fn release() -> Weight;
+    fn transfer_to_name() -> Weight;
+}
+
+/// Weights for the names pallet using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn register() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn renew() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_name() -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn release() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_to_name() -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn register() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn renew() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_name() -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn release() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_to_name() -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+}
@@ -32,7 +32,7 @@
 
 use std::sync::Arc;
 
-use parami_node_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Index};
+use parami_node_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Index, Moment};
 use sc_client_api::AuxStore;
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_babe_rpc::BabeRpcHandler;
@@ -123,6 +123,9 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: did_rpc::DidRuntimeApi<Block, AccountId, Hash>,
+    C::Api: credentials_rpc::CredentialsRuntimeApi<Block, Hash, Moment>,
+    C::Api: names_rpc::NamesRuntimeApi<Block, Hash>,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
@@ -130,6 +133,9 @@ where
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
     B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+    use credentials_rpc::{Credentials, CredentialsApi};
+    use did_rpc::{Did, DidApi};
+    use names_rpc::{Names, NamesApi};
     use pallet_contracts_rpc::{Contracts, ContractsApi};
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
@@ -170,6 +176,9 @@ where
     io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
         client.clone(),
     )));
+    io.extend_with(DidApi::to_delegate(Did::new(client.clone())));
+    io.extend_with(CredentialsApi::to_delegate(Credentials::new(client.clone())));
+    io.extend_with(NamesApi::to_delegate(Names::new(client.clone())));
     io.extend_with(sc_consensus_babe_rpc::BabeApi::to_delegate(
         BabeRpcHandler::new(
             client.clone(),
@@ -84,6 +84,8 @@ pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 #[cfg(any(feature = "std", test))]
 pub use pallet_staking::StakerStatus;
+#[cfg(any(feature = "std", test))]
+pub use did::GenesisDid;
 
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -112,10 +114,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // and set impl_version to 0. If only runtime
     // implementation changes and behavior does not, then leave spec_version as
     // is and increment impl_version.
-    spec_version: 253,
+    spec_version: 254,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
 };
 
 /// Native version.
@@ -756,7 +758,9 @@ where
             frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+            did::FeelessOrCharge::<Runtime, _>::from(
+                pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+            ),
             // pallet_grandpa::ValidateEquivocationReport::<Runtime>::new(),
         );
         let raw_payload = SignedPayload::new(call, extra)
@@ -869,8 +873,101 @@ parameter_types! {
     pub const ChallengePeriod: BlockNumber = 7 * DAYS;
 }
 
+parameter_types! {
+    pub const MaxDidKeys: u32 = 16;
+    pub const MaxDidKeyHistory: u32 = 32;
+    pub const DidServiceDeposit: Balance = 1 * DOLLARS;
+    pub const MaxDidServices: u32 = 8;
+    pub const MaxDidServiceTypeLength: u32 = 64;
+    pub const MaxDidServiceUrlLength: u32 = 256;
+    pub const MaxDidChains: u32 = 16;
+    pub const MaxDidAddressesPerChain: u32 = 8;
+    pub const SocialDisputeDeposit: Balance = 10 * DOLLARS;
+    pub const MaxDidGuardians: u32 = 8;
+    pub const DidRecoveryDelay: Moment = (3 * DAYS) as Moment * MILLISECS_PER_BLOCK;
+    pub const MaxDidLockPositions: u32 = 16;
+    pub const DidUnbondingPeriod: Moment = (7 * DAYS) as Moment * MILLISECS_PER_BLOCK;
+    pub const MaxDidUnbondingChunks: u32 = 32;
+    pub const MaxDidUnlockHistory: u32 = 64;
+    pub const MaxDidRewardLevels: u32 = 8;
+    pub const MaxDidBatchTransfers: u32 = 128;
+    pub const DidFeelessEra: BlockNumber = 1 * DAYS;
+    pub const DidFeelessLockUnit: Balance = 100 * DOLLARS;
+    pub const DidGroupDeposit: Balance = 1 * DOLLARS;
+    pub const MaxDidGroupMembers: u32 = 256;
+    pub const MaxGroupsPerDid: u32 = 16;
+}
+
+/// Calls registered DIDs may make without fees, creating DIDs for new users.
+pub struct DidFeelessCalls;
+impl Filter<Call> for DidFeelessCalls {
+    fn filter(call: &Call) -> bool {
+        matches!(call, Call::Did(did::Call::create(..)))
+    }
+}
+
 impl did::Config for Runtime {
     type Event = Event;
+    type MaxKeys = MaxDidKeys;
+    type MaxKeyHistory = MaxDidKeyHistory;
+    type ServiceDeposit = DidServiceDeposit;
+    type MaxServices = MaxDidServices;
+    type MaxServiceTypeLength = MaxDidServiceTypeLength;
+    type MaxServiceUrlLength = MaxDidServiceUrlLength;
+    type MaxChains = MaxDidChains;
+    type MaxAddressesPerChain = MaxDidAddressesPerChain;
+    type DisputeDeposit = SocialDisputeDeposit;
+    type MaxGuardians = MaxDidGuardians;
+    type RecoveryDelay = DidRecoveryDelay;
+    type MaxLockPositions = MaxDidLockPositions;
+    type UnbondingPeriod = DidUnbondingPeriod;
+    type MaxUnbondingChunks = MaxDidUnbondingChunks;
+    type MaxUnlockHistory = MaxDidUnlockHistory;
+    type RewardOrigin = EnsureRootOrHalfCouncil;
+    type DidTypeOrigin = EnsureRootOrHalfCouncil;
+    type MaxRewardLevels = MaxDidRewardLevels;
+    type MaxBatchTransfers = MaxDidBatchTransfers;
+    type FeelessCalls = DidFeelessCalls;
+    type FeelessEra = DidFeelessEra;
+    type FeelessLockUnit = DidFeelessLockUnit;
+    type GroupDeposit = DidGroupDeposit;
+    type MaxGroupMembers = MaxDidGroupMembers;
+    type MaxGroupsPerDid = MaxGroupsPerDid;
+    type WeightInfo = did::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const CredentialDeposit: Balance = 1 * DOLLARS;
+    pub const CredentialStatusListLength: u32 = 131072;
+    pub const MaxCredentialTypeLength: u32 = 64;
+    pub const MaxCredentialsPerIssuer: u32 = 64;
+}
+
+impl credentials::Config for Runtime {
+    type Event = Event;
+    type CredentialDeposit = CredentialDeposit;
+    type StatusListLength = CredentialStatusListLength;
+    type MaxCredentialTypeLength = MaxCredentialTypeLength;
+    type MaxCredentialsPerIssuer = MaxCredentialsPerIssuer;
+    type WeightInfo = credentials::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const NameDeposit: Balance = 5 * DOLLARS;
+    pub const NameRegistrationPeriod: Moment = (365 * DAYS) as Moment * MILLISECS_PER_BLOCK;
+    pub const MinNameLength: u32 = 3;
+    pub const MaxNameLength: u32 = 32;
+    pub const MaxNamesPerDid: u32 = 8;
+}
+
+impl names::Config for Runtime {
+    type Event = Event;
+    type NameDeposit = NameDeposit;
+    type RegistrationPeriod = NameRegistrationPeriod;
+    type MinNameLength = MinNameLength;
+    type MaxNameLength = MaxNameLength;
+    type MaxNamesPerDid = MaxNamesPerDid;
+    type WeightInfo = names::weights::SubstrateWeight<Runtime>;
 }
 
 impl ads::Config for Runtime {
@@ -942,6 +1039,8 @@ construct_runtime!(
         Bounties: pallet_bounties::{Module, Call, Storage, Event<T>},
 
         Did: did::{Module, Storage, Call, Config<T>, Event<T>},
+        Credentials: credentials::{Module, Storage, Call, Event<T>},
+        Names: names::{Module, Storage, Call, Event<T>},
         Ads: ads::{Module, Storage, Call, Config<T>, Event<T>},
         Bridge: parami_bridge::{Module, Storage, Call, Config<T>, Event<T>},
         Nft: parami_nft::{Module, Storage, Call, Config<T>, Event<T>},
@@ -973,7 +1072,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    did::FeelessOrCharge<Runtime, pallet_transaction_payment::ChargeTransactionPayment<Runtime>>,
     //pallet_grandpa::ValidateEquivocationReport<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
@@ -1173,6 +1272,64 @@ impl_runtime_apis! {
         }
     }
 
+    impl did_rpc_runtime_api::DidApi<Block, AccountId, Hash> for Runtime {
+        fn resolve(did: Vec<u8>) -> Option<did::DidDocument> {
+            Did::resolve(did)
+        }
+
+        fn resolve_by_key(user_key: Hash) -> Option<did::DidDocument> {
+            Did::resolve_by_key(user_key)
+        }
+
+        fn lookup_address(chain_id: Vec<u8>, address: Vec<u8>) -> Option<Vec<u8>> {
+            Did::lookup_address(chain_id, address)
+        }
+
+        fn child_count(user_key: Hash) -> u32 {
+            Did::child_count(user_key)
+        }
+
+        fn children(user_key: Hash, page: u32) -> Vec<Hash> {
+            Did::children(user_key, page)
+        }
+
+        fn descendants(user_key: Hash, depth: u32, start: u32, limit: u32) -> Vec<(Hash, u32)> {
+            Did::descendants(user_key, depth, start, limit)
+        }
+
+        fn ancestors(user_key: Hash) -> Vec<Hash> {
+            Did::ancestors(user_key)
+        }
+
+        fn resolve_short_id(short_id: Vec<u8>) -> Option<did::ShortIdRecord<AccountId, Hash>> {
+            Did::resolve_short_id(short_id)
+        }
+    }
+
+    impl names_rpc_runtime_api::NamesApi<Block, Hash> for Runtime {
+        fn resolve(name: Vec<u8>) -> Option<Hash> {
+            Names::resolve(name)
+        }
+
+        fn names_of(user_key: Hash) -> Vec<Vec<u8>> {
+            Names::names_of(user_key)
+        }
+    }
+
+    impl credentials_rpc_runtime_api::CredentialsApi<Block, Hash, Moment> for Runtime {
+        fn status(credential: Hash) -> credentials::CredentialStatus {
+            Credentials::status(credential)
+        }
+
+        fn credentials_of(subject: Hash) -> Vec<credentials::CredentialInfo<Hash, Moment>> {
+            Credentials::credentials_of(subject)
+        }
+
+        fn status_list(issuer: Hash, list: u32) -> Vec<u8> {
+            Credentials::status_list(issuer, list)
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
         Block,
         Balance,
@@ -1225,11 +1382,14 @@ impl_runtime_apis! {
 
             add_benchmark!(params, batches, b"balances", Balances);
             add_benchmark!(params, batches, b"collective", Council);
+            add_benchmark!(params, batches, b"credentials", Credentials);
             add_benchmark!(params, batches, b"democracy", Democracy);
+            add_benchmark!(params, batches, b"did", Did);
             add_benchmark!(params, batches, b"elections", Elections);
             add_benchmark!(params, batches, b"identity", Identity);
             add_benchmark!(params, batches, b"im-online", ImOnline);
             add_benchmark!(params, batches, b"indices", Indices);
+            add_benchmark!(params, batches, b"names", Names);
             add_benchmark!(params, batches, b"offences", OffencesBench::<Runtime>);
             add_benchmark!(params, batches, b"session", SessionBench::<Runtime>);
             add_benchmark!(params, batches, b"staking", Staking);